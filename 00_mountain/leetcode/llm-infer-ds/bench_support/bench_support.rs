@@ -0,0 +1,122 @@
+// ==============================================================================
+// Benchmark 公用工具 - 只作为 dev-dependency 给各个 crate 的 benches 使用
+// ==============================================================================
+//
+// 【内容】
+//   1. CountingAlloc：包住 System 的计数分配器，统计 alloc / realloc 次数和申请的字节数
+//   2. Lcg：固定种子的线性同余序列，负载可复现
+//   3. best_of / best_of_counting：跑 rounds 轮取最好成绩（后者顺带统计每轮的分配）
+//
+// 【用法】
+//   #[global_allocator] 只能在最终的二进制里声明，所以每个 bench 自己写一行：
+//     #[global_allocator]
+//     static GLOBAL: CountingAlloc = CountingAlloc;
+//   没有声明时计数器始终是 0，其余工具照常可用
+// ==============================================================================
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// ==========================================
+// 计数分配器
+// ==========================================
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// 某一时刻的累计计数，两次快照相减得到一段代码的分配
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocCount {
+    pub allocs: usize,
+    pub reallocs: usize,
+    pub bytes: usize, // realloc 只算增长的部分
+}
+
+pub fn alloc_snapshot() -> AllocCount {
+    AllocCount {
+        allocs: ALLOCS.load(Ordering::Relaxed),
+        reallocs: REALLOCS.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+    }
+}
+
+pub fn alloc_since(before: AllocCount) -> AllocCount {
+    let now = alloc_snapshot();
+    AllocCount {
+        allocs: now.allocs - before.allocs,
+        reallocs: now.reallocs - before.reallocs,
+        bytes: now.bytes - before.bytes,
+    }
+}
+
+// ==========================================
+// 随机序列
+// ==========================================
+
+// Knuth MMIX 常数的线性同余序列
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    // 只取高 31 位：低位的周期太短
+    pub fn next_usize(&mut self) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize
+    }
+
+    // [0, n) 内的随机数
+    pub fn below(&mut self, n: usize) -> usize {
+        self.next_usize() % n
+    }
+}
+
+// ==========================================
+// 计时
+// ==========================================
+
+// 跑 rounds 轮，取最短的一次，减少调度抖动的影响
+pub fn best_of(rounds: usize, mut run: impl FnMut() -> Duration) -> Duration {
+    (0..rounds).map(|_| run()).min().expect("rounds must be positive")
+}
+
+// 同 best_of，另外返回每轮的平均分配次数（负载固定时每轮都一样）
+pub fn best_of_counting(rounds: usize, run: impl FnMut() -> Duration) -> (Duration, AllocCount) {
+    let before = alloc_snapshot();
+    let best = best_of(rounds, run);
+    let total = alloc_since(before);
+    let per_round = AllocCount {
+        allocs: total.allocs / rounds,
+        reallocs: total.reallocs / rounds,
+        bytes: total.bytes / rounds,
+    };
+    (best, per_round)
+}
+
+pub fn ns_per_op(elapsed: Duration, ops: usize) -> f64 {
+    elapsed.as_nanos() as f64 / ops as f64
+}
@@ -0,0 +1,111 @@
+// ==============================================================================
+// Benchmark: 各个 CPU kernel 的 GFLOP/s
+// ==============================================================================
+//
+// 运行：cargo bench -p cpu_kernels --bench gflops
+//
+// 三部分：
+//   1. vector_add：1 FLOP / 元素，同时给出 GB/s（读 a、b，写 c，共 12 字节 / 元素）
+//   2. 方阵 SGEMM（2·n³ FLOP）：naive / blocked / parallel，n = 128 ... 1024
+//      naive 在大尺寸上太慢，只跑到 NAIVE_MAX
+//   3. tile 扫描：固定 n = 512 的 parallel SGEMM，换不同的 tile 看哪个最快
+// 每项取 ROUNDS 轮中的最好成绩；每个 kernel 都先和 naive 核对一遍结果
+// ==============================================================================
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bench_support::best_of;
+use cpu_kernels::{
+    assert_close, default_threads, random_vector, sgemm_blocked, sgemm_naive, sgemm_parallel, vector_add_into, MatMut,
+    MatRef, Tiles, Tolerance,
+};
+
+const ROUNDS: usize = 5;
+const NAIVE_MAX: usize = 512;
+
+fn report(workload: &str, name: &str, flops: f64, elapsed: Duration, extra: &str) {
+    println!(
+        "{:<14} {:<26} {:>10.3} ms | {:>8.2} GFLOP/s {}",
+        workload,
+        name,
+        elapsed.as_secs_f64() * 1e3,
+        flops / elapsed.as_secs_f64() / 1e9,
+        extra,
+    );
+}
+
+fn bench_vector_add() {
+    for n in [1 << 16, 1 << 20, 1 << 24] {
+        let a = random_vector(1, n);
+        let b = random_vector(2, n);
+        let mut c = vec![0.0; n];
+        let elapsed = best_of(ROUNDS, || {
+            let start = Instant::now();
+            vector_add_into(black_box(&a), black_box(&b), &mut c).unwrap();
+            black_box(&c);
+            start.elapsed()
+        });
+        let gbps = (12 * n) as f64 / elapsed.as_secs_f64() / 1e9;
+        report(&format!("n={}", n), "vector_add", n as f64, elapsed, &format!("| {:>6.2} GB/s", gbps));
+    }
+}
+
+type Gemm<'k> = dyn Fn(MatRef<'_>, MatRef<'_>, &mut MatMut<'_>) + 'k;
+
+// 方阵 n × n，先和 naive 核对，再计时
+fn bench_gemm(workload: &str, name: &str, n: usize, gemm: &Gemm<'_>, expected: Option<&[f32]>) {
+    let a = random_vector(3, n * n);
+    let b = random_vector(4, n * n);
+    let (a, b) = (MatRef::new(&a, n, n).unwrap(), MatRef::new(&b, n, n).unwrap());
+    let mut c = vec![0.0; n * n];
+    let elapsed = best_of(ROUNDS, || {
+        let mut out = MatMut::new(&mut c, n, n).unwrap();
+        let start = Instant::now();
+        gemm(black_box(a), black_box(b), &mut out);
+        start.elapsed()
+    });
+    if let Some(expected) = expected {
+        assert_close(&c, expected, Tolerance::for_reduction(n));
+    }
+    report(workload, name, 2.0 * (n * n * n) as f64, elapsed, "");
+}
+
+fn naive_reference(n: usize) -> Vec<f32> {
+    let a = random_vector(3, n * n);
+    let b = random_vector(4, n * n);
+    let mut c = vec![0.0; n * n];
+    let (a, b) = (MatRef::new(&a, n, n).unwrap(), MatRef::new(&b, n, n).unwrap());
+    sgemm_naive(1.0, a, b, 0.0, &mut MatMut::new(&mut c, n, n).unwrap()).unwrap();
+    c
+}
+
+fn main() {
+    let threads = default_threads();
+    println!("threads = {}, default tiles = {:?}\n", threads, Tiles::default());
+
+    bench_vector_add();
+
+    let naive = |a: MatRef<'_>, b: MatRef<'_>, c: &mut MatMut<'_>| sgemm_naive(1.0, a, b, 0.0, c).unwrap();
+    let blocked = |a: MatRef<'_>, b: MatRef<'_>, c: &mut MatMut<'_>| sgemm_blocked(1.0, a, b, 0.0, c, Tiles::default()).unwrap();
+    let parallel =
+        |a: MatRef<'_>, b: MatRef<'_>, c: &mut MatMut<'_>| sgemm_parallel(1.0, a, b, 0.0, c, Tiles::default(), threads).unwrap();
+    for n in [128, 256, 512, 1024] {
+        let workload = format!("n={}", n);
+        // 大尺寸不跑 naive，也就没有参考结果，只计时
+        let expected = (n <= NAIVE_MAX).then(|| naive_reference(n));
+        if n <= NAIVE_MAX {
+            bench_gemm(&workload, "naive", n, &naive, None);
+        }
+        bench_gemm(&workload, "blocked", n, &blocked, expected.as_deref());
+        bench_gemm(&workload, &format!("parallel x{}", threads), n, &parallel, expected.as_deref());
+    }
+
+    let n = 512;
+    let expected = naive_reference(n);
+    for tiles in [Tiles::new(16, 64, 64), Tiles::new(32, 128, 128), Tiles::default(), Tiles::new(64, 256, 256), Tiles::new(128, 512, 512)] {
+        let gemm = move |a: MatRef<'_>, b: MatRef<'_>, c: &mut MatMut<'_>| sgemm_parallel(1.0, a, b, 0.0, c, tiles, threads).unwrap();
+        let name = format!("tiles {}x{}x{}", tiles.m, tiles.n, tiles.k);
+        bench_gemm("tile sweep", &name, n, &gemm, Some(&expected));
+    }
+}
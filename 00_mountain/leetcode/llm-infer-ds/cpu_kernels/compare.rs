@@ -0,0 +1,215 @@
+// ==============================================================================
+// 带容差的比较 - 拿 golden 输出对 kernel 结果
+// ==============================================================================
+//
+// 【为什么不能用 ==】
+//   浮点加法不满足结合律：naive matmul 按 k = 0, 1, 2, ... 顺序累加，
+//   分块 / 多线程 / CUDA 的 kernel 累加顺序都不一样，结果差几个 ulp 是正常的
+//
+// 【判定】（和 numpy.allclose 一样）
+//   |actual - expected| <= atol + rtol * |expected|
+//   NaN 只和 NaN 相等，inf 只和同号 inf 相等
+//
+// 【容差取多大】
+//   长度为 k 的点积，舍入误差大约随 k 线性增长（最坏情况），
+//   Tolerance::for_reduction(k) 按 k × f32::EPSILON 放宽，k 越大越宽
+// ==============================================================================
+
+use std::error::Error;
+use std::fmt;
+
+use vector::Tensor;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub atol: f32,
+    pub rtol: f32,
+}
+
+impl Tolerance {
+    // 逐元素的运算（vector_add 等），和 CUDA 结果应当只差舍入
+    pub const ELEMENTWISE: Tolerance = Tolerance { atol: 1e-6, rtol: 1e-6 };
+
+    pub const fn new(atol: f32, rtol: f32) -> Self {
+        Self { atol, rtol }
+    }
+
+    // 每个输出是 k 项累加的结果（matmul 的 k 维），输入在 [-1, 1] 量级时够用
+    pub fn for_reduction(k: usize) -> Self {
+        let eps = f32::EPSILON * (k.max(1) as f32) * 4.0;
+        Self { atol: eps.max(1e-6), rtol: eps.max(1e-6) }
+    }
+
+    pub fn is_close(&self, actual: f32, expected: f32) -> bool {
+        if actual.is_nan() || expected.is_nan() {
+            return actual.is_nan() && expected.is_nan();
+        }
+        if actual.is_infinite() || expected.is_infinite() {
+            return actual == expected;
+        }
+        (actual - expected).abs() <= self.atol + self.rtol * expected.abs()
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::new(1e-5, 1e-4)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareError {
+    LengthMismatch { actual: usize, expected: usize },
+    ShapeMismatch { actual: Vec<usize>, expected: Vec<usize> },
+    // 第一个超出容差的元素；mismatches 是超出容差的总个数
+    NotClose { index: usize, actual: f32, expected: f32, allowed: f32, mismatches: usize },
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareError::LengthMismatch { actual, expected } => {
+                write!(f, "length mismatch: got {} elements, expected {}", actual, expected)
+            }
+            CompareError::ShapeMismatch { actual, expected } => {
+                write!(f, "shape mismatch: got {:?}, expected {:?}", actual, expected)
+            }
+            CompareError::NotClose { index, actual, expected, allowed, mismatches } => write!(
+                f,
+                "{} element(s) differ; first at [{}]: got {}, expected {} (|diff| = {}, allowed {})",
+                mismatches,
+                index,
+                actual,
+                expected,
+                (actual - expected).abs(),
+                allowed
+            ),
+        }
+    }
+}
+
+impl Error for CompareError {}
+
+// 误差统计：打印 / 画图用
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiffStats {
+    pub max_abs: f32,
+    pub max_rel: f32,
+    pub max_abs_index: usize,
+}
+
+pub fn diff_stats(actual: &[f32], expected: &[f32]) -> DiffStats {
+    let mut stats = DiffStats::default();
+    for (index, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+        let abs = (a - e).abs();
+        if abs > stats.max_abs {
+            stats.max_abs = abs;
+            stats.max_abs_index = index;
+        }
+        if e != 0.0 {
+            stats.max_rel = stats.max_rel.max(abs / e.abs());
+        }
+    }
+    stats
+}
+
+pub fn check_close(actual: &[f32], expected: &[f32], tol: Tolerance) -> Result<(), CompareError> {
+    if actual.len() != expected.len() {
+        return Err(CompareError::LengthMismatch { actual: actual.len(), expected: expected.len() });
+    }
+    let mut first = None;
+    let mut mismatches = 0;
+    for (index, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+        if !tol.is_close(a, e) {
+            mismatches += 1;
+            first.get_or_insert((index, a, e));
+        }
+    }
+    match first {
+        None => Ok(()),
+        Some((index, actual, expected)) => Err(CompareError::NotClose {
+            index,
+            actual,
+            expected,
+            allowed: tol.atol + tol.rtol * expected.abs(),
+            mismatches,
+        }),
+    }
+}
+
+pub fn all_close(actual: &[f32], expected: &[f32], tol: Tolerance) -> bool {
+    check_close(actual, expected, tol).is_ok()
+}
+
+// 形状也要一致；按逻辑顺序比较，所以转置 / narrow 之后的视图也行
+pub fn check_close_tensor(actual: &Tensor<f32>, expected: &Tensor<f32>, tol: Tolerance) -> Result<(), CompareError> {
+    if actual.shape() != expected.shape() {
+        return Err(CompareError::ShapeMismatch { actual: actual.shape().to_vec(), expected: expected.shape().to_vec() });
+    }
+    let actual: Vec<f32> = actual.iter().copied().collect();
+    let expected: Vec<f32> = expected.iter().copied().collect();
+    check_close(&actual, &expected, tol)
+}
+
+// 测试里用：失败时 panic 并打印第一个不一致的位置
+#[track_caller]
+pub fn assert_close(actual: &[f32], expected: &[f32], tol: Tolerance) {
+    if let Err(err) = check_close(actual, expected, tol) {
+        panic!("assert_close failed: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_close, check_close_tensor, diff_stats, CompareError, Tolerance};
+    use vector::{Tensor, Vector};
+
+    #[test]
+    fn test_tolerance_rules() {
+        let tol = Tolerance::new(1e-3, 1e-2);
+        assert!(tol.is_close(1.0, 1.0));
+        assert!(tol.is_close(100.5, 100.0)); // 0.5 <= 0.001 + 1.0
+        assert!(!tol.is_close(0.01, 0.0));
+        assert!(tol.is_close(f32::NAN, f32::NAN));
+        assert!(!tol.is_close(f32::NAN, 0.0));
+        assert!(tol.is_close(f32::INFINITY, f32::INFINITY));
+        assert!(!tol.is_close(f32::INFINITY, f32::NEG_INFINITY));
+        assert!(!tol.is_close(f32::MAX, f32::INFINITY));
+
+        assert!(Tolerance::for_reduction(4096).rtol > Tolerance::for_reduction(64).rtol);
+    }
+
+    #[test]
+    fn test_check_close_reports_first_mismatch() {
+        let expected = [1.0, 2.0, 3.0, 4.0];
+        assert!(check_close(&[1.0, 2.0, 3.0, 4.0000005], &expected, Tolerance::default()).is_ok());
+
+        let err = check_close(&[1.0, 2.5, 3.0, 5.0], &expected, Tolerance::default()).unwrap_err();
+        let CompareError::NotClose { index, actual, mismatches, .. } = err else { panic!("{:?}", err) };
+        assert_eq!((index, actual, mismatches), (1, 2.5, 2));
+        assert!(err.to_string().contains("2 element(s) differ"), "{}", err);
+
+        assert_eq!(
+            check_close(&[1.0], &expected, Tolerance::default()),
+            Err(CompareError::LengthMismatch { actual: 1, expected: 4 })
+        );
+
+        let stats = diff_stats(&[1.0, 2.5, 3.0, 5.0], &expected);
+        assert_eq!(stats.max_abs, 1.0);
+        assert_eq!(stats.max_abs_index, 3);
+        assert_eq!(stats.max_rel, 0.25);
+    }
+
+    #[test]
+    fn test_check_close_tensor_uses_logical_order() {
+        let data: Vector<f32> = (0..6).map(|i| i as f32).collect();
+        let t = Tensor::from_vector(data, &[2, 3]).unwrap();
+        let transposed = t.transpose(0, 1).unwrap();
+        let expected = Tensor::from_vector([0.0, 3.0, 1.0, 4.0, 2.0, 5.0].into_iter().collect(), &[3, 2]).unwrap();
+        assert!(check_close_tensor(&transposed, &expected, Tolerance::ELEMENTWISE).is_ok());
+        assert_eq!(
+            check_close_tensor(&t, &expected, Tolerance::ELEMENTWISE),
+            Err(CompareError::ShapeMismatch { actual: vec![2, 3], expected: vec![3, 2] })
+        );
+    }
+}
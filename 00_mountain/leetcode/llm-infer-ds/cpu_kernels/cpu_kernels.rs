@@ -0,0 +1,50 @@
+// ==============================================================================
+// CPU 参考 kernel - 对应 00_mountain/cuda_drills 里的 CUDA 练习
+// ==============================================================================
+//
+// 【为什么需要】
+//   CI 机器没有 GPU，.cu 跑不了；这里用 Rust 在 CPU 上实现同样的 kernel，
+//   输出当作 golden：在有 GPU 的机器上拿 CUDA 的结果按容差对比
+//
+// 【kernel 对照】
+//   vector_add.rs  <-> 01_basic_moves/01_vector_add.cu
+//   matmul.rs      <-> 01_basic_moves/02_matrix_mul_naive.cu（三重循环，也是所有 GEMM 的标准答案）
+//   sgemm.rs       <-> 02_memory_magic/01_sgemm_cuda.cu（分块 + 多线程，tile 大小可调）
+//
+// 【学习重点】
+//   1. 视图：MatRef / MatMut 借用 Vector<f32>、Tensor、AlignedRows 的存储，不拷贝
+//   2. 分块：把 B 的一块搬成连续的（CPU 上的 "shared memory"），在 cache 里反复用
+//   3. 多线程：C 按行块切开，各线程写互不重叠的行，不需要加锁
+//   4. 浮点误差：累加顺序不同结果就不同，比较要用容差（compare.rs）
+//
+// 【用法】
+//   let c = matmul_parallel(&a, &b, Tiles::default(), default_threads())?;
+//   check_close_tensor(&c, &matmul_naive(&a, &b)?, Tolerance::for_reduction(k))?;
+//
+//   cargo run -p cpu_kernels --release -- golden/     生成 golden 文件
+//   cargo bench -p cpu_kernels --bench gflops         GFLOP/s
+// ==============================================================================
+
+// 二维视图 MatRef / MatMut 和 kernel 的错误类型
+pub mod matrix;
+pub use matrix::{KernelError, MatMut, MatRef};
+
+// 带容差的比较：Tolerance、check_close、assert_close
+pub mod compare;
+pub use compare::{all_close, assert_close, check_close, check_close_tensor, diff_stats, CompareError, DiffStats, Tolerance};
+
+// 固定种子的输入和 .f32 二进制文件读写
+pub mod golden;
+pub use golden::{random_vector, read_f32_file, write_f32_file};
+
+// c = a + b
+pub mod vector_add;
+pub use vector_add::{vector_add, vector_add_into};
+
+// 三重循环的 naive matmul
+pub mod matmul;
+pub use matmul::{matmul_naive, sgemm_naive};
+
+// 分块 SGEMM 和多线程 SGEMM
+pub mod sgemm;
+pub use sgemm::{default_threads, matmul_blocked, matmul_parallel, sgemm_blocked, sgemm_parallel, Tiles};
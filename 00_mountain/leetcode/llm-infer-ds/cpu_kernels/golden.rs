@@ -0,0 +1,88 @@
+// ==============================================================================
+// golden 文件 - 固定种子的输入 + 原始 f32 二进制读写
+// ==============================================================================
+//
+// 【流程】（CI 没有 GPU，所以分两步）
+//   1. 没有 GPU 的机器：cargo run -p cpu_kernels --release -- golden/
+//      生成 <kernel>.a.f32 / .b.f32（输入）和 <kernel>.out.f32（CPU 参考输出）
+//   2. 有 GPU 的机器：.cu 用 fread 读同样的输入，算完和 .out.f32 按容差比较
+//
+// 【文件格式】
+//   没有文件头，就是 n 个 little-endian f32 紧挨着，C 里一个 fread 就能读进 float 数组；
+//   形状由文件名约定（见 main.rs），不写进文件
+//
+// 【随机数】
+//   同一个 seed 在任何机器上都生成同样的序列（LCG，不依赖 rand crate），
+//   值在 [-1, 1) 之间，这样 matmul 的结果量级约为 sqrt(k)，容差好估计
+// ==============================================================================
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use vector::Vector;
+
+// Knuth 的 MMIX LCG，取高 24 位刚好是 f32 的尾数精度
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let bits = (self.0 >> 40) as u32; // [0, 2^24)
+        bits as f32 / (1u32 << 23) as f32 - 1.0
+    }
+}
+
+// len 个 [-1, 1) 之间的 f32，同一个 seed 结果固定
+pub fn random_vector(seed: u64, len: usize) -> Vector<f32> {
+    let mut rng = Lcg(seed ^ 0x9E37_79B9_7F4A_7C15);
+    (0..len).map(|_| rng.next_f32()).collect()
+}
+
+pub fn write_f32_file(path: impl AsRef<Path>, values: &[f32]) -> io::Result<()> {
+    let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+    fs::write(path, bytes)
+}
+
+pub fn read_f32_file(path: impl AsRef<Path>) -> io::Result<Vector<f32>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() % 4 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} bytes is not a whole number of f32", bytes.len()),
+        ));
+    }
+    Ok(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_vector, read_f32_file, write_f32_file};
+
+    #[test]
+    fn test_random_vector_is_deterministic_and_bounded() {
+        let a = random_vector(42, 10_000);
+        assert_eq!(a, random_vector(42, 10_000));
+        assert_ne!(a, random_vector(43, 10_000));
+        assert!(a.iter().all(|&x| (-1.0..1.0).contains(&x)));
+        // 均值应当接近 0
+        let mean = a.iter().sum::<f32>() / a.len() as f32;
+        assert!(mean.abs() < 0.05, "mean {}", mean);
+    }
+
+    #[test]
+    fn test_f32_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cpu_kernels_golden_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("x.f32");
+
+        let values = [1.5, -0.0, f32::MAX, f32::MIN_POSITIVE, f32::INFINITY];
+        write_f32_file(&path, &values).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 20);
+        assert_eq!(read_f32_file(&path).unwrap(), values);
+
+        std::fs::write(&path, [0u8; 6]).unwrap();
+        assert_eq!(read_f32_file(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+// ==============================================================================
+// 生成 golden 文件 - cargo run -p cpu_kernels --release -- [输出目录]
+// ==============================================================================
+//
+// 每个 kernel 一组文件（格式见 golden.rs），形状写在文件名里：
+//   vector_add_n{N}.a.f32 / .b.f32 / .out.f32                    out = a + b
+//   matmul_naive_m{M}_n{N}_k{K}.a.f32 / .b.f32 / .out.f32         out = A · B
+//   sgemm_m{M}_n{N}_k{K}.a.f32 / .b.f32 / .c.f32 / .out.f32       out = ALPHA · A · B + BETA · C
+// 矩阵都是行主序。写文件之前先确认分块 / 多线程版本和 naive 在容差内一致
+// ==============================================================================
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use cpu_kernels::{
+    check_close, default_threads, random_vector, sgemm_naive, sgemm_parallel, vector_add, write_f32_file, MatMut, MatRef,
+    Tiles, Tolerance,
+};
+
+const VECTOR_LEN: usize = 1 << 20;
+const NAIVE_SHAPE: (usize, usize, usize) = (256, 192, 128);
+const SGEMM_SHAPE: (usize, usize, usize) = (512, 512, 512);
+const ALPHA: f32 = 0.5;
+const BETA: f32 = 0.25;
+
+fn write(dir: &Path, name: &str, values: &[f32]) -> Result<(), Box<dyn Error>> {
+    let path = dir.join(name);
+    write_f32_file(&path, values)?;
+    println!("  {:<44} {:>9} floats", path.display(), values.len());
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let dir: PathBuf = std::env::args().nth(1).unwrap_or_else(|| "golden".to_string()).into();
+    std::fs::create_dir_all(&dir)?;
+    println!("=== 生成 golden 文件 -> {} ===", dir.display());
+
+    // 【第一步：vector_add】
+    let a = random_vector(1, VECTOR_LEN);
+    let b = random_vector(2, VECTOR_LEN);
+    let out = vector_add(&a, &b)?;
+    let prefix = format!("vector_add_n{}", VECTOR_LEN);
+    write(&dir, &format!("{}.a.f32", prefix), &a)?;
+    write(&dir, &format!("{}.b.f32", prefix), &b)?;
+    write(&dir, &format!("{}.out.f32", prefix), &out)?;
+
+    // 【第二步：naive matmul】
+    let (m, n, k) = NAIVE_SHAPE;
+    let a = random_vector(3, m * k);
+    let b = random_vector(4, k * n);
+    let mut out = vec![0.0; m * n];
+    sgemm_naive(1.0, MatRef::new(&a, m, k)?, MatRef::new(&b, k, n)?, 0.0, &mut MatMut::new(&mut out, m, n)?)?;
+    let prefix = format!("matmul_naive_m{}_n{}_k{}", m, n, k);
+    write(&dir, &format!("{}.a.f32", prefix), &a)?;
+    write(&dir, &format!("{}.b.f32", prefix), &b)?;
+    write(&dir, &format!("{}.out.f32", prefix), &out)?;
+
+    // 【第三步：SGEMM】参考输出用 naive 算，再用多线程版本核对一遍
+    let (m, n, k) = SGEMM_SHAPE;
+    let a = random_vector(5, m * k);
+    let b = random_vector(6, k * n);
+    let c = random_vector(7, m * n);
+    let (a_ref, b_ref) = (MatRef::new(&a, m, k)?, MatRef::new(&b, k, n)?);
+    let mut expected = c.to_vec();
+    sgemm_naive(ALPHA, a_ref, b_ref, BETA, &mut MatMut::new(&mut expected, m, n)?)?;
+    let mut parallel = c.to_vec();
+    sgemm_parallel(ALPHA, a_ref, b_ref, BETA, &mut MatMut::new(&mut parallel, m, n)?, Tiles::default(), default_threads())?;
+    check_close(&parallel, &expected, Tolerance::for_reduction(k))?;
+    let prefix = format!("sgemm_m{}_n{}_k{}", m, n, k);
+    write(&dir, &format!("{}.a.f32", prefix), &a)?;
+    write(&dir, &format!("{}.b.f32", prefix), &b)?;
+    write(&dir, &format!("{}.c.f32", prefix), &c)?;
+    write(&dir, &format!("{}.out.f32", prefix), &expected)?;
+    println!("sgemm: alpha = {}, beta = {}；建议容差 {:?}", ALPHA, BETA, Tolerance::for_reduction(k));
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// ==============================================================================
+// naive matmul - 对应 cuda_drills/01_basic_moves/02_matrix_mul_naive.cu
+// ==============================================================================
+//
+// 【CUDA 版本】
+//   每个线程算 C 的一个元素：row = blockIdx.y * blockDim.y + threadIdx.y, col = ...x
+//   for k in 0..K: sum += A[row][k] * B[k][col]
+//
+// 【CPU 版本】
+//   同样的三重循环、同样的累加顺序（k 从 0 到 K-1），当作所有 GEMM kernel 的标准答案
+//   C = alpha * A · B + beta * C（cuBLAS / siboehm SGEMM 的约定）
+//   beta == 0 时不读 C：C 里原来是 NaN 也不会传染到结果
+// ==============================================================================
+
+use vector::{Tensor, Vector};
+
+use crate::{KernelError, MatMut, MatRef};
+
+// A [m, k] · B [k, n] -> C [m, n]，三个维度都要对上
+pub(crate) fn check_gemm_shapes(a: &MatRef<'_>, b: &MatRef<'_>, c: &MatMut<'_>) -> Result<(), KernelError> {
+    if b.rows() != a.cols() {
+        return Err(KernelError::ShapeMismatch { what: "rows of B (inner dimension)", expected: a.cols(), actual: b.rows() });
+    }
+    if c.rows() != a.rows() {
+        return Err(KernelError::ShapeMismatch { what: "rows of C", expected: a.rows(), actual: c.rows() });
+    }
+    if c.cols() != b.cols() {
+        return Err(KernelError::ShapeMismatch { what: "cols of C", expected: b.cols(), actual: c.cols() });
+    }
+    Ok(())
+}
+
+// c = alpha * dot + beta * c，beta == 0 时忽略 c 原来的值
+#[inline]
+fn axpby(alpha: f32, dot: f32, beta: f32, c: f32) -> f32 {
+    if beta == 0.0 {
+        alpha * dot
+    } else {
+        alpha * dot + beta * c
+    }
+}
+
+pub fn sgemm_naive(alpha: f32, a: MatRef<'_>, b: MatRef<'_>, beta: f32, c: &mut MatMut<'_>) -> Result<(), KernelError> {
+    check_gemm_shapes(&a, &b, c)?;
+    for i in 0..a.rows() {
+        for j in 0..b.cols() {
+            let mut sum = 0.0;
+            for p in 0..a.cols() {
+                sum += a.get(i, p) * b.get(p, j);
+            }
+            let out = &mut c.row_mut(i)[j];
+            *out = axpby(alpha, sum, beta, *out);
+        }
+    }
+    Ok(())
+}
+
+// 两个二维 Tensor 相乘，结果写进新的连续 Tensor [m, n]；gemm 是具体用哪个 kernel
+pub(crate) fn tensor_matmul<F>(a: &Tensor<f32>, b: &Tensor<f32>, gemm: F) -> Result<Tensor<f32>, KernelError>
+where
+    F: FnOnce(MatRef<'_>, MatRef<'_>, &mut MatMut<'_>) -> Result<(), KernelError>,
+{
+    let (a, b) = (MatRef::from_tensor(a)?, MatRef::from_tensor(b)?);
+    let (m, n) = (a.rows(), b.cols());
+    let mut out: Vector<f32> = std::iter::repeat_n(0.0, m * n).collect();
+    gemm(a, b, &mut MatMut::new(&mut out, m, n)?)?;
+    Ok(Tensor::from_vector(out, &[m, n]).expect("m * n elements"))
+}
+
+pub fn matmul_naive(a: &Tensor<f32>, b: &Tensor<f32>) -> Result<Tensor<f32>, KernelError> {
+    tensor_matmul(a, b, |a, b, c| sgemm_naive(1.0, a, b, 0.0, c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matmul_naive, sgemm_naive};
+    use crate::{KernelError, MatMut, MatRef};
+    use vector::{Tensor, Vector};
+
+    fn tensor(values: &[f32], shape: &[usize]) -> Tensor<f32> {
+        Tensor::from_vector(values.iter().copied().collect::<Vector<f32>>(), shape).unwrap()
+    }
+
+    #[test]
+    fn test_matmul_small_example() {
+        // [1 2 3]   [ 7  8]   [ 58  64]
+        // [4 5 6] · [ 9 10] = [139 154]
+        //           [11 12]
+        let a = tensor(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+        let b = tensor(&[7.0, 8.0, 9.0, 10.0, 11.0, 12.0], &[3, 2]);
+        let c = matmul_naive(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[2, 2]);
+        assert_eq!(c.to_vector(), [58.0, 64.0, 139.0, 154.0]);
+
+        // 转置视图当输入：(Bᵀ)ᵀ = B
+        let bt = tensor(&[7.0, 9.0, 11.0, 8.0, 10.0, 12.0], &[2, 3]);
+        let c2 = matmul_naive(&a, &bt.transpose(0, 1).unwrap()).unwrap();
+        assert_eq!(c2.to_vector(), c.to_vector());
+
+        assert_eq!(
+            matmul_naive(&a, &a).unwrap_err(),
+            KernelError::ShapeMismatch { what: "rows of B (inner dimension)", expected: 3, actual: 2 }
+        );
+    }
+
+    #[test]
+    fn test_alpha_beta() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        let eye = [1.0, 0.0, 0.0, 1.0];
+        let mut c = [1.0, 1.0, 1.0, 1.0];
+        let mut out = MatMut::new(&mut c, 2, 2).unwrap();
+        sgemm_naive(2.0, MatRef::new(&a, 2, 2).unwrap(), MatRef::new(&eye, 2, 2).unwrap(), 0.5, &mut out).unwrap();
+        assert_eq!(c, [2.5, 4.5, 6.5, 8.5]);
+
+        // beta == 0：C 里的 NaN 不影响结果
+        let mut c = [f32::NAN; 4];
+        let mut out = MatMut::new(&mut c, 2, 2).unwrap();
+        sgemm_naive(1.0, MatRef::new(&a, 2, 2).unwrap(), MatRef::new(&eye, 2, 2).unwrap(), 0.0, &mut out).unwrap();
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn test_empty_inner_dimension() {
+        // k = 0：A · B 是全 0
+        let a: Tensor<f32> = Tensor::zeros(&[3, 0]);
+        let b: Tensor<f32> = Tensor::zeros(&[0, 2]);
+        let c = matmul_naive(&a, &b).unwrap();
+        assert_eq!(c.shape(), &[3, 2]);
+        assert!(c.iter().all(|&x| x == 0.0));
+    }
+}
@@ -0,0 +1,316 @@
+// ==============================================================================
+// 矩阵视图 - kernel 的输入 MatRef / 输出 MatMut
+// ==============================================================================
+//
+// 【为什么不直接用 Tensor】
+//   kernel 只关心二维、f32、"第 (i, j) 个元素在哪"，不需要 Arc / 写时复制；
+//   借用一段 &[f32] 就够了，这样 Vector<f32>、Tensor 视图、AlignedRows 都能零拷贝地喂进来
+//
+// 【MatRef】只读，任意 strides（转置后的 Tensor 也行）
+//   元素 (i, j) = data[offset + i * row_stride + j * col_stride]
+//
+// 【MatMut】可写，列方向必须连续（col_stride = 1），行之间可以有补齐（row_stride >= cols）
+//   这样 C 的一行就是一个 &mut [f32]，可以按行块切开分给不同线程
+// ==============================================================================
+
+use std::error::Error;
+use std::fmt;
+
+use vector::{AlignedRows, Tensor};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KernelError {
+    // 维度对不上，例如 A 的列数 != B 的行数
+    ShapeMismatch { what: &'static str, expected: usize, actual: usize },
+    // 视图会读 / 写到 data 之外
+    OutOfBounds { required: usize, len: usize },
+    // 只接受二维张量
+    NotAMatrix { ndim: usize },
+    // MatMut 要求 row_stride >= cols（行之间不能重叠）
+    OverlappingRows { row_stride: usize, cols: usize },
+}
+
+impl fmt::Display for KernelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KernelError::ShapeMismatch { what, expected, actual } => {
+                write!(f, "{} mismatch: expected {}, got {}", what, expected, actual)
+            }
+            KernelError::OutOfBounds { required, len } => {
+                write!(f, "view needs {} elements but the buffer has {}", required, len)
+            }
+            KernelError::NotAMatrix { ndim } => write!(f, "expected a 2-d tensor, got {}-d", ndim),
+            KernelError::OverlappingRows { row_stride, cols } => {
+                write!(f, "row stride {} is smaller than {} columns", row_stride, cols)
+            }
+        }
+    }
+}
+
+impl Error for KernelError {}
+
+// 紧密排列时 data.len() 必须等于 rows * cols；乘法溢出时 expected 记为 usize::MAX
+fn check_element_count(len: usize, rows: usize, cols: usize) -> Result<(), KernelError> {
+    match rows.checked_mul(cols) {
+        Some(expected) if expected == len => Ok(()),
+        expected => Err(KernelError::ShapeMismatch { what: "element count", expected: expected.unwrap_or(usize::MAX), actual: len }),
+    }
+}
+
+// 视图覆盖的最后一个元素 + 1，必须 <= len；任何一步溢出都记为 usize::MAX
+fn check_bounds(len: usize, offset: usize, rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Result<(), KernelError> {
+    if rows == 0 || cols == 0 {
+        return Ok(());
+    }
+    let required = (rows - 1)
+        .checked_mul(row_stride)
+        .and_then(|r| (cols - 1).checked_mul(col_stride).and_then(|c| r.checked_add(c)))
+        .and_then(|last| last.checked_add(offset))
+        .and_then(|last| last.checked_add(1))
+        .unwrap_or(usize::MAX);
+    if required > len {
+        return Err(KernelError::OutOfBounds { required, len });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatRef<'a> {
+    data: &'a [f32],
+    offset: usize,
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl<'a> MatRef<'a> {
+    // 行主序、紧密排列的 rows × cols
+    pub fn new(data: &'a [f32], rows: usize, cols: usize) -> Result<Self, KernelError> {
+        check_element_count(data.len(), rows, cols)?;
+        Self::with_strides(data, 0, rows, cols, cols, 1)
+    }
+
+    pub fn with_strides(
+        data: &'a [f32],
+        offset: usize,
+        rows: usize,
+        cols: usize,
+        row_stride: usize,
+        col_stride: usize,
+    ) -> Result<Self, KernelError> {
+        check_bounds(data.len(), offset, rows, cols, row_stride, col_stride)?;
+        Ok(Self { data, offset, rows, cols, row_stride, col_stride })
+    }
+
+    // 二维 Tensor 视图（可以是转置 / narrow 之后的），直接借用它的 storage
+    pub fn from_tensor(tensor: &'a Tensor<f32>) -> Result<Self, KernelError> {
+        let &[rows, cols] = tensor.shape() else {
+            return Err(KernelError::NotAMatrix { ndim: tensor.ndim() });
+        };
+        let strides = tensor.strides();
+        Self::with_strides(tensor.storage(), tensor.offset(), rows, cols, strides[0], strides[1])
+    }
+
+    // 每行补齐到对齐边界的矩阵：跳过补齐部分
+    pub fn from_aligned(matrix: &'a AlignedRows<f32>) -> Self {
+        Self {
+            data: matrix.as_padded_slice(),
+            offset: 0,
+            rows: matrix.rows(),
+            cols: matrix.cols(),
+            row_stride: matrix.row_stride(),
+            col_stride: 1,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        debug_assert!(i < self.rows && j < self.cols, "({}, {}) out of {} x {}", i, j, self.rows, self.cols);
+        self.data[self.offset + i * self.row_stride + j * self.col_stride]
+    }
+
+    // 转置视图：交换 rows / cols 和两个 stride，不拷贝
+    pub fn t(&self) -> MatRef<'a> {
+        MatRef { rows: self.cols, cols: self.rows, row_stride: self.col_stride, col_stride: self.row_stride, ..*self }
+    }
+
+    // 第 start .. start + len 行
+    pub(crate) fn row_block(&self, start: usize, len: usize) -> MatRef<'a> {
+        debug_assert!(start + len <= self.rows);
+        MatRef { offset: self.offset + start * self.row_stride, rows: len, ..*self }
+    }
+
+    // 行内连续时返回第 i 行的 [start, start + len) 段，kernel 用它走快速路径
+    #[inline]
+    pub(crate) fn row_segment(&self, i: usize, start: usize, len: usize) -> Option<&'a [f32]> {
+        if self.col_stride != 1 {
+            return None;
+        }
+        let begin = self.offset + i * self.row_stride + start;
+        Some(&self.data[begin..begin + len])
+    }
+
+    // 按行主序拷贝出来（对比结果 / 写 golden 文件用）
+    pub fn to_vec(&self) -> Vec<f32> {
+        (0..self.rows).flat_map(|i| (0..self.cols).map(move |j| self.get(i, j))).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct MatMut<'a> {
+    data: &'a mut [f32],
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+}
+
+impl<'a> MatMut<'a> {
+    // 行主序、紧密排列的 rows × cols（通常是 &mut Vector<f32>）
+    pub fn new(data: &'a mut [f32], rows: usize, cols: usize) -> Result<Self, KernelError> {
+        check_element_count(data.len(), rows, cols)?;
+        Self::with_row_stride(data, rows, cols, cols)
+    }
+
+    pub fn with_row_stride(data: &'a mut [f32], rows: usize, cols: usize, row_stride: usize) -> Result<Self, KernelError> {
+        if rows > 1 && row_stride < cols {
+            return Err(KernelError::OverlappingRows { row_stride, cols });
+        }
+        check_bounds(data.len(), 0, rows, cols, row_stride, 1)?;
+        Ok(Self { data, rows, cols, row_stride })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        self.row(i)[j]
+    }
+
+    pub fn row(&self, i: usize) -> &[f32] {
+        assert!(i < self.rows, "row {} out of range ({} rows)", i, self.rows);
+        let start = i * self.row_stride;
+        &self.data[start..start + self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [f32] {
+        assert!(i < self.rows, "row {} out of range ({} rows)", i, self.rows);
+        let start = i * self.row_stride;
+        &mut self.data[start..start + self.cols]
+    }
+
+    pub fn as_ref(&self) -> MatRef<'_> {
+        MatRef { data: self.data, offset: 0, rows: self.rows, cols: self.cols, row_stride: self.row_stride, col_stride: 1 }
+    }
+
+    // 借出一个生命周期更短的 MatMut（切块之后原来的 MatMut 还能继续用）
+    pub(crate) fn reborrow(&mut self) -> MatMut<'_> {
+        MatMut { data: self.data, rows: self.rows, cols: self.cols, row_stride: self.row_stride }
+    }
+
+    // 按 block_rows 行一块切开，各块互不重叠，可以交给不同线程写
+    pub(crate) fn into_row_blocks(self, block_rows: usize) -> Vec<(usize, MatMut<'a>)> {
+        let MatMut { mut data, rows, cols, row_stride } = self;
+        let mut blocks = Vec::new();
+        let mut start = 0;
+        while start < rows {
+            let len = block_rows.min(rows - start);
+            // 最后一块只取到它自己的最后一个元素，data 可能不含最后一行的补齐
+            let take = if start + len == rows { data.len() } else { len * row_stride };
+            let (head, tail) = std::mem::take(&mut data).split_at_mut(take);
+            blocks.push((start, MatMut { data: head, rows: len, cols, row_stride }));
+            data = tail;
+            start += len;
+        }
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KernelError, MatMut, MatRef};
+    use vector::{AlignedRows, Tensor, Vector};
+
+    #[test]
+    fn test_views_from_tensor_and_aligned_rows() {
+        let data: Vector<f32> = (0..6).map(|i| i as f32).collect();
+        let t = Tensor::from_vector(data, &[2, 3]).unwrap();
+        let a = MatRef::from_tensor(&t).unwrap();
+        assert_eq!((a.rows(), a.cols()), (2, 3));
+        assert_eq!(a.get(1, 2), 5.0);
+
+        // 转置后的 Tensor 不连续，照样能当输入
+        let tt = t.transpose(0, 1).unwrap();
+        let at = MatRef::from_tensor(&tt).unwrap();
+        assert_eq!(at.to_vec(), a.t().to_vec());
+        assert_eq!(at.to_vec(), [0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+
+        let narrowed = t.narrow(1, 1, 2).unwrap();
+        assert_eq!(MatRef::from_tensor(&narrowed).unwrap().to_vec(), [1.0, 2.0, 4.0, 5.0]);
+
+        let padded = AlignedRows::from_rows(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3, 64);
+        assert_eq!(MatRef::from_aligned(&padded).to_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let cube: Tensor<f32> = Tensor::zeros(&[2, 2, 2]);
+        assert_eq!(MatRef::from_tensor(&cube).unwrap_err(), KernelError::NotAMatrix { ndim: 3 });
+    }
+
+    #[test]
+    fn test_bounds_are_checked() {
+        let data = [0.0f32; 10];
+        assert!(MatRef::with_strides(&data, 1, 3, 3, 3, 1).is_ok());
+        assert_eq!(
+            MatRef::with_strides(&data, 2, 3, 3, 3, 1).unwrap_err(),
+            KernelError::OutOfBounds { required: 11, len: 10 }
+        );
+        assert!(MatRef::new(&data, 3, 3).is_err());
+        assert!(MatRef::with_strides(&data, 0, 0, 100, 100, 1).is_ok());
+
+        // 乘法 / 加法溢出不能回绕成一个能通过检查的小数字
+        let overflow = KernelError::ShapeMismatch { what: "element count", expected: usize::MAX, actual: 10 };
+        assert_eq!(MatRef::new(&data, usize::MAX, 2).unwrap_err(), overflow);
+        assert_eq!(
+            MatRef::with_strides(&data, usize::MAX, 1, 1, 1, 1).unwrap_err(),
+            KernelError::OutOfBounds { required: usize::MAX, len: 10 }
+        );
+        assert!(MatRef::with_strides(&data, 0, 2, 1, usize::MAX, 1).is_err());
+
+        let mut out = [0.0f32; 10];
+        assert_eq!(
+            MatMut::with_row_stride(&mut out, 2, 4, 3).unwrap_err(),
+            KernelError::OverlappingRows { row_stride: 3, cols: 4 }
+        );
+        assert_eq!(MatMut::new(&mut out, 2, usize::MAX / 2 + 1).unwrap_err(), overflow);
+    }
+
+    #[test]
+    fn test_row_blocks_cover_every_row() {
+        // 行 stride 5、3 列，最后一行不带补齐：5 * 6 + 3 = 33 个元素
+        let mut data = vec![0.0f32; 33];
+        let c = MatMut::with_row_stride(&mut data, 7, 3, 5).unwrap();
+        let blocks = c.into_row_blocks(3);
+        assert_eq!(blocks.iter().map(|(start, b)| (*start, b.rows())).collect::<Vec<_>>(), [(0, 3), (3, 3), (6, 1)]);
+        for (start, mut block) in blocks {
+            for i in 0..block.rows() {
+                block.row_mut(i).fill((start + i) as f32);
+            }
+        }
+        let c = MatMut::with_row_stride(&mut data, 7, 3, 5).unwrap();
+        for i in 0..7 {
+            assert_eq!(c.row(i), &[i as f32; 3]);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+// ==============================================================================
+// SGEMM - 对应 cuda_drills/02_memory_magic/01_sgemm_cuda.cu
+// ==============================================================================
+//
+// 【CUDA 版本的思路】（siboehm/SGEMM_CUDA）
+//   naive 每算一个 C[i][j] 都要从全局内存读一整行 A 和一整列 B；
+//   分块后一个 block 把 A / B 的一个 tile 搬进 shared memory，tile 内反复复用
+//
+// 【CPU 版本】同一个思路，shared memory 换成 L1 / L2 cache
+//   for j0 in 0..n step tile.n          C / B 的列块
+//     for p0 in 0..k step tile.k        k 维的块
+//       pack B[p0.., j0..] 成连续、按缓存行对齐的 [kb, nb]（和搬进 shared memory 一样）
+//       for i0 in 0..m step tile.m      C / A 的行块
+//         for i, p:  C[i, j0..j0+nb] += alpha * A[i, p] * packed[p, ..]
+//   最内层是两段连续内存的 axpy，编译器可以向量化；packed B 在整个 i 循环里留在 cache
+//
+// 【多线程】
+//   C 按 tile.m 行切成互不重叠的行块，放进一个队列，threads 个线程轮流取；
+//   每个线程有自己的 packed 缓冲区。每一行的累加顺序和单线程分块版完全一样，
+//   所以 sgemm_parallel 和 sgemm_blocked 的结果逐位相同（与线程数无关）
+//
+// 【tile 怎么选】
+//   packed B 是 tile.k × tile.n 个 f32，默认 256 × 128 × 4 B = 128 KB，大约放得进 L2；
+//   调参时用 benches/gflops.rs 扫一遍
+// ==============================================================================
+
+use std::sync::Mutex;
+
+use vector::aligned::CACHE_LINE;
+use vector::{Tensor, Vector};
+
+use crate::matmul::{check_gemm_shapes, tensor_matmul};
+use crate::{KernelError, MatMut, MatRef};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tiles {
+    pub m: usize, // C / A 的行块
+    pub n: usize, // C / B 的列块
+    pub k: usize, // 内积维的块
+}
+
+impl Tiles {
+    pub const fn new(m: usize, n: usize, k: usize) -> Self {
+        Self { m, n, k }
+    }
+
+    fn assert_valid(&self) {
+        assert!(self.m > 0 && self.n > 0 && self.k > 0, "tile sizes must be non-zero: {:?}", self);
+    }
+}
+
+impl Default for Tiles {
+    fn default() -> Self {
+        Self::new(64, 128, 256)
+    }
+}
+
+// 机器的逻辑核数，拿不到时用 1
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// C = beta * C（beta == 0 时直接清零，不读原值）
+fn scale(c: &mut MatMut<'_>, beta: f32) {
+    for i in 0..c.rows() {
+        let row = c.row_mut(i);
+        if beta == 0.0 {
+            row.fill(0.0);
+        } else if beta != 1.0 {
+            row.iter_mut().for_each(|x| *x *= beta);
+        }
+    }
+}
+
+fn packed_buffer(tiles: Tiles) -> Vector<f32> {
+    let len = tiles.k.checked_mul(tiles.n).expect("tile too large");
+    let mut packed = Vector::with_alignment(len, CACHE_LINE);
+    packed.extend(std::iter::repeat_n(0.0, len));
+    packed
+}
+
+// B[p0 .. p0 + kb, j0 .. j0 + nb] -> packed[0 .. kb * nb]，行主序
+fn pack_b(b: &MatRef<'_>, p0: usize, kb: usize, j0: usize, nb: usize, packed: &mut [f32]) {
+    for (p, dst) in packed[..kb * nb].chunks_exact_mut(nb).enumerate() {
+        match b.row_segment(p0 + p, j0, nb) {
+            Some(src) => dst.copy_from_slice(src),
+            None => dst.iter_mut().enumerate().for_each(|(j, x)| *x = b.get(p0 + p, j0 + j)),
+        }
+    }
+}
+
+// C += alpha * A · B（C 已经乘过 beta）
+fn blocked_kernel(alpha: f32, a: &MatRef<'_>, b: &MatRef<'_>, c: &mut MatMut<'_>, tiles: Tiles, packed: &mut [f32]) {
+    let (m, n, k) = (a.rows(), b.cols(), a.cols());
+    for j0 in (0..n).step_by(tiles.n) {
+        let nb = tiles.n.min(n - j0);
+        for p0 in (0..k).step_by(tiles.k) {
+            let kb = tiles.k.min(k - p0);
+            // 1. 把 B 的这一块搬成连续的
+            pack_b(b, p0, kb, j0, nb, packed);
+            // 2. A 的每一行和 packed B 相乘，累加到 C 的对应行段
+            for i0 in (0..m).step_by(tiles.m) {
+                for i in i0..m.min(i0 + tiles.m) {
+                    let c_row = &mut c.row_mut(i)[j0..j0 + nb];
+                    for (p, b_row) in packed[..kb * nb].chunks_exact(nb).enumerate() {
+                        let a_ip = alpha * a.get(i, p0 + p);
+                        for (x, &y) in c_row.iter_mut().zip(b_row) {
+                            *x += a_ip * y;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn sgemm_blocked(alpha: f32, a: MatRef<'_>, b: MatRef<'_>, beta: f32, c: &mut MatMut<'_>, tiles: Tiles) -> Result<(), KernelError> {
+    check_gemm_shapes(&a, &b, c)?;
+    tiles.assert_valid();
+    scale(c, beta);
+    let mut packed = packed_buffer(tiles);
+    blocked_kernel(alpha, &a, &b, c, tiles, &mut packed);
+    Ok(())
+}
+
+pub fn sgemm_parallel(
+    alpha: f32,
+    a: MatRef<'_>,
+    b: MatRef<'_>,
+    beta: f32,
+    c: &mut MatMut<'_>,
+    tiles: Tiles,
+    threads: usize,
+) -> Result<(), KernelError> {
+    check_gemm_shapes(&a, &b, c)?;
+    tiles.assert_valid();
+    assert!(threads > 0, "need at least one thread");
+
+    // 1. C 切成行块，放进队列（倒序，pop 时从第 0 块开始）
+    let mut blocks = c.reborrow().into_row_blocks(tiles.m);
+    blocks.reverse();
+    let threads = threads.min(blocks.len());
+    let queue = Mutex::new(blocks);
+
+    // 2. 每个线程反复取一块：先乘 beta，再用自己的 packed 缓冲区算
+    let worker = || {
+        let mut packed = packed_buffer(tiles);
+        loop {
+            let Some((start, mut block)) = queue.lock().unwrap().pop() else { break };
+            scale(&mut block, beta);
+            blocked_kernel(alpha, &a.row_block(start, block.rows()), &b, &mut block, tiles, &mut packed);
+        }
+    };
+    if threads <= 1 {
+        worker();
+    } else {
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(worker);
+            }
+        });
+    }
+    Ok(())
+}
+
+pub fn matmul_blocked(a: &Tensor<f32>, b: &Tensor<f32>, tiles: Tiles) -> Result<Tensor<f32>, KernelError> {
+    tensor_matmul(a, b, |a, b, c| sgemm_blocked(1.0, a, b, 0.0, c, tiles))
+}
+
+pub fn matmul_parallel(a: &Tensor<f32>, b: &Tensor<f32>, tiles: Tiles, threads: usize) -> Result<Tensor<f32>, KernelError> {
+    tensor_matmul(a, b, |a, b, c| sgemm_parallel(1.0, a, b, 0.0, c, tiles, threads))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matmul_blocked, matmul_parallel, sgemm_blocked, sgemm_parallel, Tiles};
+    use crate::{assert_close, random_vector, sgemm_naive, MatMut, MatRef, Tolerance};
+    use vector::{AlignedRows, Tensor};
+
+    const ODD_TILES: Tiles = Tiles::new(5, 7, 3);
+
+    #[test]
+    fn test_blocked_matches_naive_on_ragged_shapes() {
+        // 维度故意不是 tile 的整数倍
+        for (m, n, k) in [(1, 1, 1), (17, 23, 31), (64, 130, 257), (3, 200, 1)] {
+            let a = random_vector(1, m * k);
+            let b = random_vector(2, k * n);
+            let c0 = random_vector(3, m * n);
+            let (a, b) = (MatRef::new(&a, m, k).unwrap(), MatRef::new(&b, k, n).unwrap());
+            for tiles in [Tiles::default(), ODD_TILES, Tiles::new(1, 1, 1)] {
+                let mut expected = c0.clone();
+                sgemm_naive(0.75, a, b, -0.5, &mut MatMut::new(&mut expected, m, n).unwrap()).unwrap();
+                let mut actual = c0.clone();
+                sgemm_blocked(0.75, a, b, -0.5, &mut MatMut::new(&mut actual, m, n).unwrap(), tiles).unwrap();
+                assert_close(&actual, &expected, Tolerance::for_reduction(k));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_is_bitwise_equal_to_blocked() {
+        let (m, n, k) = (97, 61, 45);
+        let a = random_vector(4, m * k);
+        let b = random_vector(5, k * n);
+        let (a, b) = (MatRef::new(&a, m, k).unwrap(), MatRef::new(&b, k, n).unwrap());
+        let mut expected = random_vector(6, m * n);
+        let c0 = expected.clone();
+        sgemm_blocked(1.0, a, b, 1.0, &mut MatMut::new(&mut expected, m, n).unwrap(), ODD_TILES).unwrap();
+        for threads in [1, 2, 3, 8, 64] {
+            let mut actual = c0.clone();
+            sgemm_parallel(1.0, a, b, 1.0, &mut MatMut::new(&mut actual, m, n).unwrap(), ODD_TILES, threads).unwrap();
+            assert_eq!(actual, expected, "threads = {}", threads);
+        }
+    }
+
+    #[test]
+    fn test_strided_inputs_and_padded_output() {
+        let (m, n, k) = (10, 12, 9);
+        // A 来自 AlignedRows（每行补齐到 64 字节），B 是转置后的 Tensor 视图
+        let a_rows = random_vector(7, m * k);
+        let a = AlignedRows::from_rows(&a_rows, m, k, 64);
+        let bt = Tensor::from_vector(random_vector(8, n * k), &[n, k]).unwrap();
+        let b = bt.transpose(0, 1).unwrap();
+
+        let mut expected = vec![0.0; m * n];
+        let (a_ref, b_ref) = (MatRef::from_aligned(&a), MatRef::from_tensor(&b).unwrap());
+        sgemm_naive(1.0, a_ref, b_ref, 0.0, &mut MatMut::new(&mut expected, m, n).unwrap()).unwrap();
+
+        // C 每行 16 个 f32，后 4 个是补齐，不能被写
+        let mut padded = vec![-7.0; m * 16];
+        let mut c = MatMut::with_row_stride(&mut padded, m, n, 16).unwrap();
+        sgemm_parallel(1.0, a_ref, b_ref, 0.0, &mut c, ODD_TILES, 3).unwrap();
+        let actual: Vec<f32> = (0..m).flat_map(|i| c.row(i).to_vec()).collect();
+        assert_close(&actual, &expected, Tolerance::for_reduction(k));
+        assert!(padded.chunks(16).all(|row| row[n..] == [-7.0; 4]));
+    }
+
+    #[test]
+    fn test_tensor_entry_points() {
+        let a = Tensor::from_vector(random_vector(9, 33 * 20), &[33, 20]).unwrap();
+        let b = Tensor::from_vector(random_vector(10, 20 * 15), &[20, 15]).unwrap();
+        let expected = crate::matmul_naive(&a, &b).unwrap();
+        let blocked = matmul_blocked(&a, &b, ODD_TILES).unwrap();
+        let parallel = matmul_parallel(&a, &b, ODD_TILES, 4).unwrap();
+        assert_eq!(parallel.shape(), &[33, 15]);
+        crate::check_close_tensor(&blocked, &expected, Tolerance::for_reduction(20)).unwrap();
+        assert_eq!(blocked.to_vector(), parallel.to_vector());
+        assert!(matmul_parallel(&a, &a, Tiles::default(), 2).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "tile sizes must be non-zero")]
+    fn test_zero_tile_panics() {
+        let x = [1.0];
+        let mut c = [0.0];
+        let one = MatRef::new(&x, 1, 1).unwrap();
+        let _ = sgemm_blocked(1.0, one, one, 0.0, &mut MatMut::new(&mut c, 1, 1).unwrap(), Tiles::new(0, 1, 1));
+    }
+}
@@ -0,0 +1,65 @@
+// ==============================================================================
+// vector_add - 对应 cuda_drills/01_basic_moves/01_vector_add.cu
+// ==============================================================================
+//
+// 【CUDA 版本】
+//   每个线程算一个元素：i = blockIdx.x * blockDim.x + threadIdx.x; if (i < n) c[i] = a[i] + b[i];
+//
+// 【CPU 版本】
+//   一个循环，编译器会自动向量化；这里是 golden 输出，正确性优先
+//   访存 3 × 4 字节 / 元素、计算 1 FLOP / 元素，是典型的带宽受限 kernel
+// ==============================================================================
+
+use vector::Vector;
+
+use crate::KernelError;
+
+// out[i] = a[i] + b[i]
+pub fn vector_add_into(a: &[f32], b: &[f32], out: &mut [f32]) -> Result<(), KernelError> {
+    if b.len() != a.len() {
+        return Err(KernelError::ShapeMismatch { what: "length of b", expected: a.len(), actual: b.len() });
+    }
+    if out.len() != a.len() {
+        return Err(KernelError::ShapeMismatch { what: "length of out", expected: a.len(), actual: out.len() });
+    }
+    for ((c, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *c = x + y;
+    }
+    Ok(())
+}
+
+pub fn vector_add(a: &Vector<f32>, b: &Vector<f32>) -> Result<Vector<f32>, KernelError> {
+    let mut out: Vector<f32> = std::iter::repeat_n(0.0, a.len()).collect();
+    vector_add_into(a, b, &mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{vector_add, vector_add_into};
+    use crate::KernelError;
+    use vector::Vector;
+
+    #[test]
+    fn test_vector_add() {
+        let a: Vector<f32> = (0..1000).map(|i| i as f32).collect();
+        let b: Vector<f32> = (0..1000).map(|i| 0.5 * i as f32).collect();
+        let c = vector_add(&a, &b).unwrap();
+        assert_eq!(c.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(c[i], 1.5 * i as f32);
+        }
+        assert!(vector_add(&Vector::new(), &Vector::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_vector_add_checks_lengths() {
+        let mut out = [0.0; 3];
+        assert_eq!(
+            vector_add_into(&[1.0; 3], &[1.0; 2], &mut out),
+            Err(KernelError::ShapeMismatch { what: "length of b", expected: 3, actual: 2 })
+        );
+        assert!(vector_add_into(&[1.0; 4], &[1.0; 4], &mut out).is_err());
+        assert_eq!(out, [0.0; 3]);
+    }
+}
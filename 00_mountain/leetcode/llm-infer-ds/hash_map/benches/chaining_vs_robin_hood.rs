@@ -0,0 +1,42 @@
+// ==============================================================================
+// Benchmark: 链地址法 HashMap vs 开放寻址 RobinHoodMap
+// ==============================================================================
+//
+// 运行：cargo bench -p hash_map --bench chaining_vs_robin_hood
+//
+// 模拟 Page Table 的典型负载（sequential / strided block id，见 common/mod.rs），
+// 每种负载测插入、命中查找、未命中查找、删除，取 ROUNDS 轮中的最好成绩
+// 最后打印不同哈希函数下 HashMap 的桶分布（stats），检查 strided id 是否退化
+// ==============================================================================
+
+mod common;
+
+use std::hash::BuildHasher;
+
+use common::{best_of, report, sequential_ids, strided_ids, NUM_KEYS};
+use hash_map::{
+    DefaultHashBuilder, FixedSeedState, FxBuildHasher, HashMap, HashMapTrait,
+    IdentityBuildHasher, RobinHoodMap,
+};
+
+fn main() {
+    for (workload, keys) in [("sequential", sequential_ids(NUM_KEYS)), ("strided", strided_ids(NUM_KEYS))] {
+        report(workload, "HashMap", &best_of::<HashMap<usize, usize>>(&keys));
+        report(workload, "RobinHoodMap", &best_of::<RobinHoodMap<usize, usize>>(&keys));
+    }
+
+    for (workload, keys) in [("sequential", sequential_ids(NUM_KEYS)), ("strided", strided_ids(NUM_KEYS))] {
+        report_stats::<DefaultHashBuilder>(workload, "default", &keys);
+        report_stats::<FxBuildHasher>(workload, "fx", &keys);
+        report_stats::<IdentityBuildHasher>(workload, "identity", &keys);
+        report_stats::<FixedSeedState>(workload, "fixed-seed", &keys);
+    }
+}
+
+fn report_stats<S: BuildHasher + Default>(workload: &str, hasher: &str, keys: &[usize]) {
+    let mut map: HashMap<usize, usize, S> = HashMapTrait::new(4);
+    for &k in keys {
+        map.put(k, k);
+    }
+    println!("{:<12} {:<14} {}", workload, hasher, map.stats());
+}
@@ -0,0 +1,98 @@
+// ==============================================================================
+// 对比 benchmark 共用的负载和计时 - chaining_vs_robin_hood / slab_vs_chaining
+// ==============================================================================
+//
+// key 都是 block id：
+//   - sequential: 物理块 id 0..n 连续分配
+//   - strided:    seq_id * 4096 + logical_idx，每个序列占一段 id 空间
+//
+// 每种负载测 4 个阶段：插入、命中查找、未命中查找、删除，取 ROUNDS 轮中的最好成绩
+// ==============================================================================
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bench_support::ns_per_op;
+use hash_map::HashMapTrait;
+
+pub const NUM_KEYS: usize = 100_000;
+pub const ROUNDS: usize = 5;
+
+pub fn sequential_ids(n: usize) -> Vec<usize> {
+    (0..n).collect()
+}
+
+pub fn strided_ids(n: usize) -> Vec<usize> {
+    // 每个序列 100 个逻辑块
+    (0..n).map(|i| (i / 100) * 4096 + i % 100).collect()
+}
+
+pub struct Timings {
+    ops: usize,
+    insert: Duration,
+    hit: Duration,
+    miss: Duration,
+    remove: Duration,
+}
+
+fn run_once<M: HashMapTrait<usize, usize>>(keys: &[usize]) -> Timings {
+    let mut map = M::new(4);
+
+    let start = Instant::now();
+    for &k in keys {
+        map.put(k, k + 1);
+    }
+    let insert = start.elapsed();
+
+    let start = Instant::now();
+    for k in keys {
+        black_box(map.get(black_box(k)));
+    }
+    let hit = start.elapsed();
+
+    // 未命中：用一段与所有 key 都不重叠的 id
+    let start = Instant::now();
+    for k in keys {
+        black_box(map.get(black_box(&(k + usize::MAX / 2))));
+    }
+    let miss = start.elapsed();
+
+    let start = Instant::now();
+    for k in keys {
+        black_box(map.remove(black_box(k)));
+    }
+    let remove = start.elapsed();
+
+    Timings { ops: keys.len(), insert, hit, miss, remove }
+}
+
+pub fn best_of<M: HashMapTrait<usize, usize>>(keys: &[usize]) -> Timings {
+    let mut best = Timings {
+        ops: keys.len(),
+        insert: Duration::MAX,
+        hit: Duration::MAX,
+        miss: Duration::MAX,
+        remove: Duration::MAX,
+    };
+    for _ in 0..ROUNDS {
+        let t = run_once::<M>(keys);
+        best.insert = best.insert.min(t.insert);
+        best.hit = best.hit.min(t.hit);
+        best.miss = best.miss.min(t.miss);
+        best.remove = best.remove.min(t.remove);
+    }
+    best
+}
+
+pub fn report(workload: &str, name: &str, t: &Timings) {
+    let ns_per_op = |d: Duration| ns_per_op(d, t.ops);
+    println!(
+        "{:<12} {:<14} insert {:>7.1} ns/op | hit {:>7.1} ns/op | miss {:>7.1} ns/op | remove {:>7.1} ns/op",
+        workload,
+        name,
+        ns_per_op(t.insert),
+        ns_per_op(t.hit),
+        ns_per_op(t.miss),
+        ns_per_op(t.remove),
+    );
+}
@@ -0,0 +1,88 @@
+// ==============================================================================
+// Benchmark: 一次性 rehash vs 渐进式 rehash 的 put 尾延迟
+// ==============================================================================
+//
+// 运行：cargo bench -p hash_map --bench incremental_rehash
+//
+// 逐个记录每次 put 的耗时，看分位数而不是平均值：
+//   - 一次性 rehash：平均值很低，但每次扩容都有一个与元素数成正比的尖刺
+//   - 渐进式 rehash：搬家的代价被摊到之后的写操作上，p99 / p99.9 略升，max 下降
+//   - 渐进式模式剩下的 max 来自分配并初始化新的桶数组（2N 个空 Vec），
+//     这一步仍然与桶数成正比，只有换成惰性分配的桶数组才能消除
+//
+// 单次 put 只有几十纳秒，Instant 本身的开销不可忽略，所以 p50 只能看相对值
+// ==============================================================================
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use hash_map::HashMap;
+
+const NUM_KEYS: usize = 1_000_000;
+const ROUNDS: usize = 3;
+
+// 每次写操作额外搬的旧桶数
+const REHASH_STEPS: [usize; 3] = [1, 4, 16];
+
+fn put_latencies(incremental_step: Option<usize>) -> Vec<Duration> {
+    let mut map: HashMap<usize, usize> = HashMap::new(4);
+    if let Some(step) = incremental_step {
+        map.enable_incremental_rehash(step);
+    }
+
+    let mut latencies = Vec::with_capacity(NUM_KEYS);
+    for block in 0..NUM_KEYS {
+        let start = Instant::now();
+        map.put(black_box(block), block);
+        latencies.push(start.elapsed());
+    }
+    black_box(&map);
+    latencies
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let index = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[index]
+}
+
+fn report(name: &str, incremental_step: Option<usize>) {
+    // 多跑几轮，每个分位数取最好成绩，减少调度抖动的影响
+    let mut best = [Duration::MAX; 6];
+    let mut total = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let mut latencies = put_latencies(incremental_step);
+        total = total.min(latencies.iter().sum());
+        latencies.sort_unstable();
+
+        let values = [
+            percentile(&latencies, 0.50),
+            percentile(&latencies, 0.99),
+            percentile(&latencies, 0.999),
+            percentile(&latencies, 0.9999),
+            percentile(&latencies, 1.0),
+            latencies.iter().sum::<Duration>() / NUM_KEYS as u32,
+        ];
+        for (b, v) in best.iter_mut().zip(values) {
+            *b = (*b).min(v);
+        }
+    }
+
+    println!(
+        "{:<18} p50 {:>6} ns | p99 {:>6} ns | p99.9 {:>7} ns | p99.99 {:>8} ns | max {:>10} ns | mean {:>5} ns | total {:>6.1} ms",
+        name,
+        best[0].as_nanos(),
+        best[1].as_nanos(),
+        best[2].as_nanos(),
+        best[3].as_nanos(),
+        best[4].as_nanos(),
+        best[5].as_nanos(),
+        total.as_secs_f64() * 1000.0,
+    );
+}
+
+fn main() {
+    report("one-shot", None);
+    for step in REHASH_STEPS {
+        report(&format!("incremental x{}", step), Some(step));
+    }
+}
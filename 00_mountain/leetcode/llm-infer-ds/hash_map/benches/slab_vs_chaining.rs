@@ -0,0 +1,68 @@
+// ==============================================================================
+// Benchmark: Vec<Vec<(K, V)>> 的 HashMap vs 连续 slab 的 SlabHashMap
+// ==============================================================================
+//
+// 运行：cargo bench -p hash_map --bench slab_vs_chaining
+//
+// 两部分：
+//   1. 分配次数：用计数的全局分配器（bench_support）统计插入 N 个 key
+//      （以及随后全部删除）期间发生的 alloc / realloc 次数和申请的总字节数
+//   2. 吞吐：插入、命中查找、未命中查找、删除，与 chaining_vs_robin_hood 共用
+//      负载和计时（common/mod.rs）
+// ==============================================================================
+
+mod common;
+
+use std::hint::black_box;
+
+use bench_support::{alloc_since, alloc_snapshot, AllocCount, CountingAlloc};
+use common::{best_of, report, sequential_ids, strided_ids, NUM_KEYS};
+use hash_map::{HashMap, HashMapTrait, SlabHashMap};
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// ==========================================
+// 分配次数
+// ==========================================
+
+fn count_allocs<M: HashMapTrait<usize, usize>>(keys: &[usize]) -> (AllocCount, AllocCount) {
+    let before = alloc_snapshot();
+    let mut map = M::new(4);
+    for &k in keys {
+        map.put(k, k);
+    }
+    let insert = alloc_since(before);
+
+    let before = alloc_snapshot();
+    for k in keys {
+        map.remove(k);
+    }
+    let remove = alloc_since(before);
+    black_box(map);
+    (insert, remove)
+}
+
+fn report_allocs(name: &str, n: usize, insert: AllocCount, remove: AllocCount) {
+    println!(
+        "{:<14} n={:<8} insert: alloc {:>7} realloc {:>4} bytes {:>10} | remove: alloc {:>4} realloc {:>4}",
+        name, n, insert.allocs, insert.reallocs, insert.bytes, remove.allocs, remove.reallocs,
+    );
+}
+
+fn main() {
+    // 1. 分配次数：1000 个 key 对应 test_large_volume
+    for n in [1_000, NUM_KEYS] {
+        let keys = sequential_ids(n);
+        let (insert, remove) = count_allocs::<HashMap<usize, usize>>(&keys);
+        report_allocs("HashMap", n, insert, remove);
+        let (insert, remove) = count_allocs::<SlabHashMap<usize, usize>>(&keys);
+        report_allocs("SlabHashMap", n, insert, remove);
+    }
+
+    // 2. 吞吐
+    for (workload, keys) in [("sequential", sequential_ids(NUM_KEYS)), ("strided", strided_ids(NUM_KEYS))] {
+        report(workload, "HashMap", &best_of::<HashMap<usize, usize>>(&keys));
+        report(workload, "SlabHashMap", &best_of::<SlabHashMap<usize, usize>>(&keys));
+    }
+}
@@ -0,0 +1,320 @@
+// ==============================================================================
+// BiMap - 一对一的双向映射
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - 物理块 <-> 内容哈希：prefix cache 按 token 哈希找块，淘汰块时又要按块找哈希
+//   - 任何"两边都是唯一 id、两个方向都要 O(1) 查"的表
+//
+// 【结构】
+//   left:  HashMap<L, R>   正向
+//   right: HashMap<R, L>   反向
+//   两张表始终互为逆映射：left[l] == r 当且仅当 right[r] == l
+//
+// 【插入冲突】
+//   一对一映射里 insert(l, r) 可能同时顶掉两对旧映射：(l, r1) 和 (l2, r)。
+//   insert 把被顶掉的映射通过 Overwritten 原样交还给调用方，
+//   不想覆盖时用 insert_no_overwrite
+//
+// 【限制】
+//   每个 key 在两张表里各存一份，所以要求 L: Clone, R: Clone（块 id / 哈希都是整数）
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, HashMap, HashMapTrait};
+
+// insert 顶掉的旧映射
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Overwritten<L, R> {
+    // 两边都是新的
+    Neither,
+    // 完全相同的一对已经存在
+    Pair(L, R),
+    // left 已经映射到别的 right，这是被顶掉的那一对
+    Left(L, R),
+    // right 已经被别的 left 映射，这是被顶掉的那一对
+    Right(L, R),
+    // 两边各顶掉一对：(按 left 顶掉的, 按 right 顶掉的)
+    Both((L, R), (L, R)),
+}
+
+#[derive(Debug)]
+pub struct BiMap<L, R, S = DefaultHashBuilder>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+{
+    left: HashMap<L, R, S>,  // 正向：left -> right
+    right: HashMap<R, L, S>, // 反向：right -> left
+}
+
+impl<L, R> BiMap<L, R>
+where
+    L: Hash + Eq + Clone,
+    R: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<L, R, S> Default for BiMap<L, R, S>
+where
+    L: Hash + Eq + Clone,
+    R: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self { left: HashMapTrait::new(4), right: HashMapTrait::new(4) }
+    }
+}
+
+impl<L, R, S> BiMap<L, R, S>
+where
+    L: Hash + Eq + Clone,
+    R: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    // 插入 (left, right)，先删掉与任一边冲突的旧映射
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        // 1. 两边分别摘掉旧映射（同时维护另一张表）
+        let by_left = self.remove_by_left(&left);
+        let by_right = self.remove_by_right(&right);
+
+        // 2. 判断顶掉了什么：按 left 摘下来的恰好是 (left, right) 时说明原样重复插入
+        let overwritten = match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) if r == right => Overwritten::Pair(l, r),
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            (Some(a), Some(b)) => Overwritten::Both(a, b),
+        };
+
+        // 3. 两张表各写一份
+        self.left.put(left.clone(), right.clone());
+        self.right.put(right, left);
+        overwritten
+    }
+
+    // 任一边已存在时不插入，把这一对原样还给调用方
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.left.contains_key(&left) || self.right.contains_key(&right) {
+            return Err((left, right));
+        }
+        self.left.put(left.clone(), right.clone());
+        self.right.put(right, left);
+        Ok(())
+    }
+
+    // 查找 / 删除与 HashMap 一样接收借用形式：BiMap<String, _> 可以用 &str 查
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.left.get(left)
+    }
+
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.right.get(right)
+    }
+
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.left.contains_key(left)
+    }
+
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.right.contains_key(right)
+    }
+
+    // 按 left 删除，返回被删掉的一对
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (l, r) = self.left.remove_entry(left)?;
+        self.right.remove(&r);
+        Some((l, r))
+    }
+
+    // 按 right 删除，返回被删掉的一对
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (r, l) = self.right.remove_entry(right)?;
+        self.left.remove(&l);
+        Some((l, r))
+    }
+
+    pub fn clear(&mut self) {
+        self.left.clear();
+        self.right.clear();
+    }
+
+    // 按正向表遍历 (left, right)
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.left.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bench_support::Lcg;
+
+    use super::{BiMap, Overwritten};
+
+    // 两张表互为逆映射
+    fn assert_consistent(map: &BiMap<usize, usize>) {
+        assert_eq!(map.left.len(), map.right.len());
+        for (l, r) in map.left.iter() {
+            assert_eq!(map.right.get(r), Some(l));
+        }
+    }
+
+    #[test]
+    fn test_basic_lookup_both_ways() {
+        let mut map = BiMap::new();
+        assert_eq!(map.insert(1, 100), Overwritten::Neither);
+        assert_eq!(map.insert(2, 200), Overwritten::Neither);
+
+        assert_eq!(map.get_by_left(&1), Some(&100));
+        assert_eq!(map.get_by_right(&200), Some(&2));
+        assert!(map.contains_left(&2));
+        assert!(!map.contains_right(&300));
+        assert_eq!(map.len(), 2);
+        assert_consistent(&map);
+    }
+
+    #[test]
+    fn test_insert_reports_overwritten_pairs() {
+        let mut map = BiMap::new();
+        map.insert(1, 100);
+        map.insert(2, 200);
+
+        assert_eq!(map.insert(1, 100), Overwritten::Pair(1, 100));
+        assert_eq!(map.insert(1, 101), Overwritten::Left(1, 100));
+        assert_eq!(map.get_by_right(&100), None);
+        assert_eq!(map.insert(3, 200), Overwritten::Right(2, 200));
+        assert_eq!(map.get_by_left(&2), None);
+
+        // (1, 101) 和 (3, 200) 同时被顶掉
+        assert_eq!(map.insert(1, 200), Overwritten::Both((1, 101), (3, 200)));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get_by_left(&1), Some(&200));
+        assert_consistent(&map);
+    }
+
+    #[test]
+    fn test_insert_no_overwrite() {
+        let mut map = BiMap::new();
+        assert_eq!(map.insert_no_overwrite(1, 100), Ok(()));
+        assert_eq!(map.insert_no_overwrite(1, 101), Err((1, 101)));
+        assert_eq!(map.insert_no_overwrite(2, 100), Err((2, 100)));
+        assert_eq!(map.get_by_left(&1), Some(&100));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_from_either_side() {
+        let mut map = BiMap::new();
+        for i in 0..10 {
+            map.insert(i, i * 10);
+        }
+        assert_eq!(map.remove_by_left(&3), Some((3, 30)));
+        assert_eq!(map.remove_by_right(&50), Some((5, 50)));
+        assert_eq!(map.remove_by_left(&3), None);
+        assert_eq!(map.get_by_right(&30), None);
+        assert_eq!(map.get_by_left(&5), None);
+        assert_eq!(map.len(), 8);
+        assert_consistent(&map);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_consistent(&map);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        // 内容哈希 -> 块 id，用 &str 查询和删除，不需要先构造 String
+        let mut map: BiMap<String, usize> = BiMap::new();
+        map.insert("prefix-a".to_string(), 1);
+        map.insert("prefix-b".to_string(), 2);
+        assert_eq!(map.get_by_left("prefix-a"), Some(&1));
+        assert!(map.contains_left("prefix-b"));
+        assert_eq!(map.remove_by_left("prefix-a"), Some(("prefix-a".to_string(), 1)));
+        assert_eq!(map.get_by_right(&1), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_churn_against_model() {
+        // 与两张 std HashMap 组成的模型对照
+        let mut map = BiMap::new();
+        let mut forward = std::collections::HashMap::new();
+        let mut backward = std::collections::HashMap::new();
+        let mut rng = Lcg::new(11);
+        for step in 0..5000 {
+            let l = rng.below(64);
+            let r = rng.below(64);
+            match step % 4 {
+                0 => {
+                    let expected = forward.remove(&l).map(|r| {
+                        backward.remove(&r);
+                        (l, r)
+                    });
+                    assert_eq!(map.remove_by_left(&l), expected);
+                }
+                1 => {
+                    let expected = backward.remove(&r).map(|l| {
+                        forward.remove(&l);
+                        (l, r)
+                    });
+                    assert_eq!(map.remove_by_right(&r), expected);
+                }
+                _ => {
+                    if let Some(old_r) = forward.remove(&l) {
+                        backward.remove(&old_r);
+                    }
+                    if let Some(old_l) = backward.remove(&r) {
+                        forward.remove(&old_l);
+                    }
+                    forward.insert(l, r);
+                    backward.insert(r, l);
+                    map.insert(l, r);
+                }
+            }
+        }
+
+        assert_consistent(&map);
+        assert_eq!(map.len(), forward.len());
+        for (l, r) in &forward {
+            assert_eq!(map.get_by_left(l), Some(r));
+            assert_eq!(map.get_by_right(r), Some(l));
+        }
+    }
+}
@@ -0,0 +1,410 @@
+// ==============================================================================
+// BlockTable - PagedAttention 的逻辑块 -> 物理块映射
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - vLLM BlockSpaceManager / BlockTable
+//   - 每个序列的 KV cache 按 block_size 个 token 切成逻辑块，
+//     逻辑块 i 存在哪个物理显存块里，由这张表决定
+//
+// 【数据结构】
+//   mapping:   HashMap<(seq_id, logical_idx), physical>  —— 核心映射
+//   refcounts: HashMap<physical, refcount>               —— 多个序列共享同一个物理块
+//   seqs:      HashMap<seq_id, token 数>                  —— 逻辑块数 = ceil(tokens / block_size)
+//   free:      Vec<physical>                              —— 空闲物理块（栈，后进先出）
+//
+// 【学习重点】
+//   1. append_slot：最后一个块写满才分配新块
+//   2. fork：beam search / parallel sampling 的子序列直接共享父序列的全部物理块，
+//      只把引用计数 +1，不拷贝任何 KV 数据
+//   3. 写时复制（Copy-on-Write）：往一个共享的、未写满的最后一块追加 token 时，
+//      先分配新块，由调用方把旧块内容拷贝过去，再写入
+//   4. free：引用计数归零的物理块才真正回到空闲列表
+//
+// 【错误处理】
+//   所有操作失败时都不修改表的状态，返回 BlockTableError
+// ==============================================================================
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{Entry, HashMap};
+
+pub type SeqId = usize;
+pub type PhysicalBlock = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTableError {
+    OutOfBlocks,              // 空闲物理块用完了，调度器应该抢占（preempt）某个序列
+    UnknownSequence(SeqId),   // 序列不存在（从未添加，或已经 free）
+    SequenceExists(SeqId),    // 添加 / fork 出的序列 id 已被占用
+}
+
+impl fmt::Display for BlockTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockTableError::OutOfBlocks => write!(f, "no free physical blocks"),
+            BlockTableError::UnknownSequence(seq) => write!(f, "unknown sequence {}", seq),
+            BlockTableError::SequenceExists(seq) => write!(f, "sequence {} already exists", seq),
+        }
+    }
+}
+
+impl Error for BlockTableError {}
+
+// append_slot 的结果：新 token 写到哪个物理块的第几个位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub block: PhysicalBlock,
+    pub offset: usize,
+    // 触发了写时复制：调用方需要先把 src 块的 KV 数据拷贝到 dst 块
+    pub copy: Option<(PhysicalBlock, PhysicalBlock)>,
+}
+
+#[derive(Debug)]
+pub struct BlockTable {
+    block_size: usize,                                   // 每个物理块容纳的 token 数
+    num_blocks: usize,                                   // 物理块总数
+    free: Vec<PhysicalBlock>,                            // 空闲物理块
+    mapping: HashMap<(SeqId, usize), PhysicalBlock>,     // (seq_id, logical_idx) -> physical
+    refcounts: HashMap<PhysicalBlock, usize>,            // 只记录正在使用的物理块
+    seqs: HashMap<SeqId, usize>,                         // seq_id -> 已写入的 token 数
+}
+
+impl BlockTable {
+    pub fn new(num_blocks: usize, block_size: usize) -> Self {
+        assert!(block_size > 0, "block_size must be positive");
+        Self {
+            block_size,
+            num_blocks,
+            // 倒序入栈，pop 时先拿到 0 号块，分配顺序直观
+            free: (0..num_blocks).rev().collect(),
+            mapping: HashMap::new(4),
+            refcounts: HashMap::new(4),
+            seqs: HashMap::new(4),
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.num_blocks
+    }
+
+    pub fn num_free_blocks(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn num_sequences(&self) -> usize {
+        self.seqs.len()
+    }
+
+    // 物理块当前被多少个序列引用，空闲块为 0
+    pub fn refcount(&self, block: PhysicalBlock) -> usize {
+        self.refcounts.get(&block).copied().unwrap_or(0)
+    }
+
+    // 序列已写入的 token 数
+    pub fn num_tokens(&self, seq: SeqId) -> Result<usize, BlockTableError> {
+        self.seqs.get(&seq).copied().ok_or(BlockTableError::UnknownSequence(seq))
+    }
+
+    // 逻辑块数 = ceil(tokens / block_size)
+    fn num_logical_blocks(&self, num_tokens: usize) -> usize {
+        num_tokens.div_ceil(self.block_size)
+    }
+
+    // 注册一个空序列，第一次 append_slot 时才分配物理块
+    pub fn add_sequence(&mut self, seq: SeqId) -> Result<(), BlockTableError> {
+        if self.seqs.contains_key(&seq) {
+            return Err(BlockTableError::SequenceExists(seq));
+        }
+        self.seqs.put(seq, 0);
+        Ok(())
+    }
+
+    // 逻辑块 logical_idx 对应的物理块
+    pub fn lookup(&self, seq: SeqId, logical_idx: usize) -> Option<PhysicalBlock> {
+        self.mapping.get(&(seq, logical_idx)).copied()
+    }
+
+    // 按逻辑顺序列出序列占用的物理块（attention kernel 的 block table 参数）
+    pub fn physical_blocks(&self, seq: SeqId) -> Result<Vec<PhysicalBlock>, BlockTableError> {
+        let num_tokens = self.num_tokens(seq)?;
+        Ok((0..self.num_logical_blocks(num_tokens))
+            .map(|logical_idx| self.mapping.get(&(seq, logical_idx)).copied())
+            .map(|block| block.expect("every logical block of a live sequence is mapped"))
+            .collect())
+    }
+
+    // 为下一个 token 分配位置
+    pub fn append_slot(&mut self, seq: SeqId) -> Result<Slot, BlockTableError> {
+        let num_tokens = self.num_tokens(seq)?;
+        let logical_idx = num_tokens / self.block_size;
+        let offset = num_tokens % self.block_size;
+
+        // 1. 最后一块已写满（或还没有任何块）：分配一个新块
+        if offset == 0 {
+            let block = self.allocate()?;
+            self.mapping.put((seq, logical_idx), block);
+            self.seqs.put(seq, num_tokens + 1);
+            return Ok(Slot { block, offset, copy: None });
+        }
+
+        // 2. 最后一块还有空位，但和别的序列共享：写时复制
+        let last = self.mapping.get(&(seq, logical_idx)).copied();
+        let last = last.expect("every logical block of a live sequence is mapped");
+        if self.refcount(last) > 1 {
+            let block = self.allocate()?;
+            self.release(last);
+            self.mapping.put((seq, logical_idx), block);
+            self.seqs.put(seq, num_tokens + 1);
+            return Ok(Slot { block, offset, copy: Some((last, block)) });
+        }
+
+        // 3. 独占的最后一块：直接写
+        self.seqs.put(seq, num_tokens + 1);
+        Ok(Slot { block: last, offset, copy: None })
+    }
+
+    // 子序列共享父序列的全部物理块
+    pub fn fork(&mut self, parent: SeqId, child: SeqId) -> Result<(), BlockTableError> {
+        let num_tokens = self.num_tokens(parent)?;
+        if self.seqs.contains_key(&child) {
+            return Err(BlockTableError::SequenceExists(child));
+        }
+
+        for logical_idx in 0..self.num_logical_blocks(num_tokens) {
+            let block = self.mapping.get(&(parent, logical_idx)).copied();
+            let block = block.expect("every logical block of a live sequence is mapped");
+            self.mapping.put((child, logical_idx), block);
+            *self.refcounts.entry(block).or_insert(0) += 1;
+        }
+        self.seqs.put(child, num_tokens);
+        Ok(())
+    }
+
+    // 释放序列：每个物理块引用计数 -1，归零的块回到空闲列表
+    pub fn free(&mut self, seq: SeqId) -> Result<(), BlockTableError> {
+        let num_tokens = self.num_tokens(seq)?;
+        for logical_idx in 0..self.num_logical_blocks(num_tokens) {
+            let block = self.mapping.get(&(seq, logical_idx)).copied();
+            let block = block.expect("every logical block of a live sequence is mapped");
+            self.mapping.remove(&(seq, logical_idx));
+            self.release(block);
+        }
+        self.seqs.remove(&seq);
+        Ok(())
+    }
+
+    fn allocate(&mut self) -> Result<PhysicalBlock, BlockTableError> {
+        let block = self.free.pop().ok_or(BlockTableError::OutOfBlocks)?;
+        self.refcounts.put(block, 1);
+        Ok(block)
+    }
+
+    fn release(&mut self, block: PhysicalBlock) {
+        match self.refcounts.entry(block) {
+            Entry::Occupied(mut entry) => {
+                if *entry.get() > 1 {
+                    *entry.get_mut() -= 1;
+                } else {
+                    entry.remove();
+                    self.free.push(block);
+                }
+            }
+            Entry::Vacant(_) => unreachable!("released block {} is not allocated", block),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockTable, BlockTableError, Slot};
+
+    // 检查所有物理块要么空闲，要么引用计数 = 映射到它的逻辑块数
+    fn assert_consistent(table: &BlockTable, seqs: &[usize]) {
+        let mut uses = vec![0; table.num_blocks()];
+        for &seq in seqs {
+            for block in table.physical_blocks(seq).unwrap() {
+                uses[block] += 1;
+            }
+        }
+        let in_use = uses.iter().filter(|&&n| n > 0).count();
+        assert_eq!(in_use + table.num_free_blocks(), table.num_blocks());
+        for (block, &n) in uses.iter().enumerate() {
+            assert_eq!(table.refcount(block), n, "block {}", block);
+        }
+    }
+
+    #[test]
+    fn test_append_allocates_one_block_per_block_size_tokens() {
+        let mut table = BlockTable::new(8, 4);
+        table.add_sequence(0).unwrap();
+
+        let slots: Vec<Slot> = (0..9).map(|_| table.append_slot(0).unwrap()).collect();
+        let placed: Vec<(usize, usize)> = slots.iter().map(|s| (s.block, s.offset)).collect();
+        assert_eq!(
+            placed,
+            vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (1, 2), (1, 3), (2, 0)]
+        );
+        assert!(slots.iter().all(|s| s.copy.is_none()));
+
+        assert_eq!(table.physical_blocks(0), Ok(vec![0, 1, 2]));
+        assert_eq!(table.lookup(0, 1), Some(1));
+        assert_eq!(table.num_tokens(0), Ok(9));
+        assert_eq!(table.num_free_blocks(), 5);
+        assert_consistent(&table, &[0]);
+    }
+
+    #[test]
+    fn test_fork_shares_blocks_and_copies_on_write() {
+        let mut table = BlockTable::new(8, 4);
+        table.add_sequence(0).unwrap();
+        for _ in 0..6 {
+            table.append_slot(0).unwrap();
+        }
+
+        // fork 不分配任何物理块
+        table.fork(0, 1).unwrap();
+        assert_eq!(table.physical_blocks(1), Ok(vec![0, 1]));
+        assert_eq!(table.refcount(0), 2);
+        assert_eq!(table.refcount(1), 2);
+        assert_eq!(table.num_free_blocks(), 6);
+
+        // 子序列往共享的半满块里写：复制出新块
+        let slot = table.append_slot(1).unwrap();
+        assert_eq!(slot, Slot { block: 2, offset: 2, copy: Some((1, 2)) });
+        assert_eq!(table.physical_blocks(1), Ok(vec![0, 2]));
+        assert_eq!(table.refcount(1), 1);
+
+        // 父序列此时独占块 1，直接写，不再复制
+        let slot = table.append_slot(0).unwrap();
+        assert_eq!(slot, Slot { block: 1, offset: 2, copy: None });
+        assert_consistent(&table, &[0, 1]);
+    }
+
+    #[test]
+    fn test_fork_at_block_boundary_needs_no_copy() {
+        let mut table = BlockTable::new(8, 4);
+        table.add_sequence(0).unwrap();
+        for _ in 0..4 {
+            table.append_slot(0).unwrap();
+        }
+        table.fork(0, 1).unwrap();
+
+        // 最后一块已满，双方各自分配新块，共享的块 0 保持共享
+        assert_eq!(table.append_slot(0).unwrap().copy, None);
+        assert_eq!(table.append_slot(1).unwrap().copy, None);
+        assert_eq!(table.refcount(0), 2);
+        assert_consistent(&table, &[0, 1]);
+    }
+
+    #[test]
+    fn test_free_returns_blocks_only_when_unshared() {
+        let mut table = BlockTable::new(4, 2);
+        table.add_sequence(7).unwrap();
+        for _ in 0..4 {
+            table.append_slot(7).unwrap();
+        }
+        table.fork(7, 8).unwrap();
+        assert_eq!(table.num_free_blocks(), 2);
+
+        // 父序列先结束：块还被子序列引用
+        table.free(7).unwrap();
+        assert_eq!(table.num_free_blocks(), 2);
+        assert_eq!(table.physical_blocks(8), Ok(vec![0, 1]));
+        assert_eq!(table.refcount(0), 1);
+
+        table.free(8).unwrap();
+        assert_eq!(table.num_free_blocks(), 4);
+        assert_eq!(table.num_sequences(), 0);
+        assert_eq!(table.refcount(0), 0);
+        assert_eq!(table.lookup(8, 0), None);
+    }
+
+    #[test]
+    fn test_out_of_blocks_leaves_table_unchanged() {
+        let mut table = BlockTable::new(2, 2);
+        table.add_sequence(0).unwrap();
+        for _ in 0..3 {
+            table.append_slot(0).unwrap();
+        }
+        table.fork(0, 1).unwrap();
+
+        // 块 1 半满且共享，写时复制需要一个新块，但 2 个块都已占用
+        assert_eq!(table.append_slot(1), Err(BlockTableError::OutOfBlocks));
+        assert_eq!(table.num_tokens(1), Ok(3));
+        assert_eq!(table.physical_blocks(1), Ok(vec![0, 1]));
+        assert_eq!(table.refcount(1), 2);
+
+        // 父序列独占后还能继续写满块 1，再往后就没有块了
+        table.free(1).unwrap();
+        table.append_slot(0).unwrap();
+        assert_eq!(table.append_slot(0), Err(BlockTableError::OutOfBlocks));
+        assert_eq!(table.num_tokens(0), Ok(4));
+        assert_consistent(&table, &[0]);
+    }
+
+    #[test]
+    fn test_unknown_and_duplicate_sequences() {
+        let mut table = BlockTable::new(4, 4);
+        assert_eq!(table.append_slot(3), Err(BlockTableError::UnknownSequence(3)));
+        assert_eq!(table.physical_blocks(3), Err(BlockTableError::UnknownSequence(3)));
+        assert_eq!(table.free(3), Err(BlockTableError::UnknownSequence(3)));
+        assert_eq!(table.fork(3, 4), Err(BlockTableError::UnknownSequence(3)));
+
+        table.add_sequence(3).unwrap();
+        table.add_sequence(4).unwrap();
+        assert_eq!(table.add_sequence(3), Err(BlockTableError::SequenceExists(3)));
+        assert_eq!(table.fork(3, 4), Err(BlockTableError::SequenceExists(4)));
+
+        // free 之后 id 可以复用
+        table.free(3).unwrap();
+        assert_eq!(table.free(3), Err(BlockTableError::UnknownSequence(3)));
+        table.add_sequence(3).unwrap();
+
+        assert_eq!(BlockTableError::OutOfBlocks.to_string(), "no free physical blocks");
+        assert_eq!(BlockTableError::UnknownSequence(9).to_string(), "unknown sequence 9");
+    }
+
+    #[test]
+    fn test_beam_search_churn_keeps_refcounts_consistent() {
+        // 4 个 beam 反复 fork / 追加 / 淘汰，每一步检查引用计数与映射一致
+        let mut table = BlockTable::new(64, 4);
+        table.add_sequence(0).unwrap();
+        let mut beams = vec![0];
+        let mut next_id = 1;
+
+        for step in 0..40 {
+            for &beam in &beams {
+                table.append_slot(beam).unwrap();
+            }
+            if step % 5 == 4 {
+                // 淘汰最老的一半，剩下的各 fork 一个
+                let survivors = beams.split_off(beams.len() / 2);
+                for beam in beams {
+                    table.free(beam).unwrap();
+                }
+                beams = survivors;
+                for beam in beams.clone() {
+                    table.fork(beam, next_id).unwrap();
+                    beams.push(next_id);
+                    next_id += 1;
+                }
+            } else if beams.len() < 4 {
+                table.fork(beams[0], next_id).unwrap();
+                beams.push(next_id);
+                next_id += 1;
+            }
+            assert_consistent(&table, &beams);
+        }
+
+        for beam in beams {
+            table.free(beam).unwrap();
+        }
+        assert_eq!(table.num_free_blocks(), 64);
+    }
+}
@@ -0,0 +1,574 @@
+// ==============================================================================
+// ConcurrentHashMap - 分片加锁的线程安全 Page Table
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - 调度线程和多个 worker 线程共享的 block table
+//   - 调度线程分配 / 释放块（写），worker 在 attention 前查物理块（读）
+//
+// 【为什么不直接 RwLock<HashMap>】
+//   - HashMap 的所有写操作都是 &mut self，整张表一把锁，所有线程排队
+//   - 一次扩容要 rehash 整张表，期间所有读者都被挡住
+//
+// 【做法】
+//   1. 把表拆成 N 个分片（N 取 2 的幂），每个分片是一个 RwLock<HashMap>
+//   2. 同一个 key 总是落在同一个分片：哈希值先乘黄金分割常数（Fibonacci hashing）
+//      再取最高的 shard_bits 位选分片。乘法把低位的差异扩散到高位，
+//      所以 IdentityBuildHasher 下连续的 block id 也能均匀分到各分片；
+//      分片内部的 HashMap 仍用原哈希的低位选桶（% size），两者互不相关
+//   3. 不同分片的读写完全并行；扩容 / 缩容也只锁住自己那一片
+//   4. compute / update 的闭包在分片写锁内执行，"读-改-写"是原子的
+//
+// 【限制】
+//   - 锁不能跨方法持有，所以 get 返回克隆的值（V: Clone），或用 read 在锁内访问
+//   - len / for_each 逐个分片加锁，并发写入时看到的不是同一时刻的快照
+//   - 锁中毒：update / compute 的闭包 panic 时先释放写锁再继续 unwind，不会让分片中毒；
+//     分片只会因为 HashMap 自己的操作中途 panic 而中毒（比如 key 的 Hash / Eq 在
+//     put / resize 里 panic），这时分片可能不一致，之后对这个分片的所有访问都会 panic
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{DefaultHashBuilder, Entry, HashMap};
+
+const POISONED: &str = "shard poisoned: a key's Hash / Eq panicked while the shard was being modified";
+
+#[derive(Debug)]
+pub struct ConcurrentHashMap<K, V, S = DefaultHashBuilder>
+where
+    K: Hash + Eq,
+{
+    shards: Box<[RwLock<HashMap<K, V, S>>]>, // 分片数组，长度是 2 的幂
+    shard_bits: u32,                         // log2(分片数)，选分片时取混合后哈希的高 shard_bits 位
+    hash_builder: S,                         // 选分片用的哈希，各分片内部持有它的克隆
+}
+
+impl<K, V> ConcurrentHashMap<K, V>
+where
+    K: Hash + Eq,
+{
+    pub const DEFAULT_SHARDS: usize = 16;
+
+    // 与 HashMap::new 相同：固定 S = DefaultHashBuilder，方便类型推断
+    pub fn new() -> Self {
+        Self::with_shards(Self::DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(num_shards: usize) -> Self {
+        Self::with_shards_and_hasher(num_shards, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V> Default for ConcurrentHashMap<K, V>
+where
+    K: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> ConcurrentHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Clone,
+{
+    // 分片数向上取整到 2 的幂（至少 1 个）；每个分片持有哈希函数的一份克隆
+    pub fn with_shards_and_hasher(num_shards: usize, hash_builder: S) -> Self {
+        let num_shards = num_shards.max(1).next_power_of_two();
+        let shards = (0..num_shards)
+            .map(|_| RwLock::new(HashMap::with_hasher(hash_builder.clone())))
+            .collect();
+        Self {
+            shards,
+            shard_bits: num_shards.trailing_zeros(),
+            hash_builder,
+        }
+    }
+}
+
+// 其余操作只要求 S: BuildHasher（与 HashMap 相同）
+impl<K, V, S> ConcurrentHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    // Fibonacci hashing：乘 2^64 / φ 之后取最高 shard_bits 位。
+    // 不能直接取原哈希的高位：IdentityHasher 下 block id 的高位全是 0，所有 key 都会落进第 0 片。
+    // 分片只有 1 个时 shard_bits = 0（右移 64 位会溢出），总是落在第 0 片
+    fn shard_index<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        if self.shard_bits == 0 {
+            return 0;
+        }
+        let hash = self.hash_builder.hash_one(key);
+        (hash.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (64 - self.shard_bits)) as usize
+    }
+
+    // 用户闭包不会让锁中毒（见 update / compute），中毒说明 HashMap 自己的操作
+    // 中途 panic 了（key 的 Hash / Eq），分片可能不一致，不能假装没事继续用
+    fn read_shard<Q: Hash + ?Sized>(&self, key: &Q) -> RwLockReadGuard<'_, HashMap<K, V, S>> {
+        self.shards[self.shard_index(key)].read().expect(POISONED)
+    }
+
+    fn write_shard<Q: Hash + ?Sized>(&self, key: &Q) -> RwLockWriteGuard<'_, HashMap<K, V, S>> {
+        self.shards[self.shard_index(key)].write().expect(POISONED)
+    }
+
+    // ==========================================
+    // 与 HashMapTrait 相同的 put / get / remove，只是都接收 &self
+    // ==========================================
+
+    pub fn put(&self, key: K, value: V) {
+        self.write_shard(&key).put(key, value);
+    }
+
+    // 锁在返回前释放，只能把值克隆出来
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.read_shard(key).get(key).cloned()
+    }
+
+    // 不想克隆时：在读锁内访问值，返回闭包的结果
+    pub fn read<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&V) -> R,
+    {
+        self.read_shard(key).get(key).map(f)
+    }
+
+    pub fn remove<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.write_shard(key).remove(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.read_shard(key).contains_key(key)
+    }
+
+    // ==========================================
+    // 在分片写锁内执行的"读-改-写"
+    // ==========================================
+
+    // key 存在时原地修改，返回闭包的结果；key 不存在时不调用 f，返回 None
+    pub fn update<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut shard = self.write_shard(key);
+        let value = shard.get_mut(key)?;
+        // 闭包 panic 时值可能只改了一半，但表结构是完整的：先释放写锁再继续 unwind
+        match panic::catch_unwind(AssertUnwindSafe(|| f(value))) {
+            Ok(result) => Some(result),
+            Err(payload) => {
+                drop(shard);
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    // 根据当前值（可能不存在）算出新值：
+    //   f 返回 Some(new) -> 写入 new；f 返回 None -> 删除 key（不存在则什么都不做）
+    // 返回旧值。典型用法是引用计数：
+    //   map.compute(block, |rc| rc.map(|n| n + 1).or(Some(1)));           // fork
+    //   map.compute(block, |rc| rc.filter(|&&n| n > 1).map(|n| n - 1));   // free
+    pub fn compute<F>(&self, key: K, f: F) -> Option<V>
+    where
+        F: FnOnce(Option<&V>) -> Option<V>,
+    {
+        let mut shard = self.write_shard(&key);
+        let entry = shard.entry(key);
+        // 与 update 相同：只对闭包 catch_unwind，entry 的插入 / 删除仍然在外面
+        let current = match &entry {
+            Entry::Occupied(entry) => Some(entry.get()),
+            Entry::Vacant(_) => None,
+        };
+        let new_value = match panic::catch_unwind(AssertUnwindSafe(|| f(current))) {
+            Ok(new_value) => new_value,
+            Err(payload) => {
+                drop(entry);
+                drop(shard);
+                panic::resume_unwind(payload)
+            }
+        };
+
+        match (entry, new_value) {
+            (Entry::Occupied(mut entry), Some(value)) => Some(entry.insert(value)),
+            (Entry::Occupied(entry), None) => Some(entry.remove()),
+            (Entry::Vacant(entry), Some(value)) => {
+                entry.insert(value);
+                None
+            }
+            (Entry::Vacant(_), None) => None,
+        }
+    }
+
+    // ==========================================
+    // 整表操作：逐个分片加锁
+    // ==========================================
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().expect(POISONED).len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.write().expect(POISONED).clear();
+        }
+    }
+
+    // 依次在每个分片的读锁内访问所有元素（读锁不会因闭包 panic 而中毒）
+    pub fn for_each<F: FnMut(&K, &V)>(&self, mut f: F) {
+        for shard in self.shards.iter() {
+            let shard = shard.read().expect(POISONED);
+            for (key, value) in shard.iter() {
+                f(key, value);
+            }
+        }
+    }
+
+    // 拆回普通 HashMap 的集合，不再需要加锁
+    pub fn into_shards(self) -> Vec<HashMap<K, V, S>> {
+        self.shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| shard.into_inner().expect(POISONED))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap as StdHashMap;
+    use std::sync::Barrier;
+    use std::thread;
+
+    use bench_support::Lcg;
+
+    use super::ConcurrentHashMap;
+    use crate::test_util::Seeded;
+    use crate::{FixedSeedState, IdentityBuildHasher};
+
+    const THREADS: usize = 8;
+
+    #[test]
+    fn test_basic_operations() {
+        let map = ConcurrentHashMap::with_shards(4);
+        map.put(1, "one".to_string());
+        map.put(2, "two".to_string());
+
+        assert_eq!(map.get(&1), Some("one".to_string()));
+        assert_eq!(map.read(&2, |v| v.len()), Some(3));
+        assert_eq!(map.get(&3), None);
+        assert!(map.contains_key(&2));
+        assert_eq!(map.len(), 2);
+
+        assert!(map.remove(&1));
+        assert!(!map.remove(&1));
+        assert_eq!(map.len(), 1);
+
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        // Seeded 没有 Default，构造只需要 Clone，其余操作只需要 BuildHasher
+        let map: ConcurrentHashMap<String, usize, Seeded> = ConcurrentHashMap::with_shards_and_hasher(4, Seeded(7));
+        for i in 0..100 {
+            map.put(format!("block-{}", i), i);
+        }
+        assert_eq!(map.get("block-42"), Some(42));
+        assert_eq!(map.update("block-1", |v| { *v += 1; *v }), Some(2));
+        assert!(map.remove("block-0"));
+        assert!(!map.contains_key("block-0"));
+        assert_eq!(map.len(), 99);
+    }
+
+    #[test]
+    fn test_shard_count_rounds_up_to_power_of_two() {
+        let map: ConcurrentHashMap<usize, usize> = ConcurrentHashMap::with_shards(5);
+        assert_eq!(map.shard_count(), 8);
+        let map: ConcurrentHashMap<usize, usize> = ConcurrentHashMap::with_shards(0);
+        assert_eq!(map.shard_count(), 1);
+
+        // 单分片也能正常工作
+        for block in 0..100 {
+            map.put(block, block);
+        }
+        assert_eq!(map.len(), 100);
+    }
+
+    #[test]
+    fn test_identity_hasher_spreads_over_shards() {
+        // block id 用 IdentityHasher：哈希值就是 id 本身，高位全是 0
+        let map: ConcurrentHashMap<usize, usize, IdentityBuildHasher> =
+            ConcurrentHashMap::with_shards_and_hasher(16, IdentityBuildHasher::default());
+        for block in 0..16_000usize {
+            map.put(block, block);
+        }
+        for shard in map.into_shards() {
+            assert!((800..1200).contains(&shard.len()), "unbalanced shard: {}", shard.len());
+            // 分片内部用低位选桶，不能因为选分片而只用到一部分桶
+            let stats = shard.stats();
+            let occupied = stats.bucket_count - stats.empty_buckets();
+            assert!(stats.max_chain_len <= 2, "{:?}", stats);
+            assert!(occupied * 10 >= stats.element_count * 9, "{:?}", stats);
+        }
+    }
+
+    #[test]
+    fn test_keys_spread_over_shards() {
+        let map = ConcurrentHashMap::with_shards(8);
+        for block in 0..8000usize {
+            map.put(block, block);
+        }
+        for shard in map.into_shards() {
+            assert!(shard.len() > 500, "shard too small: {}", shard.len());
+        }
+    }
+
+    #[test]
+    fn test_update_and_compute() {
+        let map: ConcurrentHashMap<&str, usize> = ConcurrentHashMap::new();
+
+        // update 不会插入
+        assert_eq!(map.update("a", |v| *v += 1), None);
+        assert!(!map.contains_key("a"));
+
+        // compute：不存在 -> 插入
+        assert_eq!(map.compute("a", |v| v.map(|n| n + 1).or(Some(1))), None);
+        assert_eq!(map.compute("a", |v| v.map(|n| n + 1).or(Some(1))), Some(1));
+        assert_eq!(map.get("a"), Some(2));
+
+        assert_eq!(map.update("a", |v| { *v *= 10; *v }), Some(20));
+
+        // compute 返回 None -> 删除
+        assert_eq!(map.compute("a", |_| None), Some(20));
+        assert!(!map.contains_key("a"));
+        assert_eq!(map.compute("a", |_| None), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_stress_disjoint_writers_match_sequential_model() {
+        // 每个线程只写自己的 key 段，线程间顺序无关，
+        // 最终状态等于把各线程的操作依次在一个单线程 map 上重放
+        let map = ConcurrentHashMap::with_shards_and_hasher(8, FixedSeedState::new(5));
+        let ops = |thread_id: usize| {
+            // 每个线程自己的确定性随机序列
+            let mut rng = Lcg::new(thread_id as u64 + 1);
+            (0..20_000)
+                .map(|_| {
+                    let key = thread_id * 1_000_000 + rng.below(500);
+                    let insert = !rng.next_usize().is_multiple_of(3);
+                    (key, insert, rng.next_usize())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        thread::scope(|s| {
+            for thread_id in 0..THREADS {
+                let map = &map;
+                s.spawn(move || {
+                    for (key, insert, value) in ops(thread_id) {
+                        if insert {
+                            map.put(key, value);
+                        } else {
+                            map.remove(&key);
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut model = StdHashMap::new();
+        for thread_id in 0..THREADS {
+            for (key, insert, value) in ops(thread_id) {
+                if insert {
+                    model.insert(key, value);
+                } else {
+                    model.remove(&key);
+                }
+            }
+        }
+
+        assert_eq!(map.len(), model.len());
+        let mut seen = 0;
+        map.for_each(|key, value| {
+            assert_eq!(model.get(key), Some(value));
+            seen += 1;
+        });
+        assert_eq!(seen, model.len());
+    }
+
+    #[test]
+    fn test_stress_compute_and_update_match_sequential_model() {
+        // 所有线程争抢同一批 key 做引用计数。每轮 +1、-1（减到 0 删除）、+1，
+        // 这些操作可交换，任意交错下每个 key 的最终计数都等于顺序执行的结果；
+        // 如果 compute 不是原子的，就会丢失更新
+        const ROUNDS: usize = 5_000;
+        const KEYS: usize = 64;
+        let map: ConcurrentHashMap<usize, usize> = ConcurrentHashMap::with_shards(4);
+        let hits: ConcurrentHashMap<usize, usize> = ConcurrentHashMap::with_shards(4);
+        for key in 0..KEYS {
+            hits.put(key, 0);
+        }
+        let barrier = Barrier::new(THREADS);
+
+        thread::scope(|s| {
+            for thread_id in 0..THREADS {
+                let (map, hits, barrier) = (&map, &hits, &barrier);
+                s.spawn(move || {
+                    barrier.wait();
+                    for round in 0..ROUNDS {
+                        let key = (round * 7 + thread_id) % KEYS;
+                        map.compute(key, |rc| rc.map(|n| n + 1).or(Some(1)));
+                        map.compute(key, |rc| rc.filter(|&&n| n > 1).map(|n| n - 1));
+                        map.compute(key, |rc| rc.map(|n| n + 1).or(Some(1)));
+                        hits.update(&key, |n| *n += 1);
+                    }
+                });
+            }
+        });
+
+        let mut model: StdHashMap<usize, usize> = StdHashMap::new();
+        for thread_id in 0..THREADS {
+            for round in 0..ROUNDS {
+                *model.entry((round * 7 + thread_id) % KEYS).or_insert(0) += 1;
+            }
+        }
+
+        assert_eq!(map.len(), model.len());
+        for (key, count) in &model {
+            assert_eq!(map.get(key), Some(*count));
+            assert_eq!(hits.get(key), Some(*count));
+        }
+    }
+
+    #[test]
+    fn test_readers_see_consistent_values_during_writes() {
+        // 写线程不断插入 / 删除，读线程看到的值要么不存在，要么是完整写入的值
+        let map: ConcurrentHashMap<usize, (usize, usize)> = ConcurrentHashMap::with_shards(4);
+
+        thread::scope(|s| {
+            for thread_id in 0..THREADS / 2 {
+                let map = &map;
+                s.spawn(move || {
+                    let mut rng = Lcg::new(thread_id as u64);
+                    for _ in 0..20_000 {
+                        let key = rng.below(1000);
+                        if rng.next_usize().is_multiple_of(4) {
+                            map.remove(&key);
+                        } else {
+                            map.put(key, (key, key * 2));
+                        }
+                    }
+                });
+            }
+            for _ in 0..THREADS / 2 {
+                let map = &map;
+                s.spawn(move || {
+                    for round in 0..20_000 {
+                        let key = round % 1000;
+                        if let Some((k, double)) = map.get(&key) {
+                            assert_eq!((k, double), (key, key * 2));
+                        }
+                    }
+                });
+            }
+        });
+
+        map.for_each(|key, value| assert_eq!(*value, (*key, key * 2)));
+    }
+
+    #[test]
+    fn test_panicking_closure_does_not_poison_shard() {
+        let map: ConcurrentHashMap<usize, usize> = ConcurrentHashMap::with_shards(1);
+        map.put(1, 1);
+
+        let result = thread::scope(|s| {
+            s.spawn(|| map.update(&1, |_| panic!("closure failed"))).join()
+        });
+        assert!(result.is_err());
+
+        // 分片仍然可用，数据保持闭包执行前的状态
+        assert_eq!(map.get(&1), Some(1));
+        map.put(2, 2);
+        assert_eq!(map.len(), 2);
+
+        // compute 的闭包同理，key 不存在时也不会写入
+        let result = thread::scope(|s| {
+            s.spawn(|| map.compute(3, |_| panic!("closure failed"))).join()
+        });
+        assert!(result.is_err());
+        assert!(!map.contains_key(&3));
+        assert_eq!(map.compute(1, |rc| rc.map(|n| n + 1)), Some(1));
+        assert_eq!(map.get(&1), Some(2));
+    }
+
+    #[test]
+    fn test_panicking_key_poisons_shard() {
+        use std::hash::{Hash, Hasher};
+
+        // Eq 在比较 13 与 13 时 panic：发生在分片内部的 put 里，分片可能已经不一致
+        #[derive(Clone, Copy, Debug)]
+        struct Key(usize);
+        impl Hash for Key {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                assert!(self.0 != 13 || other.0 != 13, "key comparison failed");
+                self.0 == other.0
+            }
+        }
+        impl Eq for Key {}
+
+        let map: ConcurrentHashMap<Key, usize> = ConcurrentHashMap::with_shards(1);
+        map.put(Key(13), 1);
+        let result = thread::scope(|s| s.spawn(|| map.put(Key(13), 2)).join());
+        assert!(result.is_err());
+
+        // 中毒不再被忽略：之后对这个分片的访问都会 panic
+        let result = thread::scope(|s| s.spawn(|| map.get(&Key(1))).join());
+        assert!(result.is_err());
+        let result = thread::scope(|s| s.spawn(|| map.len()).join());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,306 @@
+// ==============================================================================
+// Entry API - 一次哈希完成"读-改-写"
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - Block Manager 的引用计数：fork 时 refcount += 1，free 时 refcount -= 1
+//
+// 【为什么需要】
+//   - get 再 put 会把同一个 key 哈希两次、在桶里找两次
+//   - entry(key) 只算一次 bucket_index、只遍历一次桶，之后的操作直接落在这个位置
+//
+// 【用法】
+//   *map.entry(block_id).or_insert(0) += 1;
+//   map.entry(block_id).and_modify(|rc| *rc += 1).or_insert(1);
+// ==============================================================================
+
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, HashMap, LoadFactorPolicy, ResizePolicy};
+
+pub enum Entry<'a, K, V, S = DefaultHashBuilder, P = LoadFactorPolicy>
+where
+    K: Hash + Eq,
+{
+    Occupied(OccupiedEntry<'a, K, V, S, P>),
+    Vacant(VacantEntry<'a, K, V, S, P>),
+}
+
+// key 已存在：记住它在哪个桶、桶内第几个
+pub struct OccupiedEntry<'a, K, V, S = DefaultHashBuilder, P = LoadFactorPolicy>
+where
+    K: Hash + Eq,
+{
+    map: &'a mut HashMap<K, V, S, P>,
+    bucket_index: usize,
+    pos: usize,
+}
+
+// key 不存在：记住它应该落在哪个桶，并持有 key 的所有权
+pub struct VacantEntry<'a, K, V, S = DefaultHashBuilder, P = LoadFactorPolicy>
+where
+    K: Hash + Eq,
+{
+    map: &'a mut HashMap<K, V, S, P>,
+    bucket_index: usize,
+    key: K,
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, P> {
+        // 0. 渐进式 rehash 进行中：把 key 所在的旧桶搬到新表，之后只看新表
+        self.prepare_write(&key);
+
+        // 1. 只计算一次哈希
+        let bucket_index = self.hash_function(&key);
+
+        // 2. 只遍历一次桶
+        match self.buckets[bucket_index].iter().position(|pair| pair.0 == key) {
+            Some(pos) => Entry::Occupied(OccupiedEntry { map: self, bucket_index, pos }),
+            None => Entry::Vacant(VacantEntry { map: self, bucket_index, key }),
+        }
+    }
+}
+
+impl<'a, K, V, S, P> Entry<'a, K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    // 只有 key 不存在时才调用 f，适合构造代价高的值
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    // 已存在时原地修改，然后把 Entry 原样交还，便于继续链式调用 or_insert
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S, P> OccupiedEntry<'a, K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn key(&self) -> &K {
+        &self.map.buckets[self.bucket_index][self.pos].0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.map.buckets[self.bucket_index][self.pos].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.buckets[self.bucket_index][self.pos].1
+    }
+
+    // 消耗 Entry，返回与 map 生命周期相同的可变引用
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.buckets[self.bucket_index][self.pos].1
+    }
+
+    // 替换值，返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    // 删除并取回 (key, value)，缩容逻辑与 remove 一致
+    pub fn remove_entry(self) -> (K, V) {
+        let pair = self.map.buckets[self.bucket_index].remove(self.pos);
+        self.map.count -= 1;
+        self.map.shrink_if_needed();
+        pair
+    }
+}
+
+impl<'a, K, V, S, P> VacantEntry<'a, K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        let mut bucket_index = self.bucket_index;
+
+        // 1. 扩容检查：put 是插入后用 count 询问策略，
+        //    这里提前用 (count + 1) 询问，触发时机完全相同，
+        //    但扩容发生在插入之前，返回的引用不会因为 rehash 而失效
+        if map.policy.should_grow(map.count + 1, map.size) {
+            map.resize(map.policy.grow(map.size));
+            // 桶数量变了，需要重新定位
+            bucket_index = map.hash_function(&self.key);
+        }
+
+        // 2. 插入并返回新值的引用
+        let bucket = &mut map.buckets[bucket_index];
+        bucket.push((self.key, value));
+        map.count += 1;
+
+        &mut bucket.last_mut().expect("bucket cannot be empty right after push").1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashMap;
+
+    use super::Entry;
+
+    #[test]
+    fn test_refcount_increment() {
+        // Page Table 场景：同一个物理块被多次引用
+        let mut refcounts: HashMap<usize, usize> = HashMap::new(4);
+
+        for block in [7, 7, 3, 7, 3, 9] {
+            *refcounts.entry(block).or_insert(0) += 1;
+        }
+
+        assert_eq!(refcounts.get(&7), Some(&3));
+        assert_eq!(refcounts.get(&3), Some(&2));
+        assert_eq!(refcounts.get(&9), Some(&1));
+        assert_eq!(refcounts.count, 3);
+    }
+
+    #[test]
+    fn test_and_modify_or_insert() {
+        let mut map: HashMap<&str, i32> = HashMap::new(4);
+
+        map.entry("a").and_modify(|v| *v += 10).or_insert(1);
+        assert_eq!(map.get(&"a"), Some(&1));
+
+        map.entry("a").and_modify(|v| *v += 10).or_insert(1);
+        assert_eq!(map.get(&"a"), Some(&11));
+    }
+
+    #[test]
+    fn test_or_insert_with_is_lazy() {
+        let mut map: HashMap<i32, String> = HashMap::new(4);
+        map.put(1, "one".to_string());
+
+        let mut calls = 0;
+        map.entry(1).or_insert_with(|| {
+            calls += 1;
+            "uno".to_string()
+        });
+        map.entry(2).or_insert_with(|| {
+            calls += 1;
+            "two".to_string()
+        });
+
+        assert_eq!(calls, 1);
+        assert_eq!(map.get(&1), Some(&"one".to_string()));
+        assert_eq!(map.get(&2), Some(&"two".to_string()));
+    }
+
+    #[test]
+    fn test_or_default() {
+        let mut map: HashMap<i32, Vec<usize>> = HashMap::new(4);
+        map.entry(1).or_default().push(100);
+        map.entry(1).or_default().push(200);
+        assert_eq!(map.get(&1), Some(&vec![100, 200]));
+    }
+
+    #[test]
+    fn test_entry_grows_like_put() {
+        // 与 test_resize 相同的时机：第 4 个元素触发 4 -> 8
+        let mut map: HashMap<i32, i32> = HashMap::new(4);
+        for i in 1..=3 {
+            *map.entry(i).or_insert(0) += i;
+        }
+        assert_eq!(map.size, 4);
+
+        let value = map.entry(4).or_insert(4);
+        *value += 1;
+        assert_eq!(map.size, 8);
+
+        // 扩容后返回的引用指向的是新桶里的值
+        assert_eq!(map.get(&4), Some(&5));
+        for i in 1..=3 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_remove_entry_shrinks_like_remove() {
+        let mut map: HashMap<i32, i32> = HashMap::new(4);
+        for i in 0..20 {
+            map.put(i, i * 2);
+        }
+        assert_eq!(map.size, 32);
+
+        for i in 0..13 {
+            match map.entry(i) {
+                Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), (i, i * 2)),
+                Entry::Vacant(_) => panic!("key {} should exist", i),
+            }
+        }
+        assert_eq!(map.count, 7);
+        assert_eq!(map.size, 16);
+
+        assert!(matches!(map.entry(0), Entry::Vacant(_)));
+        assert_eq!(map.get(&19), Some(&38));
+    }
+
+    #[test]
+    fn test_occupied_insert_returns_old_value() {
+        let mut map: HashMap<i32, i32> = HashMap::new(4);
+        map.put(1, 10);
+
+        if let Entry::Occupied(mut entry) = map.entry(1) {
+            assert_eq!(entry.key(), &1);
+            assert_eq!(entry.insert(20), 10);
+            assert_eq!(entry.get(), &20);
+        } else {
+            panic!("key 1 should exist");
+        }
+        assert_eq!(map.count, 1);
+    }
+}
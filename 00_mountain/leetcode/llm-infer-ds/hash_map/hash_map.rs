@@ -14,8 +14,11 @@
 // 【实现要求】
 //   - 实现 put, get, remove 等操作，要求平均 O(1) 时间复杂度
 //   - 使用 Vec<Vec<(K,V)>> 存储数据（链地址法）
-//   - 使用 std::collections::hash_map::DefaultHasher 计算哈希值
-//   - 正确处理泛型约束：K 需要实现 Hash + Eq
+//   - 默认使用 std::collections::hash_map::DefaultHasher 计算哈希值，
+//     也可以通过 S: BuildHasher 换成 Fx / Identity / 固定种子哈希（见 hasher.rs）
+//   - 正确处理泛型约束：K 需要实现 Hash + Eq（K / V 都不要求 Clone）
+//   - 查找支持借用形式：HashMap<String, _> 可以直接用 &str 查询（K: Borrow<Q>）
+//   - 扩缩容阈值由 P: ResizePolicy 决定，默认 > 0.75 扩容 / < 0.25 缩容（见 policy.rs）
 //
 // 【练习目标】
 //   - 理解 Rust 的 trait 系统在泛型数据结构中的应用
@@ -24,20 +27,303 @@
 // ==============================================================================
 
 use std::mem;
-use std::hash::{Hash, Hasher}; // 引入traits
-use std::collections::hash_map::DefaultHasher;
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash}; // 引入traits
+
+// 开放寻址 + Robin Hood 置换的第二种实现，同样实现 HashMapTrait
+pub mod robin_hood;
+pub use robin_hood::RobinHoodMap;
+
+// Entry API：entry(key) 一次哈希完成读-改-写
+pub mod entry;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+
+// 迭代器：iter / iter_mut / keys / values / drain / retain ...
+pub mod iter;
+pub use iter::{Drain, IntoIter, Iter, IterMut, Keys, Values, ValuesMut};
+
+// 可插拔哈希：Fx / Identity / 固定种子
+pub mod hasher;
+pub use hasher::{
+    DefaultHashBuilder, FixedSeedState, FxBuildHasher, FxHasher, IdentityBuildHasher,
+    IdentityHasher, SeededHasher,
+};
+
+// 扩缩容策略：阈值、倍数、桶数量下限（默认 0.75 / 0.25 / x2 / 4）
+pub mod policy;
+pub use policy::{LoadFactorPolicy, ResizePolicy};
+
+// 渐进式 rehash：新旧两张桶数组并存，每次写操作搬一部分
+pub mod rehash;
+use rehash::Rehash;
+
+// 分片加锁的线程安全版本：每个分片是一个 RwLock<HashMap>
+pub mod concurrent;
+pub use concurrent::ConcurrentHashMap;
+
+// 头部注释里的"logical block id -> physical block id"：PagedAttention 的块表
+pub mod block_table;
+pub use block_table::{BlockTable, BlockTableError, PhysicalBlock, SeqId, Slot};
+
+// 运行时统计：负载因子、链长分布、累计扩缩容次数
+pub mod stats;
+pub use stats::MapStats;
+
+// 哈希表 + 侵入式下标链表：插入顺序 / 访问顺序（LRU 淘汰）
+pub mod linked_hash_map;
+pub use linked_hash_map::{LinkedHashMap, LinkedIter, ListOrder};
+
+// 所有条目放进一个连续 slab、桶里只存下标链的链地址法，同样实现 HashMapTrait
+pub mod slab;
+pub use slab::SlabHashMap;
+
+// 双向映射：一对一的 BiMap、多对多带反向索引的 MultiMap（物理块 <-> 所属序列）
+pub mod bimap;
+pub use bimap::{BiMap, Overwritten};
+pub mod multimap;
+pub use multimap::MultiMap;
+
+// 可选的 serde 支持：Serialize / Deserialize + 保留桶数量的二进制快照
+#[cfg(feature = "serde")]
+pub mod snapshot;
+#[cfg(feature = "serde")]
+pub use snapshot::SnapshotError;
+
+// 测试公用的检查：几种 HashMapTrait 实现共享的扩缩容用例
+#[cfg(test)]
+mod test_util;
 
 #[derive(Debug)]
-pub struct HashMap<K, V>
+pub struct HashMap<K, V, S = DefaultHashBuilder, P = LoadFactorPolicy>
 where 
-    K: Hash + Eq + Clone,
-    V: Clone,
+    K: Hash + Eq,
 {
     buckets: Vec<Vec<(K, V)>>, // 桶数组，每个桶是vector
     size: usize, // 桶的数量
     count: usize, // 元素总数
+    hash_builder: S, // 哈希函数工厂，resize 时复用同一个，不重新构造
+    policy: P, // 扩缩容策略：什么时候扩 / 缩，扩 / 缩到多少
+    rehash: Option<Rehash<K, V>>, // 进行中的渐进式 rehash（旧桶数组 + 搬迁游标）
+    rehash_step: usize, // 每次写操作搬几个旧桶，0 表示关闭渐进式 rehash
+    grow_count: usize, // 累计扩容次数（stats() 使用）
+    shrink_count: usize, // 累计缩容次数，drain / clear 一次性缩小也算一次
+}
+
+impl<K, V> HashMap<K, V>
+where
+    K: Hash + Eq,
+{
+    // HashMap::new(n) 不写类型标注时，默认类型参数 S 不参与类型推断。
+    // 和 std 一样，在 S = DefaultHashBuilder 上提供同名的固有方法，
+    // 让 HashMap::new(4) 直接得到默认哈希的表；其他 S 走 HashMapTrait::new
+    pub fn new(initial_size: usize) -> Self {
+        Self::with_capacity_and_hasher(initial_size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, P> HashMap<K, V, DefaultHashBuilder, P>
+where
+    K: Hash + Eq,
+    P: ResizePolicy,
+{
+    // 默认哈希 + 指定的扩缩容策略，initial_size 与 new 的含义相同（桶数量）
+    pub fn with_capacity_and_policy(initial_size: usize, policy: P) -> Self {
+        Self::with_capacity_hasher_and_policy(initial_size, DefaultHashBuilder::default(), policy)
+    }
 }
 
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    // 使用指定的哈希函数，初始 4 个桶
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(4, hash_builder)
+    }
+
+    // 使用指定的哈希函数和初始桶数量（与 new 的 initial_size 含义相同）
+    pub fn with_capacity_and_hasher(initial_size: usize, hash_builder: S) -> Self {
+        Self::with_capacity_hasher_and_policy(initial_size, hash_builder, LoadFactorPolicy::default())
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    // 最完整的构造方式，其他构造函数都转发到这里
+    pub fn with_capacity_hasher_and_policy(initial_size: usize, hash_builder: S, policy: P) -> Self {
+        Self {
+            buckets: Self::empty_buckets(initial_size),
+            size: initial_size,
+            count: 0,
+            hash_builder,
+            policy,
+            rehash: None,
+            rehash_step: 0,
+            grow_count: 0,
+            shrink_count: 0,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    // 当前桶数量
+    pub fn bucket_count(&self) -> usize {
+        self.size
+    }
+
+    // 从 buckets 个桶开始按策略扩容，直到放得下 count 个元素而不触发扩容
+    fn grown_size(&self, mut buckets: usize, count: usize) -> usize {
+        while self.policy.should_grow(count, buckets) {
+            let next = self.policy.grow(buckets);
+            if next <= buckets {
+                break;
+            }
+            buckets = next;
+        }
+        buckets
+    }
+
+    // 准备 n 个空桶。
+    // 不能写 vec![Vec::new(); n]：vec! 宏要求元素 Clone，
+    // 进而要求 (K, V): Clone，那样就没法存 Box<dyn ...> 这种不可克隆的值
+    fn empty_buckets(n: usize) -> Vec<Vec<(K, V)>> {
+        (0..n).map(|_| Vec::new()).collect()
+    }
+
+    // 与 with_capacity_hasher_and_policy 相同，但桶数组分配失败时返回错误而不是 abort。
+    // 桶数量来自外部输入（快照恢复）时使用
+    #[cfg(feature = "serde")]
+    pub(crate) fn try_with_capacity_hasher_and_policy(
+        initial_size: usize,
+        hash_builder: S,
+        policy: P,
+    ) -> Result<Self, std::collections::TryReserveError> {
+        let mut buckets = Vec::new();
+        buckets.try_reserve_exact(initial_size)?;
+        buckets.extend((0..initial_size).map(|_| Vec::new()));
+        let mut map = Self::with_capacity_hasher_and_policy(0, hash_builder, policy);
+        map.buckets = buckets;
+        map.size = initial_size;
+        Ok(map)
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    // ==========================================
+    // 缩容逻辑 (Shrinking Logic)
+    // ==========================================
+    // 触发条件由 ResizePolicy 决定，默认：
+    // 1. 当前元素密度低于 25% (0.25)
+    // 2. 当前桶大小大于最小限制 (比如 4)，防止缩没了
+    fn shrink_if_needed(&mut self) {
+        let new_size = self.shrunk_size(self.count);
+        if new_size != self.size {
+            self.resize(new_size);
+        }
+    }
+
+    // 元素数量为 count 时，按缩容规则应有的桶数量。
+    // 默认每次缩容为当前的一半，retain / drain 一次删掉很多元素时会连续减半，
+    // 直到规则不再满足，只做一次 rehash
+    fn shrunk_size(&self, count: usize) -> usize {
+        let mut new_size = self.size;
+        while self.policy.should_shrink(count, new_size) {
+            let next = self.policy.shrink(new_size);
+            if next >= new_size {
+                break;
+            }
+            new_size = next;
+        }
+        new_size
+    }
+
+    // 预留空间：之后再插入 additional 个新元素都不会触发扩容
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.count.saturating_add(additional);
+        let new_size = self.grown_size(self.size, needed);
+        if new_size != self.size {
+            self.resize(new_size);
+        }
+    }
+
+    // 缩到能放下当前元素的最小桶数量（从 min_buckets 开始按策略扩容得到）
+    pub fn shrink_to_fit(&mut self) {
+        let new_size = self.grown_size(self.policy.min_buckets(), self.count);
+        if new_size < self.size {
+            self.resize(new_size);
+        }
+    }
+
+    // HashMapTrait::resize 的安全版本：
+    // 桶数量不低于策略下限，也不会小到插入下一个元素之前就违反扩容规则。
+    // 返回实际的桶数量
+    pub fn resize_to(&mut self, buckets: usize) -> usize {
+        let buckets = buckets.max(self.policy.min_buckets());
+        let new_size = self.grown_size(buckets, self.count);
+        self.resize(new_size);
+        self.size
+    }
+
+    // 元素总数
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // 删除全部元素，等价于丢弃 drain() 的结果（桶数量同样回落）
+    pub fn clear(&mut self) {
+        self.drain();
+    }
+
+    // 可变查找：渐进式 rehash 下先把 key 所在的旧桶搬到新表，之后只看新表
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.prepare_write(key);
+        let bucket_index = self.hash_function(key);
+        self.buckets[bucket_index]
+            .iter_mut()
+            .find(|pair| pair.0.borrow() == key)
+            .map(|pair| &mut pair.1)
+    }
+
+    // 删除并取回 (key, value)：只有借用形式的 key 时也能拿回原来的 K
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.prepare_write(key);
+        let bucket_index = self.hash_function(key);
+        let bucket = &mut self.buckets[bucket_index];
+        let pos = bucket.iter().position(|pair| pair.0.borrow() == key)?;
+        let pair = bucket.remove(pos);
+        self.count -= 1;
+        self.shrink_if_needed();
+        Some(pair)
+    }
+}
 
 // 为了与c++保持设计一致，这里直接实现就行但是我们定义trait
 pub trait HashMapTrait<K, V> {
@@ -47,48 +333,67 @@ pub trait HashMapTrait<K, V> {
     
     // 核心
     fn put(& mut self, key: K, value: V);
-    fn get(&self, key: &K) -> Option<&V>;
-    fn remove(&mut self, key: &K) -> bool;
+
+    // 查找类操作接收任意可以从 K 借用出来的 Q：
+    //   HashMap<String, V> 可以用 &str 查询，不需要先构造一个 String
+    // Borrow 的约定保证 Q 与 K 的 Hash / Eq 结果一致，所以能落到同一个桶
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized;
+    fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized;
+    fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
 
     // 辅助
-    fn hash_function(&self, key: &K) -> usize;
+    fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize;
     fn resize(&mut self, new_size: usize);
 }
 
-impl<K, V> HashMapTrait<K, V> for HashMap<K, V>
-where 
-    K: Hash + Eq + Clone,
-    V: Clone,
-{
-    fn new(initial_size: usize) -> Self {
-        Self { 
-            buckets: vec![Vec::new(); initial_size], 
-            size: initial_size, 
-            count: 0,
-         }
-    }
-
-    fn hash_function(&self, key: &K) -> usize {
-        // 1. 创建DefaultHasher实例
-        let mut hasher = DefaultHasher::new();
 
-        // 2. 将key哈希到hasher中
-        key.hash(&mut hasher);
-
-        // 3. 获取最终哈希值并取模
-        (hasher.finish() as usize) % self.size
+// 核心操作只要求 S: BuildHasher、P: ResizePolicy：
+// with_hasher 传进来的哈希函数即使没有 Default，put / get / remove 也照样能用。
+// HashMapTrait 的实现只是转发到这里（trait 的 new 需要 Default，所以不能把约束放在一起）
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        // 1. 由 hash_builder 创建一个状态纯净的 Hasher
+        // 2. 将key哈希到hasher中并取得最终哈希值（hash_one 把这两步合在一起）
+        // 3. 取模
+        (self.hash_builder.hash_one(key) as usize) % self.size
     }
 
 
-    fn resize(&mut self, new_size: usize) {
-        // 1. 确保新大小合理
-        let new_size = new_size.max(4); 
+    pub fn resize(&mut self, new_size: usize) {
+        // 1. 确保新大小合理（不低于策略的桶数量下限）
+        let new_size = new_size.max(self.policy.min_buckets()); 
         if new_size == self.size {
             return;
         }
+
+        if new_size > self.size {
+            self.grow_count += 1;
+        } else {
+            self.shrink_count += 1;
+        }
+
+        // 上一轮渐进式 rehash 还没搬完，先一次性搬完，保证任何时刻最多两张表
+        self.finish_rehash();
     
         // 2. 准备新的空桶数组
-        let new_buckets = vec![Vec::new(); new_size];
+        let new_buckets = Self::empty_buckets(new_size);
     
         // 3. 【关键一步】偷梁换柱！
         // 使用 mem::replace 将 self.buckets 替换成新的空数组。
@@ -99,6 +404,12 @@ where
     
         // 4. 更新 size (这样后续计算 hash 若依赖 self.size 会是正确的，虽然后面我们手动算了)
         self.size = new_size;
+
+        // 渐进式模式：旧桶先挂起来，由后续的写操作分批搬家（见 rehash.rs）
+        if self.rehash_step > 0 {
+            self.rehash = Some(Rehash::new(old_buckets));
+            return;
+        }
     
         // 5. 遍历旧桶，把数据搬家
         for bucket in old_buckets {
@@ -106,10 +417,9 @@ where
                 // 注意：因为我们拥有 old_buckets，这里拿到的 key/value 是真身（Move）
                 
                 // 重新计算 Hash
-                // 必须每次创建新的 Hasher，保证状态纯净
-                let mut hasher = DefaultHasher::new();
-                key.hash(&mut hasher);
-                let hash_value = hasher.finish();
+                // 必须每次创建新的 Hasher，保证状态纯净；
+                // 复用 self.hash_builder（带着种子等状态），而不是重新构造一个
+                let hash_value = self.hash_builder.hash_one(&key);
                 
                 // 计算新位置
                 let new_index = (hash_value as usize) % new_size;
@@ -120,7 +430,8 @@ where
         }
     }
 
-    fn put(&mut self, key: K, value: V) {
+    #[allow(clippy::op_ref)] // 下面的 &pair.0 == &key 是有意写成引用比较
+    pub fn put(&mut self, key: K, value: V) {
         /*
          * 关键的Rust特性：
          *   1.所有权获取：key: K, value: V - 获取所有权
@@ -131,6 +442,9 @@ where
         */
 
 
+        // 0. 渐进式 rehash 进行中：先把 key 所在的旧桶搬到新表，再顺带推进一步
+        self.prepare_write(&key);
+
         // 1. 计算哈希值
         let bucket_index = self.hash_function(&key);
 
@@ -151,17 +465,26 @@ where
         bucket.push((key, value));
         self.count += 1;
 
-        // 5.扩容检查
-        if (self.count as f64) / (self.size as f64) > 0.75 {
-            self.resize(self.size * 2);
+        // 5.扩容检查（默认：负载因子 > 0.75 时扩容为 2 倍）
+        if self.policy.should_grow(self.count, self.size) {
+            self.resize(self.policy.grow(self.size));
         }
     }
 
-    fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         /*
-        get方法接收 key: &K（借用），返回 Option<&V>（可选引用）。
+        get方法接收 key: &Q（借用），返回 Option<&V>（可选引用）。
         */
 
+        // 0. 渐进式 rehash 进行中：key 可能还没搬走（&self 不能顺手搬）
+        if let Some(value) = self.get_from_old(key) {
+            return Some(value);
+        }
+
         // 1. 计算哈希值
         let bucket_index = self.hash_function(key);
 
@@ -170,7 +493,7 @@ where
 
         // 3. 遍历查找
         for pair in bucket.iter(){
-            if &pair.0 == key { // 注意这里用 &pair.0 == key
+            if pair.0.borrow() == key { // 注意这里先把 K 借用成 &Q 再比较
                 return Some(&pair.1);
             }
         }
@@ -179,7 +502,14 @@ where
         None
     }
 
-    fn remove(&mut self, key: &K) -> bool {
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // 0. 渐进式 rehash 进行中：保证 key 只可能在新表里
+        self.prepare_write(key);
+
         // 1. 计算哈希值
         let bucket_index = self.hash_function(key);
 
@@ -187,22 +517,13 @@ where
         let bucket = &mut self.buckets[bucket_index]; // 这里不加mut 下面没办法remove
 
         // 3. 遍历查找要删除的元素
-        if let Some(pos) = bucket.iter().position(|pair| &pair.0 == key) {
+        if let Some(pos) = bucket.iter().position(|pair| pair.0.borrow() == key) {
             // 删除元素
             bucket.remove(pos);
             self.count -= 1;
 
-            // ==========================================
-            // 新增：缩容逻辑 (Shrinking Logic)
-            // ==========================================
-            // 触发条件：
-            // 1. 当前元素密度低于 25% (0.25)
-            // 2. 当前桶大小大于最小限制 (比如 4)，防止缩没了
-            if self.size > 4 && (self.count as f64 / self.size as f64) < 0.25 {
-                // 缩容为当前的一半
-                let new_size = self.size / 2;
-                self.resize(new_size);
-            }
+            // 缩容检查（与 Entry::remove_entry 共用）
+            self.shrink_if_needed();
 
             return true;
         }
@@ -211,18 +532,58 @@ where
         false
     }
 
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
 
+impl<K, V, S, P> HashMapTrait<K, V> for HashMap<K, V, S, P>
+where 
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    P: ResizePolicy + Default,
+{
+    fn new(initial_size: usize) -> Self {
+        Self::with_capacity_hasher_and_policy(initial_size, S::default(), P::default())
+    }
 
-    
-}    
+    fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        HashMap::hash_function(self, key)
+    }
 
-fn main() {
-    println!("这是一个库文件，请使用 `cargo test` 或 `rustc --test` 运行测试。");
+    fn resize(&mut self, new_size: usize) {
+        HashMap::resize(self, new_size)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        HashMap::put(self, key, value)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        HashMap::get(self, key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        HashMap::remove(self, key)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*; // 引入外面的 HashMap 和 HashMapTrait
+    use crate::test_util::Seeded;
 
     #[test]
     fn test_basic_operations() {
@@ -290,6 +651,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_string_keys() {
         // 测试非数字类型的 Key (验证泛型 K: Hash + Eq)
         let mut map = HashMap::new(4);
@@ -365,4 +727,96 @@ mod tests {
         // 5. 验证剩下的数据还能查到 (Rehash 没问题)
         assert_eq!(map.get(&19), Some(&19));
     }
+
+    impl<S, P> crate::test_util::Inspect for HashMap<usize, usize, S, P> {
+        fn bucket_count(&self) -> usize {
+            self.size
+        }
+
+        fn element_count(&self) -> usize {
+            self.count
+        }
+    }
+
+    #[test]
+    fn test_resize_semantics() {
+        // 与 RobinHoodMap / SlabHashMap 共用的扩缩容用例（test_util.rs）
+        crate::test_util::check_resize_semantics::<HashMap<usize, usize>>();
+    }
+
+    #[test]
+    fn test_remove_entry() {
+        let mut map: HashMap<String, i32> = HashMap::new(4);
+        for i in 0..20 {
+            map.put(format!("k{}", i), i);
+        }
+        assert_eq!(map.remove_entry("k7"), Some(("k7".to_string(), 7)));
+        assert_eq!(map.remove_entry("k7"), None);
+        assert_eq!(map.len(), 19);
+
+        // 与 remove 一样会触发缩容
+        for i in 0..15 {
+            map.remove_entry(format!("k{}", i).as_str());
+        }
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.size, 16);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        // HashMap<String, _> 直接用 &str 查询 / 删除，不需要 to_string()
+        let mut map: HashMap<String, i32> = HashMap::new(4);
+        map.put("apple".to_string(), 10);
+        map.put("banana".to_string(), 20);
+
+        assert_eq!(map.get("apple"), Some(&10));
+        assert!(map.contains_key("banana"));
+        assert!(!map.contains_key("cherry"));
+
+        assert!(map.remove("banana"));
+        assert!(!map.contains_key("banana"));
+        assert_eq!(map.count, 1);
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        // Seeded 没有 Default，put / get / remove / resize 仍然可用
+        let mut map: HashMap<String, usize, Seeded> = HashMap::with_hasher(Seeded(7));
+        for i in 0..100 {
+            map.put(format!("block-{}", i), i);
+        }
+        assert!(map.bucket_count() > 4);
+        assert_eq!(map.get("block-42"), Some(&42));
+        assert!(map.contains_key("block-99"));
+        assert!(map.remove("block-0"));
+        assert!(!map.contains_key("block-0"));
+        map.resize(512);
+        assert_eq!(map.bucket_count(), 512);
+        *map.get_mut("block-1").unwrap() += 1;
+        assert_eq!(map.get("block-1"), Some(&2));
+        assert_eq!(map.len(), 99);
+    }
+
+    #[test]
+    fn test_non_clone_values() {
+        // 不可克隆的值：模拟独占的 tensor buffer 和 trait object
+        struct TensorBuffer {
+            data: Vec<f32>,
+        }
+
+        let mut buffers: HashMap<usize, TensorBuffer> = HashMap::new(4);
+        let mut kernels: HashMap<&str, Box<dyn Fn(f32) -> f32>> = HashMap::new(4);
+
+        for block in 0..20 {
+            buffers.put(block, TensorBuffer { data: vec![block as f32; 4] });
+        }
+        kernels.put("double", Box::new(|x| x * 2.0));
+        kernels.put("square", Box::new(|x| x * x));
+
+        // 经历了多次扩容，数据被 move 而不是 clone
+        assert_eq!(buffers.size, 32);
+        assert_eq!(buffers.get(&7).map(|b| b.data[0]), Some(7.0));
+        assert_eq!(kernels.get("double").map(|f| f(3.0)), Some(6.0));
+        assert_eq!(kernels.get("square").map(|f| f(3.0)), Some(9.0));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,308 @@
+// ==============================================================================
+// 可插拔哈希函数 - BuildHasher
+// ==============================================================================
+//
+// 【为什么不直接用 DefaultHasher】
+//   - DefaultHasher 是 SipHash-1-3：抗 HashDoS，但对 8 字节的 block id 来说太重
+//   - Page Table 的 key 是我们自己分配的整数 id，不存在恶意输入
+//   - 做可复现实验时需要"同样的种子 -> 同样的桶分布 -> 同样的迭代顺序"
+//
+// 【提供的三种哈希】
+//   1. FxHasher:       rustc 内部使用的乘法-旋转哈希，一条乘法指令处理一个字
+//   2. IdentityHasher: 直接把整数 id 当作哈希值，dense id % size 天然均匀
+//   3. SeededHasher:   固定种子 + splitmix64 收尾混合，结果与进程、平台无关
+//
+// 【用法】
+//   let map: HashMap<usize, usize, FxBuildHasher> = HashMapTrait::new(4);
+//   let map = HashMap::with_capacity_and_hasher(4, FixedSeedState::new(42));
+//   （HashMap::new 固定使用 DefaultHashBuilder，其他 S 需要通过 trait 或 with_* 构造）
+// ==============================================================================
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+// 与改造前完全相同的默认行为：每次都用 DefaultHasher::new()
+pub type DefaultHashBuilder = BuildHasherDefault<DefaultHasher>;
+
+// ------------------------------------------------------------------------------
+// FxHasher
+// ------------------------------------------------------------------------------
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        // 按 8 字节一组吃进去，尾部不足 8 字节的单独处理
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+        for &byte in chunks.remainder() {
+            self.add_to_hash(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+// ------------------------------------------------------------------------------
+// IdentityHasher：只适用于整数 key
+// ------------------------------------------------------------------------------
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IdentityHasher {
+    hash: u64,
+}
+
+impl Hasher for IdentityHasher {
+    // 非整数 key（比如 String）会走到这里，退化为逐字节折叠，保证仍然可用
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = self.hash.wrapping_shl(8) | byte as u64;
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.hash = i as u64;
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.hash = i as u64;
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.hash = i as u64;
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.hash = i;
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.hash = i as u64;
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type IdentityBuildHasher = BuildHasherDefault<IdentityHasher>;
+
+// ------------------------------------------------------------------------------
+// SeededHasher：固定种子，跨进程 / 跨平台可复现
+// ------------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy)]
+pub struct SeededHasher {
+    inner: FxHasher,
+    seed: u64,
+}
+
+impl Hasher for SeededHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.inner.write_u8(i);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.inner.write_u16(i);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.inner.write_u32(i);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.inner.write_u64(i);
+    }
+
+    // usize 统一按 u64 处理，32 位和 64 位平台得到相同结果
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_u64(i as u64);
+    }
+
+    // splitmix64 收尾：Fx 的低位分布较差，% size 取的恰好是低位
+    fn finish(&self) -> u64 {
+        let mut z = self.inner.finish() ^ self.seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedSeedState {
+    seed: u64,
+}
+
+impl FixedSeedState {
+    pub const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for FixedSeedState {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+impl BuildHasher for FixedSeedState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> SeededHasher {
+        SeededHasher {
+            inner: FxHasher { hash: self.seed },
+            seed: self.seed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::BuildHasher;
+
+    use super::*;
+    use crate::{HashMap, HashMapTrait};
+
+    #[test]
+    fn test_fx_map_basic_operations() {
+        let mut map: HashMap<usize, usize, FxBuildHasher> = HashMapTrait::new(4);
+        for block in 0..1000 {
+            map.put(block, block * 2);
+        }
+        for block in 0..1000 {
+            assert_eq!(map.get(&block), Some(&(block * 2)));
+        }
+        assert!(map.remove(&500));
+        assert_eq!(map.get(&500), None);
+        assert_eq!(map.len(), 999);
+    }
+
+    #[test]
+    fn test_identity_hasher_spreads_dense_ids() {
+        // 64 个连续 id 放进 128 个桶：identity 保证每个桶最多 1 个元素
+        let mut map: HashMap<usize, usize, IdentityBuildHasher> = HashMapTrait::new(128);
+        for block in 0..64 {
+            map.put(block, block);
+        }
+        assert_eq!(map.size, 128);
+        assert!(map.buckets.iter().all(|bucket| bucket.len() <= 1));
+        assert_eq!(map.hash_function(&37), 37);
+    }
+
+    #[test]
+    fn test_identity_hasher_accepts_string_keys() {
+        let mut map: HashMap<String, i32, IdentityBuildHasher> = HashMapTrait::new(4);
+        map.put("k1".to_string(), 1);
+        map.put("k2".to_string(), 2);
+        assert_eq!(map.get(&"k1".to_string()), Some(&1));
+        assert_eq!(map.get(&"k2".to_string()), Some(&2));
+    }
+
+    #[test]
+    fn test_fixed_seed_iteration_order_is_reproducible() {
+        let build = |seed| {
+            let mut map = HashMap::with_hasher(FixedSeedState::new(seed));
+            for block in 0..200usize {
+                map.put(block * 7919, block);
+            }
+            map.keys().copied().collect::<Vec<usize>>()
+        };
+
+        assert_eq!(build(42), build(42));
+        assert_ne!(build(42), build(43));
+    }
+
+    #[test]
+    fn test_fixed_seed_hash_values_are_stable() {
+        // 这个值一旦变化，之前保存的实验结果就无法复现
+        let state = FixedSeedState::new(42);
+        assert_eq!(state.hash_one(12345u64), state.hash_one(12345usize));
+        assert_eq!(state.hash_one(12345u64), FixedSeedState::new(42).hash_one(12345u64));
+        assert_eq!(state.hash_one(12345u64), 0xc595_06c5_7468_68f1);
+    }
+
+    #[test]
+    fn test_resize_reuses_stored_hasher() {
+        // 如果 resize 重新构造 S::default()，种子就会丢失，
+        // 扩容后的元素位置与 hash_function 的计算结果对不上
+        let mut map = HashMap::with_capacity_and_hasher(4, FixedSeedState::new(7));
+        for block in 0..100usize {
+            map.put(block, block);
+        }
+        assert!(map.size >= 128);
+        assert_eq!(map.hasher().seed(), 7);
+
+        for (index, bucket) in map.buckets.iter().enumerate() {
+            for (key, _) in bucket {
+                assert_eq!(map.hash_function(key), index);
+            }
+        }
+
+        for block in 0..90usize {
+            assert!(map.remove(&block));
+        }
+        for block in 90..100usize {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+    }
+
+    #[test]
+    fn test_robin_hood_with_custom_hasher() {
+        use crate::RobinHoodMap;
+
+        let mut map = RobinHoodMap::with_hasher(FixedSeedState::new(1));
+        for block in 0..300usize {
+            map.put(block, block + 1);
+        }
+        for block in 0..300usize {
+            assert_eq!(map.get(&block), Some(&(block + 1)));
+        }
+    }
+}
@@ -0,0 +1,487 @@
+// ==============================================================================
+// 迭代器 - 遍历 Page Table 中的所有映射
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - 打印 / 导出整张 block table（调试、可视化）
+//   - 序列结束时释放它拥有的全部物理块（retain / drain）
+//
+// 【学习重点】
+//   1. 三种所有权形态：&map -> Iter, &mut map -> IterMut, map -> IntoIter
+//   2. 桶数组是 Vec<Vec<(K, V)>>，遍历就是"先遍历桶，再遍历桶内元素"（flatten）
+//      渐进式 rehash 进行中时，新表之后再接上还没搬完的旧表（chain）
+//   3. count 已知，所以所有迭代器都能实现 ExactSizeIterator
+//   4. retain / drain 会批量删除元素，删除后同样遵守 0.25 缩容规则
+// ==============================================================================
+
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Chain, Flatten, FromIterator};
+use std::{slice, vec};
+
+use crate::{HashMap, ResizePolicy};
+
+// 新表在前、旧表（渐进式 rehash 中还没搬完的部分）在后
+type BucketsIter<'a, K, V> = Chain<slice::Iter<'a, Vec<(K, V)>>, slice::Iter<'a, Vec<(K, V)>>>;
+type BucketsIterMut<'a, K, V> =
+    Chain<slice::IterMut<'a, Vec<(K, V)>>, slice::IterMut<'a, Vec<(K, V)>>>;
+type BucketsIntoIter<K, V> = Chain<vec::IntoIter<Vec<(K, V)>>, vec::IntoIter<Vec<(K, V)>>>;
+
+// ------------------------------------------------------------------------------
+// Iter: (&K, &V)
+// ------------------------------------------------------------------------------
+pub struct Iter<'a, K, V> {
+    inner: Flatten<BucketsIter<'a, K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+// ------------------------------------------------------------------------------
+// IterMut: (&K, &mut V) —— key 只读，改了 key 就找不到桶了
+// ------------------------------------------------------------------------------
+pub struct IterMut<'a, K, V> {
+    inner: Flatten<BucketsIterMut<'a, K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        self.remaining -= 1;
+        Some((&*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+// ------------------------------------------------------------------------------
+// IntoIter / Drain: (K, V) —— 拿走所有权
+// ------------------------------------------------------------------------------
+pub struct IntoIter<K, V> {
+    inner: Flatten<BucketsIntoIter<K, V>>,
+    remaining: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.inner.next()?;
+        self.remaining -= 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+// drain 先把旧桶整体搬出来，map 立即变为空表，
+// 迭代器提前丢弃时，剩余元素随旧桶一起被 drop
+pub struct Drain<K, V> {
+    inner: IntoIter<K, V>,
+}
+
+impl<K, V> Iterator for Drain<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Drain<K, V> {}
+
+// ------------------------------------------------------------------------------
+// Keys / Values / ValuesMut
+// ------------------------------------------------------------------------------
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+// ------------------------------------------------------------------------------
+// HashMap 上的入口方法
+// ------------------------------------------------------------------------------
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.buckets.iter().chain(self.old_buckets().iter()).flatten(),
+            remaining: self.count,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        // buckets 和 rehash 是两个不同的字段，可以同时可变借用
+        let old: &mut [Vec<(K, V)>] = match &mut self.rehash {
+            Some(rehash) => &mut rehash.old_buckets,
+            None => &mut [],
+        };
+        IterMut {
+            inner: self.buckets.iter_mut().chain(old.iter_mut()).flatten(),
+            remaining: self.count,
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    // 取走全部元素。count 变为 0，按缩容规则桶数量回落到最小值
+    pub fn drain(&mut self) -> Drain<K, V> {
+        let remaining = self.count;
+
+        // 1. 计算清空后应有的桶数量（与逐个 remove 到 0 的结果一致）
+        let new_size = self.shrunk_size(0);
+        if new_size < self.size {
+            self.shrink_count += 1;
+        }
+
+        // 2. 换上空桶，旧桶（包括渐进式 rehash 还没搬完的部分）的所有权交给迭代器
+        let buckets = std::mem::replace(&mut self.buckets, Self::empty_buckets(new_size));
+        let pending = self.rehash.take().map(|r| r.old_buckets).unwrap_or_default();
+        self.size = new_size;
+        self.count = 0;
+
+        Drain {
+            inner: IntoIter {
+                inner: buckets.into_iter().chain(pending).flatten(),
+                remaining,
+            },
+        }
+    }
+
+    // 只保留 f 返回 true 的元素，f 可以顺便修改 value
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut removed = 0;
+        let pending = self.rehash.as_mut().map(|r| r.old_buckets.iter_mut());
+        for bucket in self.buckets.iter_mut().chain(pending.into_iter().flatten()) {
+            let before = bucket.len();
+            bucket.retain_mut(|(key, value)| f(key, value));
+            removed += before - bucket.len();
+        }
+        self.count -= removed;
+
+        // 批量删除后同样执行缩容检查
+        self.shrink_if_needed();
+    }
+}
+
+// ------------------------------------------------------------------------------
+// IntoIterator：支持 for (k, v) in &map / &mut map / map
+// ------------------------------------------------------------------------------
+impl<'a, K, V, S, P> IntoIterator for &'a HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S, P> IntoIterator for &'a mut HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S, P> IntoIterator for HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let pending = self.rehash.map(|r| r.old_buckets).unwrap_or_default();
+        IntoIter {
+            remaining: self.count,
+            inner: self.buckets.into_iter().chain(pending).flatten(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------
+// FromIterator / Extend：走 put，扩容规则不变
+// ------------------------------------------------------------------------------
+impl<K, V, S, P> FromIterator<(K, V)> for HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    P: ResizePolicy + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = HashMap::with_capacity_hasher_and_policy(4, S::default(), P::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S, P> Extend<(K, V)> for HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.put(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashMap;
+
+    fn block_table(n: usize) -> HashMap<usize, usize> {
+        (0..n).map(|logical| (logical, logical + 100)).collect()
+    }
+
+    #[test]
+    fn test_iter_visits_every_mapping_once() {
+        let map = block_table(50);
+        assert_eq!(map.iter().len(), 50);
+
+        let mut pairs: Vec<(usize, usize)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        pairs.sort();
+        let expected: Vec<(usize, usize)> = (0..50).map(|i| (i, i + 100)).collect();
+        assert_eq!(pairs, expected);
+
+        let mut keys: Vec<usize> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..50).collect::<Vec<_>>());
+
+        let total: usize = map.values().sum();
+        assert_eq!(total, (100..150).sum::<usize>());
+    }
+
+    #[test]
+    fn test_iter_mut_and_values_mut() {
+        let mut map = block_table(10);
+
+        for (_, physical) in map.iter_mut() {
+            *physical += 1;
+        }
+        for physical in map.values_mut() {
+            *physical *= 2;
+        }
+        for (logical, physical) in &mut map {
+            *physical -= *logical;
+        }
+
+        for i in 0..10 {
+            assert_eq!(map.get(&i), Some(&((i + 101) * 2 - i)));
+        }
+    }
+
+    #[test]
+    fn test_into_iter_and_for_loops() {
+        let map = block_table(8);
+
+        let mut count = 0;
+        for (logical, physical) in &map {
+            assert_eq!(*physical, *logical + 100);
+            count += 1;
+        }
+        assert_eq!(count, 8);
+
+        let mut owned: Vec<(usize, usize)> = map.into_iter().collect();
+        owned.sort();
+        assert_eq!(owned.len(), 8);
+        assert_eq!(owned[7], (7, 107));
+    }
+
+    #[test]
+    fn test_extend_overwrites_and_grows() {
+        let mut map: HashMap<usize, usize> = HashMap::new(4);
+        map.extend((0..3).map(|i| (i, 0)));
+        assert_eq!(map.size, 4);
+
+        map.extend((0..10).map(|i| (i, 1)));
+        assert_eq!(map.len(), 10);
+        assert_eq!(map.size, 16);
+        assert!(map.values().all(|v| *v == 1));
+    }
+
+    #[test]
+    fn test_len_is_empty_clear() {
+        let mut map = block_table(20);
+        assert_eq!(map.len(), 20);
+        assert!(!map.is_empty());
+
+        map.clear();
+        assert_eq!(map.len(), 0);
+        assert!(map.is_empty());
+        assert_eq!(map.size, 4);
+        assert_eq!(map.get(&0), None);
+
+        // 清空后仍然可以继续使用
+        map.put(1, 1);
+        assert_eq!(map.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn test_drain_takes_everything_and_shrinks() {
+        let mut map = block_table(20);
+        assert_eq!(map.size, 32);
+
+        let drain = map.drain();
+        assert_eq!(drain.len(), 20);
+        let mut freed: Vec<usize> = drain.map(|(_, physical)| physical).collect();
+        freed.sort();
+        assert_eq!(freed, (100..120).collect::<Vec<_>>());
+
+        assert!(map.is_empty());
+        assert_eq!(map.size, 4);
+        assert_eq!(map.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_retain_frees_finished_sequence() {
+        // key = (seq_id, logical_idx)，序列 0 结束，释放它的全部块
+        let mut map: HashMap<(usize, usize), usize> = HashMap::new(4);
+        // 序列 0 有 15 个块，序列 1 有 5 个块
+        for logical in 0..15 {
+            map.put((0, logical), logical);
+        }
+        for logical in 0..5 {
+            map.put((1, logical), 100 + logical);
+        }
+        assert_eq!(map.size, 32);
+
+        let mut freed = Vec::new();
+        map.retain(|&(seq, _), physical| {
+            if seq == 0 {
+                freed.push(*physical);
+                false
+            } else {
+                true
+            }
+        });
+
+        freed.sort();
+        assert_eq!(freed, (0..15).collect::<Vec<_>>());
+        assert_eq!(map.len(), 5);
+        assert!(map.keys().all(|(seq, _)| *seq == 1));
+        // 5 / 32 < 0.25 -> 缩容到 16
+        assert_eq!(map.size, 16);
+    }
+
+    #[test]
+    fn test_retain_shrinks_to_fixed_point() {
+        let mut map = block_table(20);
+        assert_eq!(map.size, 32);
+
+        map.retain(|logical, _| *logical == 0);
+        assert_eq!(map.len(), 1);
+        // 32 -> 16 -> 8 -> 4，与逐个 remove 的最终结果一致
+        assert_eq!(map.size, 4);
+        assert_eq!(map.get(&0), Some(&100));
+    }
+}
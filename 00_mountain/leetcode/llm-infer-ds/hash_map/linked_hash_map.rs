@@ -0,0 +1,547 @@
+// ==============================================================================
+// LinkedHashMap - 哈希表 + 侵入式下标链表，对应 LRU 显存块置换
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - Block Eviction: 显存满时淘汰最久未使用的 KV block（prefix cache 的 LRU）
+//   - 既要 O(1) 按 block id 查找，又要 O(1) 找到"最旧"的块
+//
+// 【为什么不用 doubly_linked_list.rs 的 Rc<RefCell<Node>>】
+//   - 每个节点一次堆分配，外加引用计数和运行时借用检查
+//   - 前后指针互相持有 Rc 容易成环，需要 Weak 打破
+//   - 这里把所有节点放进一个 Vec（slab），prev / next 存的是下标：
+//     没有 unsafe、没有 Rc，删除后空出的槽位放进 free 列表复用
+//
+// 【结构】
+//   index: HashMap<K, usize>        key -> 节点下标（本仓库的 HashMap）
+//   nodes: Vec<Option<Node>>        节点数组，None 表示空闲槽位
+//   head / tail                     链表两端，head 最旧、tail 最新
+//
+// 【两种顺序】
+//   - Insertion: 按插入顺序，覆盖已有 key 不改变位置
+//   - Access:    按访问顺序，get / get_mut / insert 都会把 key 移到末尾，
+//                pop_front 弹出的就是最久未使用的元素（LRU）
+//
+// 【限制】
+//   key 同时存在于 index 和节点中，所以要求 K: Clone（block id 都是整数）
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, HashMap};
+
+// 空指针
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListOrder {
+    Insertion,
+    Access,
+}
+
+#[derive(Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Debug)]
+pub struct LinkedHashMap<K, V, S = DefaultHashBuilder>
+where
+    K: Hash + Eq,
+{
+    index: HashMap<K, usize, S>,    // key -> 节点下标
+    nodes: Vec<Option<Node<K, V>>>, // 节点数组
+    free: Vec<usize>,               // 空闲槽位
+    head: usize,                    // 最旧的节点
+    tail: usize,                    // 最新的节点
+    order: ListOrder,
+}
+
+impl<K, V> LinkedHashMap<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    // 插入顺序
+    pub fn new() -> Self {
+        Self::with_order(ListOrder::Insertion)
+    }
+
+    // 访问顺序（LRU）
+    pub fn with_access_order() -> Self {
+        Self::with_order(ListOrder::Access)
+    }
+
+    pub fn with_order(order: ListOrder) -> Self {
+        Self::with_order_and_hasher(order, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V> Default for LinkedHashMap<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> LinkedHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    pub fn with_order_and_hasher(order: ListOrder, hash_builder: S) -> Self {
+        Self {
+            index: HashMap::with_capacity_hasher_and_policy(4, hash_builder, Default::default()),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            order,
+        }
+    }
+
+    pub fn order(&self) -> ListOrder {
+        self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    // ==========================================
+    // 链表操作：只改下标，不移动节点
+    // ==========================================
+
+    fn node(&self, i: usize) -> &Node<K, V> {
+        self.nodes[i].as_ref().expect("linked index points to a live node")
+    }
+
+    fn node_mut(&mut self, i: usize) -> &mut Node<K, V> {
+        self.nodes[i].as_mut().expect("linked index points to a live node")
+    }
+
+    // 把节点 i 从链表上摘下来（节点本身还在 nodes 里）
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = {
+            let node = self.node(i);
+            (node.prev, node.next)
+        };
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.node_mut(prev).next = next;
+        }
+        if next == NIL {
+            self.tail = prev;
+        } else {
+            self.node_mut(next).prev = prev;
+        }
+    }
+
+    // 把节点 i 挂到链表末尾
+    fn link_back(&mut self, i: usize) {
+        let tail = self.tail;
+        {
+            let node = self.node_mut(i);
+            node.prev = tail;
+            node.next = NIL;
+        }
+        if tail == NIL {
+            self.head = i;
+        } else {
+            self.node_mut(tail).next = i;
+        }
+        self.tail = i;
+    }
+
+    // 节点 i 移到末尾（已经在末尾时什么都不做）
+    fn touch(&mut self, i: usize) {
+        if self.tail != i {
+            self.unlink(i);
+            self.link_back(i);
+        }
+    }
+
+    // 摘下并释放节点 i，槽位放进 free 列表
+    fn remove_node(&mut self, i: usize) -> (K, V) {
+        self.unlink(i);
+        let node = self.nodes[i].take().expect("linked index points to a live node");
+        self.free.push(i);
+        self.index.remove(&node.key);
+        (node.key, node.value)
+    }
+
+    // ==========================================
+    // 对外接口
+    // ==========================================
+
+    // 插入或覆盖，返回旧值。
+    // 新 key 放到末尾；已有 key 在访问顺序下移到末尾，插入顺序下位置不变
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&i) = self.index.get(&key) {
+            if self.order == ListOrder::Access {
+                self.touch(i);
+            }
+            return Some(std::mem::replace(&mut self.node_mut(i).value, value));
+        }
+
+        let node = Node { key: key.clone(), value, prev: NIL, next: NIL };
+        let i = match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = Some(node);
+                i
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.index.put(key, i);
+        self.link_back(i);
+        None
+    }
+
+    // 只读查找，不改变顺序
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let &i = self.index.get(key)?;
+        Some(&self.node(i).value)
+    }
+
+    // 访问顺序下会把 key 移到末尾，所以需要 &mut self
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let &i = self.index.get(key)?;
+        if self.order == ListOrder::Access {
+            self.touch(i);
+        }
+        Some(&mut self.node_mut(i).value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(key)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let &i = self.index.get(key)?;
+        Some(self.remove_node(i).1)
+    }
+
+    // 显式标记为"最近使用"，两种顺序下都有效；key 不存在返回 false
+    pub fn move_to_back<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.index.get(key) {
+            Some(&i) => {
+                self.touch(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 最旧的元素（访问顺序下是最久未使用的）
+    pub fn front(&self) -> Option<(&K, &V)> {
+        (self.head != NIL).then(|| {
+            let node = self.node(self.head);
+            (&node.key, &node.value)
+        })
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        (self.tail != NIL).then(|| {
+            let node = self.node(self.tail);
+            (&node.key, &node.value)
+        })
+    }
+
+    // 淘汰：弹出最旧的元素
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        (self.head != NIL).then(|| self.remove_node(self.head))
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        (self.tail != NIL).then(|| self.remove_node(self.tail))
+    }
+
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.nodes.clear();
+        self.free.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+}
+
+// ------------------------------------------------------------------------------
+// 迭代器：从 head 到 tail（从旧到新）
+// ------------------------------------------------------------------------------
+pub struct LinkedIter<'a, K, V> {
+    nodes: &'a [Option<Node<K, V>>],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for LinkedIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.nodes[self.front].as_ref()?;
+        self.front = node.next;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// 从新到旧：淘汰候选按"最不该淘汰"排序
+impl<K, V> DoubleEndedIterator for LinkedIter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.nodes[self.back].as_ref()?;
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for LinkedIter<'_, K, V> {}
+
+impl<K, V, S> LinkedHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    pub fn iter(&self) -> LinkedIter<'_, K, V> {
+        LinkedIter {
+            nodes: &self.nodes,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len(),
+        }
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a LinkedHashMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = LinkedIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bench_support::Lcg;
+
+    use super::{LinkedHashMap, ListOrder};
+    use crate::test_util::Seeded;
+
+    fn keys<V>(map: &LinkedHashMap<usize, V>) -> Vec<usize> {
+        map.keys().copied().collect()
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut map = LinkedHashMap::new();
+        for block in [5, 3, 9, 1] {
+            assert_eq!(map.insert(block, block * 10), None);
+        }
+        assert_eq!(keys(&map), vec![5, 3, 9, 1]);
+
+        // 覆盖和读取都不改变插入顺序
+        assert_eq!(map.insert(3, 33), Some(30));
+        assert_eq!(map.get(&5), Some(&50));
+        assert_eq!(keys(&map), vec![5, 3, 9, 1]);
+        assert_eq!(map.peek(&3), Some(&33));
+
+        assert_eq!(map.front(), Some((&5, &50)));
+        assert_eq!(map.back(), Some((&1, &10)));
+        assert_eq!(map.order(), ListOrder::Insertion);
+    }
+
+    #[test]
+    fn test_access_order_is_lru() {
+        let mut cache = LinkedHashMap::with_access_order();
+        for block in 0..4 {
+            cache.insert(block, ());
+        }
+
+        // 访问 0 和 2，最久未使用的变成 1
+        cache.get(&0);
+        cache.get_mut(&2);
+        assert_eq!(keys(&cache), vec![1, 3, 0, 2]);
+
+        // peek 不算访问
+        cache.peek(&1);
+        assert_eq!(cache.pop_front(), Some((1, ())));
+        assert_eq!(cache.pop_front(), Some((3, ())));
+
+        // 覆盖已有 key 也算访问
+        cache.insert(0, ());
+        assert_eq!(keys(&cache), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_move_to_back_and_pop_both_ends() {
+        let mut map = LinkedHashMap::new();
+        for block in 0..5 {
+            map.insert(block, block);
+        }
+        assert!(map.move_to_back(&1));
+        assert!(map.move_to_back(&1));
+        assert!(!map.move_to_back(&42));
+        assert_eq!(keys(&map), vec![0, 2, 3, 4, 1]);
+
+        assert_eq!(map.pop_back(), Some((1, 1)));
+        assert_eq!(map.pop_front(), Some((0, 0)));
+        assert_eq!(keys(&map), vec![2, 3, 4]);
+        assert_eq!(map.iter().rev().map(|(k, _)| *k).collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!(map.values().len(), 3);
+    }
+
+    #[test]
+    fn test_remove_reuses_slots() {
+        let mut map = LinkedHashMap::new();
+        for block in 0..4 {
+            map.insert(block, block);
+        }
+        assert_eq!(map.remove(&1), Some(1));
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.remove(&3), Some(3));
+        assert_eq!(keys(&map), vec![0, 2]);
+
+        // 新插入的元素复用空槽位，节点数组不再增长
+        map.insert(10, 10);
+        map.insert(11, 11);
+        assert_eq!(map.nodes.len(), 4);
+        assert_eq!(keys(&map), vec![0, 2, 10, 11]);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.pop_front(), None);
+        assert_eq!(map.front(), None);
+        map.insert(7, 7);
+        assert_eq!(keys(&map), vec![7]);
+    }
+
+    #[test]
+    fn test_string_keys_with_borrowed_lookup() {
+        let mut map: LinkedHashMap<String, usize> = LinkedHashMap::with_access_order();
+        map.insert("a".to_string(), 1);
+        map.insert("b".to_string(), 2);
+        assert_eq!(map.get("a"), Some(&1));
+        assert!(map.contains_key("b"));
+        assert_eq!(map.pop_front(), Some(("b".to_string(), 2)));
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        // Seeded 没有 Default，查找、修改和遍历仍然可用
+        let mut map: LinkedHashMap<String, usize, Seeded> =
+            LinkedHashMap::with_order_and_hasher(ListOrder::Access, Seeded(7));
+        for i in 0..10 {
+            map.insert(format!("block-{}", i), i);
+        }
+        assert_eq!(map.get("block-0"), Some(&0));
+        *map.get_mut("block-1").unwrap() += 10;
+        assert_eq!(map.back(), Some((&"block-1".to_string(), &11)));
+        assert_eq!(map.remove("block-2"), Some(2));
+        assert!(!map.contains_key("block-2"));
+        assert_eq!(map.iter().count(), 9);
+    }
+
+    #[test]
+    fn test_matches_vecdeque_model_under_churn() {
+        // 用 VecDeque 模拟访问顺序：O(n) 但显然正确
+        let mut map = LinkedHashMap::with_access_order();
+        let mut model: VecDeque<(usize, usize)> = VecDeque::new();
+
+        let mut rng = Lcg::new(7);
+        for round in 0..5000 {
+            let key = rng.below(64);
+            let pos = model.iter().position(|&(k, _)| k == key);
+            match rng.below(5) {
+                0 | 1 => {
+                    let old = pos.map(|p| model.remove(p).unwrap().1);
+                    model.push_back((key, round));
+                    assert_eq!(map.insert(key, round), old);
+                }
+                2 => {
+                    let expected = pos.map(|p| {
+                        let entry = model.remove(p).unwrap();
+                        model.push_back(entry);
+                        entry.1
+                    });
+                    assert_eq!(map.get(&key).copied(), expected);
+                }
+                3 => {
+                    let expected = pos.map(|p| model.remove(p).unwrap().1);
+                    assert_eq!(map.remove(&key), expected);
+                }
+                _ => assert_eq!(map.pop_front(), model.pop_front()),
+            }
+
+            assert_eq!(map.len(), model.len());
+            let expected: Vec<usize> = model.iter().map(|&(k, _)| k).collect();
+            assert_eq!(keys(&map), expected);
+        }
+    }
+}
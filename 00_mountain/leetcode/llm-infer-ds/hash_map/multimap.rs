@@ -0,0 +1,389 @@
+// ==============================================================================
+// MultiMap - 多对多映射 + 反向索引
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - Prefix Sharing: 一个物理 KV block 可以同时属于多个序列（fork / 公共前缀）
+//   - 释放物理块时要知道它属于哪些序列（块 -> 序列），
+//     序列结束时要知道它占着哪些块（序列 -> 块）
+//   - block_table.rs 的 mapping 只有"序列 -> 块"一个方向
+//
+// 【结构】
+//   forward: HashMap<K, Vec<V>>   key   -> 所有 value
+//   reverse: HashMap<V, Vec<K>>   value -> 所有 key（反向索引）
+//   每一对 (k, v) 在两边各出现一次；某个 key / value 的列表删空时，
+//   整个条目也一起删掉，不留空 Vec
+//
+// 【为什么用 Vec 而不是 HashSet 存一边】
+//   一个块被共享的序列数、一个序列占的块数都不大（几个到几百），
+//   线性扫描比再套一层哈希表更快也更省内存；列表内的顺序不保证（删除用 swap_remove）
+//
+// 【用法】
+//   let mut owners = MultiMap::new();        // physical block -> seq id
+//   owners.insert(block, seq);
+//   let freed = owners.remove_value(&seq);   // 序列结束：它引用过的所有块
+//   let seqs = owners.remove_key(&block);    // 强制回收块：所有还引用它的序列
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, HashMap, HashMapTrait};
+
+#[derive(Debug)]
+pub struct MultiMap<K, V, S = DefaultHashBuilder>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+{
+    forward: HashMap<K, Vec<V>, S>, // key -> 所有 value
+    reverse: HashMap<V, Vec<K>, S>, // value -> 所有 key
+    pairs: usize,                   // (k, v) 对的总数
+}
+
+impl<K, V> MultiMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K, V, S> Default for MultiMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self { forward: HashMapTrait::new(4), reverse: HashMapTrait::new(4), pairs: 0 }
+    }
+}
+
+// 从 map[a] 的列表里删掉 b，列表删空时连同 a 一起删掉
+fn detach<A, B, QA, QB, S>(map: &mut HashMap<A, Vec<B>, S>, a: &QA, b: &QB) -> bool
+where
+    A: Hash + Eq + Borrow<QA>,
+    B: Borrow<QB>,
+    QA: Hash + Eq + ?Sized,
+    QB: Eq + ?Sized,
+    S: BuildHasher,
+{
+    let Some(list) = map.get_mut(a) else {
+        return false;
+    };
+    let Some(pos) = list.iter().position(|x| x.borrow() == b) else {
+        return false;
+    };
+    list.swap_remove(pos);
+    if list.is_empty() {
+        map.remove(a);
+    }
+    true
+}
+
+// 把 map[a] 整个摘下来
+fn take_all<A, B, QA, S>(map: &mut HashMap<A, Vec<B>, S>, a: &QA) -> Vec<B>
+where
+    A: Hash + Eq + Borrow<QA>,
+    QA: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    map.remove_entry(a).map_or_else(Vec::new, |(_, list)| list)
+}
+
+impl<K, V, S> MultiMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    // (k, v) 对的总数
+    pub fn len(&self) -> usize {
+        self.pairs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs == 0
+    }
+
+    // 不同 key 的数量
+    pub fn key_count(&self) -> usize {
+        self.forward.len()
+    }
+
+    // 不同 value 的数量
+    pub fn value_count(&self) -> usize {
+        self.reverse.len()
+    }
+
+    // 插入一对 (key, value)；已经存在时什么都不做，返回 false
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        if self.contains(&key, &value) {
+            return false;
+        }
+        self.forward.entry(key.clone()).or_default().push(value.clone());
+        self.reverse.entry(value).or_default().push(key);
+        self.pairs += 1;
+        true
+    }
+
+    // 查找 / 删除与 HashMap 一样接收借用形式（K: Borrow<Q>, V: Borrow<W>）
+    // key 对应的所有 value，不存在时为空
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.forward.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    // value 对应的所有 key（反向查询），不存在时为空
+    pub fn get_keys<W>(&self, value: &W) -> &[K]
+    where
+        V: Borrow<W>,
+        W: Hash + Eq + ?Sized,
+    {
+        self.reverse.get(value).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contains<Q, W>(&self, key: &Q, value: &W) -> bool
+    where
+        K: Borrow<Q>,
+        V: Borrow<W>,
+        Q: Hash + Eq + ?Sized,
+        W: Eq + ?Sized,
+    {
+        self.get_all(key).iter().any(|v| v.borrow() == value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.forward.contains_key(key)
+    }
+
+    pub fn contains_value<W>(&self, value: &W) -> bool
+    where
+        V: Borrow<W>,
+        W: Hash + Eq + ?Sized,
+    {
+        self.reverse.contains_key(value)
+    }
+
+    // 只删一对 (key, value)
+    pub fn remove<Q, W>(&mut self, key: &Q, value: &W) -> bool
+    where
+        K: Borrow<Q>,
+        V: Borrow<W>,
+        Q: Hash + Eq + ?Sized,
+        W: Hash + Eq + ?Sized,
+    {
+        if !detach(&mut self.forward, key, value) {
+            return false;
+        }
+        detach(&mut self.reverse, value, key);
+        self.pairs -= 1;
+        true
+    }
+
+    // 删掉 key 的所有映射，返回它对应的所有 value
+    pub fn remove_key<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let values = take_all(&mut self.forward, key);
+        for value in &values {
+            detach(&mut self.reverse, value, key);
+        }
+        self.pairs -= values.len();
+        values
+    }
+
+    // 删掉 value 的所有映射，返回它对应的所有 key
+    pub fn remove_value<W>(&mut self, value: &W) -> Vec<K>
+    where
+        V: Borrow<W>,
+        W: Hash + Eq + ?Sized,
+    {
+        let keys = take_all(&mut self.reverse, value);
+        for key in &keys {
+            detach(&mut self.forward, key, value);
+        }
+        self.pairs -= keys.len();
+        keys
+    }
+
+    pub fn clear(&mut self) {
+        self.forward.clear();
+        self.reverse.clear();
+        self.pairs = 0;
+    }
+
+    // 遍历所有 (key, value) 对
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.forward
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bench_support::Lcg;
+
+    use super::MultiMap;
+
+    // 两个方向互为逆映射，且 pairs 与实际对数一致、没有空列表
+    fn assert_consistent(map: &MultiMap<usize, usize>) {
+        let mut pairs = 0;
+        for (key, values) in map.forward.iter() {
+            assert!(!values.is_empty());
+            for value in values {
+                assert!(map.get_keys(value).contains(key), "({}, {}) missing in reverse", key, value);
+                pairs += 1;
+            }
+        }
+        let mut reverse_pairs = 0;
+        for (value, keys) in map.reverse.iter() {
+            assert!(!keys.is_empty());
+            for key in keys {
+                assert!(map.get_all(key).contains(value), "({}, {}) missing in forward", key, value);
+                reverse_pairs += 1;
+            }
+        }
+        assert_eq!(pairs, map.len());
+        assert_eq!(reverse_pairs, map.len());
+    }
+
+    fn sorted(mut v: Vec<usize>) -> Vec<usize> {
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn test_insert_and_get_all() {
+        let mut map = MultiMap::new();
+        assert!(map.insert(1, 10));
+        assert!(map.insert(1, 11));
+        assert!(map.insert(2, 10));
+        assert!(!map.insert(1, 10));
+
+        assert_eq!(sorted(map.get_all(&1).to_vec()), vec![10, 11]);
+        assert_eq!(sorted(map.get_keys(&10).to_vec()), vec![1, 2]);
+        assert!(map.get_all(&3).is_empty());
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.key_count(), 2);
+        assert_eq!(map.value_count(), 2);
+        assert_consistent(&map);
+    }
+
+    #[test]
+    fn test_shared_blocks_after_fork() {
+        // 块 -> 序列：序列 0 占块 0..4，fork 出序列 1、2 共享前 3 块，各自再有一块
+        let mut owners = MultiMap::new();
+        for block in 0..4 {
+            owners.insert(block, 0);
+        }
+        for seq in [1, 2] {
+            for block in 0..3 {
+                owners.insert(block, seq);
+            }
+            owners.insert(3 + seq, seq);
+        }
+        assert_eq!(sorted(owners.get_all(&0).to_vec()), vec![0, 1, 2]);
+
+        // 序列 0 结束：它引用的块里只有块 3 不再被任何序列引用
+        let touched = owners.remove_value(&0);
+        assert_eq!(sorted(touched.clone()), vec![0, 1, 2, 3]);
+        let freed: Vec<usize> = touched.into_iter().filter(|b| !owners.contains_key(b)).collect();
+        assert_eq!(freed, vec![3]);
+        assert_eq!(sorted(owners.get_all(&0).to_vec()), vec![1, 2]);
+        assert_consistent(&owners);
+
+        // 强制回收块 1：返回所有还引用它的序列
+        assert_eq!(sorted(owners.remove_key(&1)), vec![1, 2]);
+        assert!(!owners.contains(&1, &1));
+        assert_eq!(sorted(owners.get_keys(&1).to_vec()), vec![0, 2, 4]);
+        assert_consistent(&owners);
+    }
+
+    #[test]
+    fn test_remove_single_pair_and_missing() {
+        let mut map = MultiMap::new();
+        map.insert(1, 10);
+        map.insert(1, 11);
+        assert!(map.remove(&1, &10));
+        assert!(!map.remove(&1, &10));
+        assert!(!map.contains_value(&10));
+        assert!(map.remove(&1, &11));
+        assert!(!map.contains_key(&1));
+        assert!(map.is_empty());
+
+        assert!(map.remove_key(&1).is_empty());
+        assert!(map.remove_value(&10).is_empty());
+        assert_consistent(&map);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        // 序列名 <-> 块 id：两个方向都可以用借用形式查询和删除
+        let mut map: MultiMap<String, usize> = MultiMap::new();
+        map.insert("seq-a".to_string(), 1);
+        map.insert("seq-a".to_string(), 2);
+        map.insert("seq-b".to_string(), 2);
+        assert_eq!(sorted(map.get_all("seq-a").to_vec()), vec![1, 2]);
+        assert!(map.contains("seq-b", &2));
+        assert!(map.remove("seq-a", &1));
+        assert_eq!(map.remove_key("seq-b"), vec![2]);
+        assert_eq!(map.get_keys(&2), ["seq-a".to_string()]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.value_count(), 1);
+    }
+
+    #[test]
+    fn test_many_to_many_churn() {
+        // 与 HashSet<(k, v)> 模型对照
+        let mut map = MultiMap::new();
+        let mut model: HashSet<(usize, usize)> = HashSet::new();
+        let mut rng = Lcg::new(5);
+        for step in 0..10_000 {
+            let k = rng.below(40);
+            let v = rng.below(40);
+            match step % 10 {
+                0 => {
+                    let expected: Vec<usize> = model.iter().filter(|p| p.0 == k).map(|p| p.1).collect();
+                    model.retain(|p| p.0 != k);
+                    assert_eq!(sorted(map.remove_key(&k)), sorted(expected));
+                }
+                1 => {
+                    let expected: Vec<usize> = model.iter().filter(|p| p.1 == v).map(|p| p.0).collect();
+                    model.retain(|p| p.1 != v);
+                    assert_eq!(sorted(map.remove_value(&v)), sorted(expected));
+                }
+                2 | 3 => assert_eq!(map.remove(&k, &v), model.remove(&(k, v))),
+                _ => assert_eq!(map.insert(k, v), model.insert((k, v))),
+            }
+            if step % 500 == 0 {
+                assert_consistent(&map);
+            }
+        }
+
+        assert_consistent(&map);
+        assert_eq!(map.len(), model.len());
+        let pairs: HashSet<(usize, usize)> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(pairs, model);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.key_count(), 0);
+        assert_consistent(&map);
+    }
+}
@@ -0,0 +1,253 @@
+// ==============================================================================
+// 扩缩容策略 - ResizePolicy
+// ==============================================================================
+//
+// 【为什么可配置】
+//   - 默认规则：负载因子 > 0.75 扩容为 2 倍，< 0.25 缩容为 1/2，最少 4 个桶
+//   - 块表的规模往往在启动时就知道（显存块总数固定），可以一次 reserve 到位
+//   - 序列频繁结束又开始时，缩容后马上又要扩容，白白 rehash 两次，
+//     这种场景下干脆关掉缩容
+//
+// 【接口】
+//   should_grow / grow:     插入后是否扩容、扩到多少
+//   should_shrink / shrink: 删除后是否缩容、缩到多少
+//   min_buckets:            桶数量下限
+//
+// 【用法】
+//   let map = HashMap::with_capacity_and_policy(1024, LoadFactorPolicy::default().no_shrink());
+//   map.reserve(num_blocks);
+// ==============================================================================
+
+pub trait ResizePolicy {
+    // 桶数量下限，resize 不会低于它
+    fn min_buckets(&self) -> usize;
+
+    // 表中有 count 个元素、buckets 个桶时是否需要扩容
+    fn should_grow(&self, count: usize, buckets: usize) -> bool;
+
+    // 扩容后的桶数量，必须大于 buckets
+    fn grow(&self, buckets: usize) -> usize;
+
+    // 表中有 count 个元素、buckets 个桶时是否需要缩容
+    fn should_shrink(&self, count: usize, buckets: usize) -> bool;
+
+    // 缩容后的桶数量，必须小于 buckets
+    fn shrink(&self, buckets: usize) -> usize;
+}
+
+// 按负载因子扩缩容，Default 与改造前硬编码的行为完全相同
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadFactorPolicy {
+    grow_above: f64,      // 负载因子超过它就扩容
+    shrink_below: f64,    // 负载因子低于它就缩容，0 表示从不缩容
+    factor: usize,        // 扩容乘以 factor，缩容除以 factor
+    min_buckets: usize,   // 桶数量下限
+}
+
+impl LoadFactorPolicy {
+    pub fn new(grow_above: f64, shrink_below: f64, factor: usize, min_buckets: usize) -> Self {
+        Self::try_new(grow_above, shrink_below, factor, min_buckets).unwrap_or_else(|msg| panic!("{}", msg))
+    }
+
+    // 参数不合法时返回原因而不是 panic（从快照恢复策略时使用）
+    pub fn try_new(grow_above: f64, shrink_below: f64, factor: usize, min_buckets: usize) -> Result<Self, &'static str> {
+        if grow_above.is_nan() || grow_above <= 0.0 {
+            return Err("grow threshold must be positive");
+        }
+        if factor < 2 {
+            return Err("resize factor must be at least 2");
+        }
+        if min_buckets < 1 {
+            return Err("at least one bucket is required");
+        }
+        // 缩容后负载因子变为原来的 factor 倍，不能因此立刻又触发扩容
+        if shrink_below.is_nan() || shrink_below < 0.0 || shrink_below * factor as f64 > grow_above {
+            return Err("shrink threshold too close to grow threshold");
+        }
+        Ok(Self { grow_above, shrink_below, factor, min_buckets })
+    }
+
+    // 关闭缩容：删除元素后桶数量保持不变
+    pub fn no_shrink(self) -> Self {
+        Self { shrink_below: 0.0, ..self }
+    }
+
+    pub fn grow_above(&self) -> f64 {
+        self.grow_above
+    }
+
+    pub fn shrink_below(&self) -> f64 {
+        self.shrink_below
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+}
+
+impl Default for LoadFactorPolicy {
+    fn default() -> Self {
+        Self::new(0.75, 0.25, 2, 4)
+    }
+}
+
+impl ResizePolicy for LoadFactorPolicy {
+    fn min_buckets(&self) -> usize {
+        self.min_buckets
+    }
+
+    fn should_grow(&self, count: usize, buckets: usize) -> bool {
+        (count as f64) / (buckets as f64) > self.grow_above
+    }
+
+    fn grow(&self, buckets: usize) -> usize {
+        buckets.saturating_mul(self.factor).max(self.min_buckets)
+    }
+
+    fn should_shrink(&self, count: usize, buckets: usize) -> bool {
+        buckets > self.min_buckets && (count as f64 / buckets as f64) < self.shrink_below
+    }
+
+    fn shrink(&self, buckets: usize) -> usize {
+        (buckets / self.factor).max(self.min_buckets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadFactorPolicy, ResizePolicy};
+    use crate::HashMap;
+
+    #[test]
+    fn test_default_policy_matches_hardcoded_rules() {
+        let policy = LoadFactorPolicy::default();
+        assert!(!policy.should_grow(3, 4));
+        assert!(policy.should_grow(4, 4));
+        assert_eq!(policy.grow(4), 8);
+        assert!(policy.should_shrink(7, 32));
+        assert!(!policy.should_shrink(8, 32));
+        assert!(!policy.should_shrink(0, 4));
+        assert_eq!(policy.shrink(32), 16);
+        assert_eq!(policy.min_buckets(), 4);
+    }
+
+    #[test]
+    fn test_no_shrink_keeps_buckets() {
+        let mut map = HashMap::with_capacity_and_policy(4, LoadFactorPolicy::default().no_shrink());
+        for block in 0..100 {
+            map.put(block, block);
+        }
+        let grown = map.bucket_count();
+        for block in 0..100 {
+            assert!(map.remove(&block));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.bucket_count(), grown);
+    }
+
+    #[test]
+    fn test_custom_thresholds_and_factor() {
+        // 负载因子超过 1.0 才扩容，每次扩为 4 倍，最少 8 个桶
+        let policy = LoadFactorPolicy::new(1.0, 0.1, 4, 8);
+        let mut map = HashMap::with_capacity_and_policy(8, policy);
+        for block in 0..8 {
+            map.put(block, block);
+        }
+        assert_eq!(map.bucket_count(), 8);
+        map.put(8, 8);
+        assert_eq!(map.bucket_count(), 32);
+
+        // 9 / 32 > 0.1；删到 3 个时 3 / 32 < 0.1，缩为 8
+        for block in 0..6 {
+            map.remove(&block);
+        }
+        assert_eq!(map.bucket_count(), 8);
+        for block in 6..9 {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+    }
+
+    // 一个不基于负载因子的策略：桶数量固定，从不扩缩容
+    #[derive(Default)]
+    struct FixedBuckets;
+
+    impl ResizePolicy for FixedBuckets {
+        fn min_buckets(&self) -> usize {
+            16
+        }
+        fn should_grow(&self, _count: usize, _buckets: usize) -> bool {
+            false
+        }
+        fn grow(&self, buckets: usize) -> usize {
+            buckets + 1
+        }
+        fn should_shrink(&self, _count: usize, _buckets: usize) -> bool {
+            false
+        }
+        fn shrink(&self, buckets: usize) -> usize {
+            buckets - 1
+        }
+    }
+
+    #[test]
+    fn test_custom_policy_type() {
+        let mut map: HashMap<usize, usize, _, FixedBuckets> =
+            HashMap::with_capacity_and_policy(16, FixedBuckets);
+        for block in 0..200 {
+            map.put(block, block);
+        }
+        assert_eq!(map.bucket_count(), 16);
+
+        // resize_to 不会低于策略的下限
+        assert_eq!(map.resize_to(2), 16);
+        assert_eq!(map.bucket_count(), 16);
+        assert_eq!(map.get(&199), Some(&199));
+    }
+
+    #[test]
+    fn test_reserve_avoids_growth() {
+        let mut map = HashMap::new(4);
+        map.reserve(1000);
+        // 1000 / 1024 > 0.75，需要 2048 个桶
+        assert_eq!(map.bucket_count(), 2048);
+
+        for block in 0..1000 {
+            map.put(block, block);
+        }
+        assert_eq!(map.bucket_count(), 2048);
+        assert_eq!(map.stats().grow_count, 1);
+
+        // 已经放得下时 reserve 不做任何事
+        map.reserve(0);
+        assert_eq!(map.bucket_count(), 2048);
+    }
+
+    #[test]
+    fn test_shrink_to_fit_and_resize_to() {
+        let mut map = HashMap::with_capacity_and_policy(4, LoadFactorPolicy::default().no_shrink());
+        for block in 0..1000 {
+            map.put(block, block);
+        }
+        for block in 7..1000 {
+            map.remove(&block);
+        }
+        assert_eq!(map.bucket_count(), 2048);
+
+        // 7 个元素：4 -> 8 -> 16 才满足 7 / 16 <= 0.75
+        map.shrink_to_fit();
+        assert_eq!(map.bucket_count(), 16);
+
+        // 太小的目标会被抬到放得下现有元素的大小
+        assert_eq!(map.resize_to(1), 16);
+        assert_eq!(map.resize_to(100), 100);
+        for block in 0..7 {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "shrink threshold too close")]
+    fn test_rejects_thrashing_thresholds() {
+        LoadFactorPolicy::new(0.75, 0.5, 2, 4);
+    }
+}
@@ -0,0 +1,306 @@
+// ==============================================================================
+// 渐进式 Rehash - 消除扩容时的延迟尖刺
+// ==============================================================================
+//
+// 【问题】
+//   resize 一次性把所有元素搬到新桶，100 万个 block 就是一次几毫秒的停顿，
+//   而扩容恰恰发生在显存压力最大、调度器最忙的时候
+//
+// 【做法（参考 Redis dict）】
+//   1. resize 时只换上新桶数组，旧桶数组原样挂在 rehash.old_buckets 上
+//   2. 之后每次写操作（put / remove / entry）顺手搬 rehash_step 个旧桶，
+//      游标 cursor 之前的旧桶都已经搬空
+//   3. 写操作还会先把"自己这个 key 所在的旧桶"搬过去，
+//      这样 put / remove / entry 永远只需要操作新表
+//   4. 读操作（get）不能修改表，所以同时查新表和旧表
+//   5. 旧桶全部搬完后丢掉旧数组，回到普通状态
+//
+// 【不变式】
+//   - 任意 key 只存在于新表或旧表其中之一
+//   - count 是两张表的元素总数，size 是新表的桶数量
+//   - 渐进式 rehash 进行中再次触发 resize：先把上一轮搬完，再开始新一轮
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::mem;
+
+use crate::HashMap;
+
+#[derive(Debug)]
+pub(crate) struct Rehash<K, V> {
+    pub(crate) old_buckets: Vec<Vec<(K, V)>>, // 旧桶数组，长度就是旧的 size
+    cursor: usize,                            // 下一个要搬的旧桶
+}
+
+impl<K, V> Rehash<K, V> {
+    pub(crate) fn new(old_buckets: Vec<Vec<(K, V)>>) -> Self {
+        Self { old_buckets, cursor: 0 }
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    pub fn is_rehashing(&self) -> bool {
+        self.rehash.is_some()
+    }
+
+    // 还没搬完的旧桶（没有进行中的 rehash 时为空切片）
+    pub(crate) fn old_buckets(&self) -> &[Vec<(K, V)>] {
+        match &self.rehash {
+            Some(rehash) => &rehash.old_buckets,
+            None => &[],
+        }
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    // 开启渐进式 rehash：之后每次写操作最多额外搬 buckets_per_op 个旧桶
+    pub fn enable_incremental_rehash(&mut self, buckets_per_op: usize) {
+        self.rehash_step = buckets_per_op.max(1);
+    }
+
+    // 关闭渐进式 rehash：正在进行的迁移一次性做完，之后 resize 恢复为一次性搬家
+    pub fn disable_incremental_rehash(&mut self) {
+        self.finish_rehash();
+        self.rehash_step = 0;
+    }
+
+    // 写操作的前置步骤：先搬 key 所在的旧桶，再按步长推进游标
+    pub(crate) fn prepare_write<Q>(&mut self, key: &Q)
+    where
+        Q: Hash + ?Sized,
+    {
+        if self.rehash.is_none() {
+            return;
+        }
+        if let Some(old_len) = self.rehash.as_ref().map(|r| r.old_buckets.len()) {
+            let old_index = (self.hash_builder.hash_one(key) as usize) % old_len;
+            self.migrate_old_bucket(old_index);
+        }
+        self.advance_rehash();
+    }
+
+    // 读操作：key 可能还留在旧表里
+    pub(crate) fn get_from_old<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let rehash = self.rehash.as_ref()?;
+        let old_index = (self.hash_builder.hash_one(key) as usize) % rehash.old_buckets.len();
+        rehash.old_buckets[old_index]
+            .iter()
+            .find(|pair| pair.0.borrow() == key)
+            .map(|pair| &pair.1)
+    }
+
+    // 一次性搬完剩下的旧桶
+    pub(crate) fn finish_rehash(&mut self) {
+        let Some(rehash) = self.rehash.take() else {
+            return;
+        };
+        for bucket in rehash.old_buckets.into_iter().skip(rehash.cursor) {
+            self.insert_moved(bucket);
+        }
+    }
+
+    // 按步长推进游标，搬完最后一个旧桶时结束本轮 rehash
+    fn advance_rehash(&mut self) {
+        let Some(rehash) = &mut self.rehash else {
+            return;
+        };
+
+        let start = rehash.cursor;
+        let end = (start + self.rehash_step).min(rehash.old_buckets.len());
+        rehash.cursor = end;
+        let done = end == rehash.old_buckets.len();
+
+        for old_index in start..end {
+            self.migrate_old_bucket(old_index);
+        }
+        if done {
+            self.rehash = None;
+        }
+    }
+
+    // 把第 old_index 个旧桶整体搬进新表。
+    // 可以乱序调用（prepare_write 会提前搬某个桶），搬过的桶变成空桶，再搬一次是空操作
+    fn migrate_old_bucket(&mut self, old_index: usize) {
+        let bucket = match &mut self.rehash {
+            Some(rehash) => mem::take(&mut rehash.old_buckets[old_index]),
+            None => return,
+        };
+        self.insert_moved(bucket);
+    }
+
+    // 旧桶里的元素在新表中一定不存在，直接按新的 size 放进去
+    fn insert_moved(&mut self, bucket: Vec<(K, V)>) {
+        for (key, value) in bucket {
+            let new_index = (self.hash_builder.hash_one(&key) as usize) % self.size;
+            self.buckets[new_index].push((key, value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::churn_against_std;
+    use crate::{Entry, FixedSeedState, HashMap};
+
+    fn old_len(map: &HashMap<usize, usize, FixedSeedState>) -> usize {
+        map.old_buckets().iter().map(|bucket| bucket.len()).sum()
+    }
+
+    fn incremental_map(step: usize) -> HashMap<usize, usize, FixedSeedState> {
+        let mut map = HashMap::with_capacity_and_hasher(4, FixedSeedState::new(3));
+        map.enable_incremental_rehash(step);
+        map
+    }
+
+    #[test]
+    fn test_growth_is_spread_over_later_writes() {
+        let mut map = incremental_map(1);
+        for block in 0..3 {
+            map.put(block, block);
+        }
+        assert!(!map.is_rehashing());
+
+        // 第 4 个元素触发扩容：只换上新桶，旧数据还留在旧表
+        map.put(3, 3);
+        assert_eq!(map.size, 8);
+        assert!(map.is_rehashing());
+        assert_eq!(map.len(), 4);
+
+        // 旧表里的数据依然可以读到
+        for block in 0..4 {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+
+        // 旧表只有 4 个桶，每次写操作至少推进 1 个，最多 4 次写后必然结束
+        // （覆盖已有 key 也算写操作）
+        for block in 0..4 {
+            map.put(block, block * 10);
+        }
+        assert!(!map.is_rehashing());
+        assert_eq!(old_len(&map), 0);
+        assert_eq!(map.len(), 4);
+        for block in 0..4 {
+            assert_eq!(map.get(&block), Some(&(block * 10)));
+        }
+    }
+
+    #[test]
+    fn test_each_write_moves_a_bounded_amount() {
+        let mut map = incremental_map(2);
+        for block in 0..1000 {
+            map.put(block, block);
+        }
+        let mut writes = 0;
+        while !map.is_rehashing() {
+            map.put(10_000 + writes, 0);
+            writes += 1;
+        }
+
+        // 每次写最多搬 step 个旧桶 + key 自己所在的 1 个旧桶
+        let max_chain = map.old_buckets().iter().map(|b| b.len()).max().unwrap_or(0);
+        while map.is_rehashing() {
+            let before = old_len(&map);
+            map.put(20_000 + writes, 0);
+            writes += 1;
+            assert!(before - old_len(&map) <= 3 * max_chain);
+        }
+    }
+
+    #[test]
+    fn test_matches_std_model_under_churn() {
+        // 插入 / 删除交替，反复触发扩容与缩容，每一步都与 std HashMap 对照
+        let mut map = incremental_map(1);
+        let model = churn_against_std(&mut map, 12345, 20_000, 2000, |_, _| {});
+        assert_eq!(map.iter().count(), model.len());
+    }
+
+    #[test]
+    fn test_shrink_while_rehashing_finishes_previous_round() {
+        let mut map = incremental_map(1);
+        for block in 0..13 {
+            map.put(block, block);
+        }
+        // 第 13 个元素触发 16 -> 32
+        assert!(map.is_rehashing());
+        assert_eq!(map.size, 32);
+
+        // 删到 7 个时触发缩容：上一轮先搬完，然后开始 32 -> 16
+        for block in 0..6 {
+            map.remove(&block);
+        }
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.size, 16);
+        for block in 6..13 {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+    }
+
+    #[test]
+    fn test_iter_entry_retain_drain_during_rehash() {
+        let mut map = incremental_map(1);
+        for block in 0..13 {
+            map.put(block, block);
+        }
+        assert!(map.is_rehashing());
+
+        // iter 同时覆盖新旧两张表
+        let mut keys: Vec<usize> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..13).collect::<Vec<_>>());
+        for value in map.values_mut() {
+            *value += 1;
+        }
+
+        // entry 会先把 key 所在的旧桶搬过来，所以不会插入重复的 key
+        match map.entry(5) {
+            Entry::Occupied(mut entry) => *entry.get_mut() += 100,
+            Entry::Vacant(_) => panic!("key 5 should exist"),
+        }
+        assert_eq!(map.get(&5), Some(&106));
+        assert_eq!(map.len(), 13);
+
+        map.retain(|block, _| block % 2 == 0);
+        assert_eq!(map.len(), 7);
+        assert!(map.keys().all(|block| block % 2 == 0));
+
+        let mut drained: Vec<usize> = map.drain().map(|(block, _)| block).collect();
+        drained.sort();
+        assert_eq!(drained, vec![0, 2, 4, 6, 8, 10, 12]);
+        assert!(map.is_empty());
+        assert!(!map.is_rehashing());
+    }
+
+    #[test]
+    fn test_disable_finishes_pending_migration() {
+        let mut map = incremental_map(1);
+        for block in 0..13 {
+            map.put(block, block);
+        }
+        assert!(map.is_rehashing());
+
+        map.disable_incremental_rehash();
+        assert!(!map.is_rehashing());
+        for block in 0..13 {
+            assert_eq!(map.get(&block), Some(&block));
+        }
+
+        // 关闭后 resize 恢复为一次性完成
+        for block in 13..30 {
+            map.put(block, block);
+        }
+        assert!(!map.is_rehashing());
+        assert_eq!(map.size, 64);
+    }
+}
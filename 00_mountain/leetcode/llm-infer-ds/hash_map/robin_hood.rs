@@ -0,0 +1,359 @@
+// ==============================================================================
+// Robin Hood Hash Map - 开放寻址版 Page Table
+// ==============================================================================
+//
+// 【为什么需要第二种实现】
+//   - 链地址法 Vec<Vec<(K, V)>> 每次查找都要跳进桶自己的堆分配（一次指针追逐）
+//   - 开放寻址把所有条目放进同一个连续数组，查找只是在数组里向后线性探测
+//   - Page Table 每个调度步要做上百万次查找，cache 命中率决定吞吐
+//
+// 【学习重点】
+//   1. 线性探测 + Robin Hood 置换：插入时"劫富济贫"，探测距离（PSL）短的
+//      条目让位给探测距离长的条目，使所有条目的探测距离趋于平均
+//   2. 提前终止：查找时一旦遇到 PSL 比当前探测距离还小的槽位，目标必然不存在
+//   3. 反向移位删除（Backward-Shift Deletion）：删除后把后继条目逐个前移，
+//      不需要墓碑（tombstone），表不会随删除退化
+//
+// 【与 HashMap 保持一致的行为】
+//   - size 是槽位数量，count 是元素数量
+//   - 负载因子 > 0.75 扩容为 2 倍，< 0.25 缩容为 1/2，最小 4 个槽位
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::mem;
+
+use crate::{DefaultHashBuilder, HashMapTrait};
+
+// 槽位中的条目：除了 key/value 还记录探测距离
+// dist = 当前位置 - 理想位置（Probe Sequence Length）
+#[derive(Debug)]
+struct Slot<K, V> {
+    key: K,
+    value: V,
+    dist: usize,
+}
+
+#[derive(Debug)]
+pub struct RobinHoodMap<K, V, S = DefaultHashBuilder>
+where
+    K: Hash + Eq,
+{
+    slots: Vec<Option<Slot<K, V>>>, // 槽位数组，None 表示空槽
+    size: usize,                    // 槽位数量
+    count: usize,                   // 元素总数
+    hash_builder: S,                // 哈希函数工厂，resize 时复用
+}
+
+impl<K, V> RobinHoodMap<K, V>
+where
+    K: Hash + Eq,
+{
+    // 与 HashMap::new 相同：固定 S = DefaultHashBuilder，保证 RobinHoodMap::new(4) 可以推断
+    pub fn new(initial_size: usize) -> Self {
+        Self::with_capacity_and_hasher(initial_size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> RobinHoodMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(4, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(initial_size: usize, hash_builder: S) -> Self {
+        Self {
+            slots: Self::empty_slots(initial_size),
+            size: initial_size,
+            count: 0,
+            hash_builder,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    // 与 HashMap::empty_buckets 相同，避免 vec![None; n] 带来的 Clone 约束
+    fn empty_slots(n: usize) -> Vec<Option<Slot<K, V>>> {
+        (0..n).map(|_| None).collect()
+    }
+}
+
+impl<K, V, S> RobinHoodMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    // 查找 key 所在的槽位下标
+    fn find_index<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut index = self.hash_function(key);
+        let mut dist = 0;
+
+        loop {
+            match &self.slots[index] {
+                // 1. 空槽：key 不存在
+                None => return None,
+                Some(slot) => {
+                    // 2. 提前终止：如果 key 存在，它在插入时一定会把这个"更富"的条目挤走
+                    if slot.dist < dist {
+                        return None;
+                    }
+                    if slot.key.borrow() == key {
+                        return Some(index);
+                    }
+                }
+            }
+
+            index = (index + 1) % self.size;
+            dist += 1;
+        }
+    }
+
+    // 把一个确定不存在于表中的条目放进去（调用方保证至少还有一个空槽）
+    fn insert_slot(&mut self, key: K, value: V) {
+        let mut index = self.hash_function(&key);
+        let mut current = Slot { key, value, dist: 0 };
+
+        loop {
+            match &mut self.slots[index] {
+                // 1. 找到空槽，直接落户
+                None => {
+                    self.slots[index] = Some(current);
+                    return;
+                }
+                // 2. Robin Hood：对方离家更近（更富），就把位置抢过来，
+                //    然后带着被挤出来的条目继续向后找
+                Some(existing) => {
+                    if existing.dist < current.dist {
+                        mem::swap(existing, &mut current);
+                    }
+                }
+            }
+
+            index = (index + 1) % self.size;
+            current.dist += 1;
+        }
+    }
+}
+
+// 核心操作只要求 S: BuildHasher，没有 Default 的哈希函数也能用（与 HashMap 相同）
+impl<K, V, S> RobinHoodMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        (self.hash_builder.hash_one(key) as usize) % self.size
+    }
+
+    pub fn resize(&mut self, new_size: usize) {
+        // 1. 开放寻址的槽位数必须严格大于元素数，否则探测找不到空槽
+        let new_size = new_size.max(4).max(self.count + 1);
+        if new_size == self.size {
+            return;
+        }
+
+        // 2. 换上新的空槽数组，旧数据的所有权转移到 old_slots
+        let old_slots = mem::replace(&mut self.slots, Self::empty_slots(new_size));
+        self.size = new_size;
+
+        // 3. 重新插入，探测距离在新表中从 0 开始重新计算
+        for slot in old_slots.into_iter().flatten() {
+            self.insert_slot(slot.key, slot.value);
+        }
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        // 1. 已存在则原地更新
+        if let Some(index) = self.find_index(&key) {
+            if let Some(slot) = &mut self.slots[index] {
+                slot.value = value;
+            }
+            return;
+        }
+
+        // 2. 扩容检查放在插入之前：开放寻址不能先塞满再扩容
+        //    (count + 1) / size > 0.75 与 HashMap 插入后检查的时机完全一致
+        if ((self.count + 1) as f64) / (self.size as f64) > 0.75 {
+            self.resize(self.size * 2);
+        }
+
+        // 3. 插入新元素
+        self.insert_slot(key, value);
+        self.count += 1;
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find_index(key)?;
+        self.slots[index].as_ref().map(|slot| &slot.value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // 1. 找到要删除的槽位
+        let Some(mut hole) = self.find_index(key) else {
+            return false;
+        };
+        self.slots[hole] = None;
+        self.count -= 1;
+
+        // 2. 反向移位：后继条目只要不在理想位置（dist > 0），就前移一格填洞
+        //    遇到空槽或 dist == 0 的条目即停止
+        let mut next = (hole + 1) % self.size;
+        while let Some(mut slot) = self.slots[next].take() {
+            if slot.dist == 0 {
+                self.slots[next] = Some(slot);
+                break;
+            }
+            slot.dist -= 1;
+            self.slots[hole] = Some(slot);
+            hole = next;
+            next = (next + 1) % self.size;
+        }
+
+        // 3. 缩容逻辑与 HashMap 相同
+        if self.size > 4 && (self.count as f64 / self.size as f64) < 0.25 {
+            let new_size = self.size / 2;
+            self.resize(new_size);
+        }
+
+        true
+    }
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_index(key).is_some()
+    }
+}
+
+// 与 HashMap 相同：trait 的 new 需要 S: Default，其余方法转发到上面的固有方法
+impl<K, V, S> HashMapTrait<K, V> for RobinHoodMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn new(initial_size: usize) -> Self {
+        Self::with_capacity_and_hasher(initial_size, S::default())
+    }
+
+    fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        RobinHoodMap::hash_function(self, key)
+    }
+
+    fn resize(&mut self, new_size: usize) {
+        RobinHoodMap::resize(self, new_size)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        RobinHoodMap::put(self, key, value)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        RobinHoodMap::get(self, key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        RobinHoodMap::remove(self, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 扩缩容用例与 HashMap 共用（test_util.rs），这里只放 Robin Hood 特有的检查
+    use super::RobinHoodMap as HashMap;
+    use crate::test_util::{check_resize_semantics, Inspect, Seeded};
+
+    impl Inspect for HashMap<usize, usize> {
+        fn bucket_count(&self) -> usize {
+            self.size
+        }
+
+        fn element_count(&self) -> usize {
+            self.count
+        }
+    }
+
+    #[test]
+    fn test_resize_semantics() {
+        check_resize_semantics::<HashMap<usize, usize>>();
+    }
+
+    #[test]
+    fn test_backward_shift_keeps_probe_chains() {
+        // 大量交错插入/删除后，剩下的每个 key 仍然能被找到，
+        // 且每个条目的 dist 都与它的实际位置一致（没有因删除留下断链）
+        let mut map = HashMap::new(4);
+        for i in 0..500 {
+            map.put(i, i);
+        }
+        for i in (0..500).step_by(3) {
+            assert!(map.remove(&i));
+        }
+
+        for i in 0..500 {
+            let expected = if i % 3 == 0 { None } else { Some(&i) };
+            assert_eq!(map.get(&i), expected);
+        }
+
+        for (index, slot) in map.slots.iter().enumerate() {
+            if let Some(slot) = slot {
+                let home = map.hash_function(&slot.key);
+                assert_eq!((home + slot.dist) % map.size, index);
+            }
+        }
+    }
+
+    #[test]
+    fn test_borrowed_lookup_and_non_clone_values() {
+        let mut map: HashMap<String, Box<dyn Fn(i32) -> i32>> = HashMap::new(4);
+        for i in 0..10 {
+            map.put(format!("k{}", i), Box::new(move |x| x + i));
+        }
+
+        assert_eq!(map.get("k3").map(|f| f(1)), Some(4));
+        assert!(map.contains_key("k9"));
+        assert!(map.remove("k9"));
+        assert!(!map.contains_key("k9"));
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        // Seeded 没有 Default，核心操作仍然可用
+        let mut map: HashMap<String, usize, Seeded> = HashMap::with_hasher(Seeded(7));
+        for i in 0..100 {
+            map.put(format!("block-{}", i), i);
+        }
+        assert!(map.size > 4);
+        assert_eq!(map.get("block-42"), Some(&42));
+        assert!(map.remove("block-0"));
+        assert!(!map.contains_key("block-0"));
+        map.resize(512);
+        assert_eq!(map.size, 512);
+        assert_eq!(map.get("block-99"), Some(&99));
+    }
+}
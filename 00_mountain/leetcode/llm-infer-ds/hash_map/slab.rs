@@ -0,0 +1,464 @@
+// ==============================================================================
+// Slab Hash Map - 条目连续存放的链地址法
+// ==============================================================================
+//
+// 【为什么需要】
+//   - HashMap 的 Vec<Vec<(K, V)>>：每个非空桶各自一次堆分配，
+//     test_large_volume 插入 1000 个 key 就有上百次分配，条目散落在堆上
+//   - 这里仍然是链地址法，但链表不再是"每个桶一个 Vec"，而是：
+//       heads:   Vec<usize>          桶数组，存链表头在 entries 中的下标
+//       entries: Vec<SlabEntry>      所有条目连续存放（slab），next 串成链
+//   - 分配只发生在两个 Vec 扩容时，都是翻倍增长：插入 n 个元素只有 O(log n) 次分配
+//
+// 【学习重点】
+//   1. 下标代替指针：next 存的是 entries 的下标，NIL 表示链尾
+//   2. 删除用 swap_remove：把最后一个条目搬进空位，slab 始终没有空洞，
+//      代价是要把指向"最后一个条目"的那个链接改成新下标
+//   3. 条目里缓存完整的哈希值：resize 只重建 heads 并重新串链，
+//      不移动任何条目，也不再调用一次哈希函数
+//
+// 【与 HashMap 保持一致的行为】
+//   - size 是桶数量，count 是元素数量
+//   - 扩缩容由 P: ResizePolicy 决定，默认 > 0.75 扩容 / < 0.25 缩容，最少 4 个桶
+//   - 缩容时 slab 的容量也一起收缩，删空之后不会一直占着峰值内存
+// ==============================================================================
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, HashMapTrait, LoadFactorPolicy, ResizePolicy};
+
+// 空链接
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct SlabEntry<K, V> {
+    key: K,
+    value: V,
+    hash: u64,   // 完整哈希值，resize 时直接取模
+    next: usize, // 同一个桶里的下一个条目，NIL 表示链尾
+}
+
+#[derive(Debug)]
+pub struct SlabHashMap<K, V, S = DefaultHashBuilder, P = LoadFactorPolicy>
+where
+    K: Hash + Eq,
+{
+    heads: Vec<usize>,             // 桶数组：链表头在 entries 中的下标
+    entries: Vec<SlabEntry<K, V>>, // slab：所有条目连续存放，没有空洞
+    size: usize,                   // 桶的数量
+    count: usize,                  // 元素总数（== entries.len()）
+    hash_builder: S,               // 哈希函数工厂
+    policy: P,                     // 扩缩容策略
+}
+
+impl<K, V> SlabHashMap<K, V>
+where
+    K: Hash + Eq,
+{
+    // 与 HashMap::new 相同：固定默认类型参数，保证 SlabHashMap::new(4) 可以推断
+    pub fn new(initial_size: usize) -> Self {
+        Self::with_capacity_and_hasher(initial_size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> SlabHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(4, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(initial_size: usize, hash_builder: S) -> Self {
+        Self::with_capacity_hasher_and_policy(initial_size, hash_builder, LoadFactorPolicy::default())
+    }
+}
+
+impl<K, V, S, P> SlabHashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn with_capacity_hasher_and_policy(initial_size: usize, hash_builder: S, policy: P) -> Self {
+        Self {
+            heads: vec![NIL; initial_size],
+            entries: Vec::new(),
+            size: initial_size,
+            count: 0,
+            hash_builder,
+            policy,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // 按 slab 中的存放顺序遍历（不是插入顺序：删除会把最后一个条目搬进空位）
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, &V)> {
+        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn bucket_of(&self, hash: u64) -> usize {
+        (hash as usize) % self.size
+    }
+
+    // 查找 key，返回 (链表中的前驱下标, 条目下标)，前驱为 NIL 表示它是链表头
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let mut prev = NIL;
+        let mut index = self.heads[self.bucket_of(hash)];
+        while index != NIL {
+            let entry = &self.entries[index];
+            // 先比哈希值，不相等就不必调用 K 的 Eq
+            if entry.hash == hash && entry.key.borrow() == key {
+                return Some((prev, index));
+            }
+            prev = index;
+            index = entry.next;
+        }
+        None
+    }
+
+    // 把指向 from 的那个链接（桶头或前驱的 next）改成 to
+    fn relink(&mut self, from: usize, to: usize) {
+        let bucket = self.bucket_of(self.entries[from].hash);
+        if self.heads[bucket] == from {
+            self.heads[bucket] = to;
+            return;
+        }
+        let mut index = self.heads[bucket];
+        while self.entries[index].next != from {
+            index = self.entries[index].next;
+        }
+        self.entries[index].next = to;
+    }
+
+    // 按当前 size 重建所有链表，条目本身不移动
+    fn rebuild_chains(&mut self) {
+        self.heads.clear();
+        self.heads.resize(self.size, NIL);
+        for index in 0..self.entries.len() {
+            let bucket = self.bucket_of(self.entries[index].hash);
+            self.entries[index].next = self.heads[bucket];
+            self.heads[bucket] = index;
+        }
+    }
+}
+
+impl<K, V, S, P> SlabHashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let (_, index) = self.find(hash, key)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    // 元素数量为 count 时，按缩容规则应有的桶数量（与 HashMap::shrunk_size 相同）
+    fn shrunk_size(&self, count: usize) -> usize {
+        let mut new_size = self.size;
+        while self.policy.should_shrink(count, new_size) {
+            let next = self.policy.shrink(new_size);
+            if next >= new_size {
+                break;
+            }
+            new_size = next;
+        }
+        new_size
+    }
+}
+
+// 核心操作只要求 S: BuildHasher、P: ResizePolicy（与 HashMap 相同）
+impl<K, V, S, P> SlabHashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: ResizePolicy,
+{
+    pub fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        self.bucket_of(self.hash_builder.hash_one(key))
+    }
+
+    pub fn resize(&mut self, new_size: usize) {
+        // 1. 不低于策略的桶数量下限
+        let new_size = new_size.max(self.policy.min_buckets());
+        if new_size == self.size {
+            return;
+        }
+
+        // 2. 缩容时 slab 也还回多余的容量（放得下 new_size 个桶对应的元素就够）
+        if new_size < self.size {
+            self.entries.shrink_to(new_size);
+        }
+
+        // 3. 只重建桶数组：缓存的哈希值对新 size 取模，重新串链
+        self.size = new_size;
+        self.rebuild_chains();
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        // 1. 计算完整哈希值，已存在则原地更新
+        let hash = self.hash_builder.hash_one(&key);
+        if let Some((_, index)) = self.find(hash, &key) {
+            self.entries[index].value = value;
+            return;
+        }
+
+        // 2. 新条目追加到 slab 末尾，挂到桶链表的头部
+        let bucket = self.bucket_of(hash);
+        self.entries.push(SlabEntry { key, value, hash, next: self.heads[bucket] });
+        self.heads[bucket] = self.entries.len() - 1;
+        self.count += 1;
+
+        // 3. 扩容检查，与 HashMap 相同
+        if self.policy.should_grow(self.count, self.size) {
+            self.resize(self.policy.grow(self.size));
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let (_, index) = self.find(hash, key)?;
+        Some(&self.entries[index].value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // 1. 找到条目，把它从桶链表上摘下来
+        let hash = self.hash_builder.hash_one(key);
+        let Some((prev, index)) = self.find(hash, key) else {
+            return false;
+        };
+        let next = self.entries[index].next;
+        if prev == NIL {
+            let bucket = self.bucket_of(hash);
+            self.heads[bucket] = next;
+        } else {
+            self.entries[prev].next = next;
+        }
+
+        // 2. swap_remove：最后一个条目会搬到 index，先把指向它的链接改过来
+        //    （index 已经不在任何链表上，relink 不会走到它）
+        let last = self.entries.len() - 1;
+        if index != last {
+            self.relink(last, index);
+        }
+        self.entries.swap_remove(index);
+        self.count -= 1;
+
+        // 3. 缩容检查，与 HashMap 相同
+        let new_size = self.shrunk_size(self.count);
+        if new_size != self.size {
+            self.resize(new_size);
+        }
+
+        true
+    }
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+// 与 HashMap 相同：trait 的 new 需要 Default，其余方法转发到上面的固有方法
+impl<K, V, S, P> HashMapTrait<K, V> for SlabHashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    P: ResizePolicy + Default,
+{
+    fn new(initial_size: usize) -> Self {
+        Self::with_capacity_hasher_and_policy(initial_size, S::default(), P::default())
+    }
+
+    fn hash_function<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        SlabHashMap::hash_function(self, key)
+    }
+
+    fn resize(&mut self, new_size: usize) {
+        SlabHashMap::resize(self, new_size)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        SlabHashMap::put(self, key, value)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        SlabHashMap::get(self, key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        SlabHashMap::remove(self, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 与 robin_hood.rs 相同：扩缩容用例与 HashMap 共用（test_util.rs）
+    use super::{SlabHashMap as HashMap, NIL};
+    use crate::test_util::{check_resize_semantics, churn_against_std, Inspect, Seeded};
+    use crate::{FixedSeedState, HashMapTrait, IdentityBuildHasher};
+
+    impl<S> Inspect for HashMap<usize, usize, S> {
+        fn bucket_count(&self) -> usize {
+            self.size
+        }
+
+        fn element_count(&self) -> usize {
+            self.count
+        }
+    }
+
+    // 检查所有链表：每个条目恰好出现一次，且都挂在自己的桶上
+    fn assert_chains_consistent<S>(map: &HashMap<usize, usize, S>)
+    where
+        S: std::hash::BuildHasher + Default,
+    {
+        let mut seen = vec![false; map.entries.len()];
+        for (bucket, &head) in map.heads.iter().enumerate() {
+            let mut index = head;
+            while index != NIL {
+                assert!(!seen[index], "entry {} linked twice", index);
+                seen[index] = true;
+                assert_eq!(map.bucket_of(map.entries[index].hash), bucket);
+                index = map.entries[index].next;
+            }
+        }
+        assert!(seen.iter().all(|&s| s), "unlinked entry in slab");
+        assert_eq!(map.entries.len(), map.count);
+    }
+
+    #[test]
+    fn test_resize_semantics() {
+        check_resize_semantics::<HashMap<usize, usize>>();
+    }
+
+    #[test]
+    fn test_swap_remove_keeps_chains() {
+        // identity 哈希 + 8 个桶：key % 8 相同的条目挂在同一条链上，
+        // 删除链中间 / 链头 / slab 最后一个条目都要保持链表完整
+        let mut map: HashMap<usize, usize, IdentityBuildHasher> = HashMapTrait::new(8);
+        for key in [0, 8, 16, 1, 9] {
+            map.put(key, key);
+        }
+        assert_eq!(map.size, 8);
+
+        assert!(map.remove(&8)); // 链中间，slab 最后一个条目 9 搬进来
+        assert_chains_consistent(&map);
+        assert!(map.remove(&16)); // 链头
+        assert_chains_consistent(&map);
+        assert!(map.remove(&9)); // 刚好是 slab 最后一个
+        assert_chains_consistent(&map);
+        assert!(!map.remove(&9));
+
+        assert_eq!(map.get(&0), Some(&0));
+        assert_eq!(map.get(&1), Some(&1));
+        assert_eq!(map.get(&8), None);
+    }
+
+    #[test]
+    fn test_churn_against_std() {
+        // 交错插入 / 覆盖 / 删除，与 std HashMap 对照，每一步后检查链表
+        let mut map = HashMap::with_hasher(FixedSeedState::new(7));
+        let model = churn_against_std(&mut map, 1, 5000, 300, |map, step| {
+            if step % 100 == 0 {
+                assert_chains_consistent(map);
+            }
+        });
+
+        assert_chains_consistent(&map);
+        assert_eq!(map.iter().count(), model.len());
+    }
+
+    #[test]
+    fn test_shrink_releases_slab_capacity() {
+        let mut map = HashMap::new(4);
+        for i in 0..1000 {
+            map.put(i, i);
+        }
+        let peak = map.entries.capacity();
+        for i in 0..995 {
+            map.remove(&i);
+        }
+        assert!(map.entries.capacity() < peak / 8, "capacity {}", map.entries.capacity());
+        assert_eq!(map.get(&999), Some(&999));
+    }
+
+    #[test]
+    fn test_borrowed_lookup_and_non_clone_values() {
+        let mut map: HashMap<String, Box<dyn Fn(i32) -> i32>> = HashMap::new(4);
+        for i in 0..10 {
+            map.put(format!("k{}", i), Box::new(move |x| x + i));
+        }
+
+        assert_eq!(map.get("k3").map(|f| f(1)), Some(4));
+        if let Some(f) = map.get_mut("k3") {
+            *f = Box::new(|x| x * 100);
+        }
+        assert_eq!(map.get("k3").map(|f| f(2)), Some(200));
+        assert!(map.contains_key("k9"));
+        assert!(map.remove("k9"));
+        assert!(!map.contains_key("k9"));
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        // Seeded 没有 Default，核心操作和 get_mut 仍然可用
+        let mut map: HashMap<String, usize, Seeded> = HashMap::with_hasher(Seeded(7));
+        for i in 0..100 {
+            map.put(format!("block-{}", i), i);
+        }
+        assert!(map.bucket_count() > 4);
+        *map.get_mut("block-1").unwrap() += 1;
+        assert_eq!(map.get("block-1"), Some(&2));
+        assert!(map.remove("block-0"));
+        assert!(!map.contains_key("block-0"));
+        map.resize(512);
+        assert_eq!(map.bucket_count(), 512);
+        assert_eq!(map.len(), 99);
+    }
+}
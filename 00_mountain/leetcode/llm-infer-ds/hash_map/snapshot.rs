@@ -0,0 +1,555 @@
+// ==============================================================================
+// Serde 支持与二进制快照 - 保存 / 恢复模拟引擎的 Page Table
+// ==============================================================================
+//
+// 【启用方式】
+//   hash_map = { path = "...", features = ["serde"] }
+//
+// 【序列化格式】
+//   HashMap 序列化为一个结构体，而不是 std 那样的 map：
+//     { "bucket_count": 64, "rehash_step": 0, "policy": {...}, "entries": [[k, v], ...] }
+//   - bucket_count: 恢复后桶数量不变
+//   - rehash_step:  渐进式 rehash 的开关和步长
+//   - policy:       扩缩容策略 P 的参数（P 需要实现 Serialize / Deserialize），
+//                   与 bucket_count 一起保证之后的扩容 / 缩容时机与原表完全一致
+//   - entries:      (key, value) 序列，key 可以是任意类型（JSON 的 map key 只能是字符串）
+//
+// 【二进制快照】
+//   b"HMAP" | 版本号 u8 | bincode(varint 编码) 的上述结构体
+//   小整数 key / value 只占 1 个字节，适合频繁保存实验现场
+//
+// 【注意】
+//   - 哈希函数 S 不会被保存，恢复时使用 S::default()；
+//     S 是 DefaultHashBuilder / FixedSeedState::default() 这类无状态哈希时，
+//     恢复出的表连迭代顺序都与原表相同
+//   - LoadFactorPolicy 恢复时重新校验参数，不合法的参数返回错误而不是 panic
+//   - 渐进式 rehash 进行中的表保存时，旧表里的元素直接恢复到新表中
+//   - bucket_count 来自外部输入，恢复前先检查：超过 SPARSE_BUCKET_LIMIT 时
+//     每个元素最多对应 MAX_BUCKETS_PER_ENTRY 个桶，桶数组也用 try_reserve 分配，
+//     伪造的巨大 bucket_count 返回 SnapshotError，不会 panic / abort
+//   - policy 同理：min_buckets 不能超过同样的上限，下一次扩容最多 MAX_BUCKETS_PER_ENTRY 倍，
+//     否则恢复成功之后的第一次 put / remove 就会去分配巨大的桶数组
+// ==============================================================================
+
+use std::collections::TryReserveError;
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+use bincode::Options;
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{HashMap, LoadFactorPolicy, ResizePolicy};
+
+const SNAPSHOT_MAGIC: &[u8; 4] = b"HMAP";
+const SNAPSHOT_VERSION: u8 = 2; // 版本 1 没有保存 policy
+
+// 桶数量不超过这个值时总是接受（约 24 MB 的空桶）
+const SPARSE_BUCKET_LIMIT: usize = 1 << 20;
+// 超过之后，负载因子不能低于 1 / MAX_BUCKETS_PER_ENTRY
+const MAX_BUCKETS_PER_ENTRY: usize = 16;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    BadMagic,                // 不是 HashMap 快照
+    UnsupportedVersion(u8),  // 快照格式版本不认识
+    InvalidBucketCount,      // bucket_count 为 0，恢复后无法取模
+    TooManyBuckets { bucket_count: usize, entries: usize }, // bucket_count 大得不合理（数据损坏或伪造）
+    PolicyOutOfRange { min_buckets: usize, grown: usize, limit: usize }, // policy 会让之后的扩缩容分配过多的桶
+    Alloc(TryReserveError),  // 桶数组分配失败
+    Codec(bincode::Error),   // bincode 编解码失败（数据截断、类型不匹配等）
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not a HashMap snapshot"),
+            SnapshotError::UnsupportedVersion(v) => write!(f, "unsupported snapshot version {}", v),
+            SnapshotError::InvalidBucketCount => write!(f, "bucket_count must be positive"),
+            SnapshotError::TooManyBuckets { bucket_count, entries } => {
+                write!(f, "bucket_count {} is too large for {} entries", bucket_count, entries)
+            }
+            SnapshotError::PolicyOutOfRange { min_buckets, grown, limit } => write!(
+                f,
+                "resize policy out of range: min_buckets {}, next grow to {} buckets (limit {})",
+                min_buckets, grown, limit
+            ),
+            SnapshotError::Alloc(err) => write!(f, "cannot allocate buckets: {}", err),
+            SnapshotError::Codec(err) => write!(f, "snapshot codec error: {}", err),
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SnapshotError::Codec(err) => Some(err),
+            SnapshotError::Alloc(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(err: bincode::Error) -> Self {
+        SnapshotError::Codec(err)
+    }
+}
+
+// varint 编码：小整数只占 1 个字节
+fn codec() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+// ------------------------------------------------------------------------------
+// Serialize：直接借用 map 中的元素，不克隆、不先收集成 Vec
+// ------------------------------------------------------------------------------
+struct Entries<'a, K, V, S, P>(&'a HashMap<K, V, S, P>)
+where
+    K: Hash + Eq;
+
+impl<K, V, S, P> Serialize for Entries<'_, K, V, S, P>
+where
+    K: Hash + Eq + Serialize,
+    V: Serialize,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+impl<K, V, S, P> Serialize for HashMap<K, V, S, P>
+where
+    K: Hash + Eq + Serialize,
+    V: Serialize,
+    P: Serialize,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut state = serializer.serialize_struct("HashMap", 4)?;
+        state.serialize_field("bucket_count", &self.size)?;
+        state.serialize_field("rehash_step", &self.rehash_step)?;
+        state.serialize_field("policy", &self.policy)?;
+        state.serialize_field("entries", &Entries(self))?;
+        state.end()
+    }
+}
+
+// ------------------------------------------------------------------------------
+// Deserialize：先读成中间结构，再按 bucket_count 重建
+// ------------------------------------------------------------------------------
+#[derive(serde::Deserialize)]
+#[serde(rename = "HashMap")]
+struct Repr<K, V, P> {
+    bucket_count: usize,
+    rehash_step: usize,
+    policy: P,
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    P: ResizePolicy,
+{
+    fn from_repr(repr: Repr<K, V, P>) -> Result<Self, SnapshotError> {
+        // 1. bucket_count 是外部输入：为 0 没法取模，太大会在分配时 abort
+        if repr.bucket_count == 0 {
+            return Err(SnapshotError::InvalidBucketCount);
+        }
+        let entries = repr.entries.len();
+        let limit = entries.saturating_mul(MAX_BUCKETS_PER_ENTRY).max(SPARSE_BUCKET_LIMIT);
+        if repr.bucket_count > limit {
+            return Err(SnapshotError::TooManyBuckets { bucket_count: repr.bucket_count, entries });
+        }
+
+        // 2. policy 也是外部输入：扩容 / 缩容都会取 max(.., min_buckets)，
+        //    min_buckets 受同样的上限；下一次扩容最多放大 MAX_BUCKETS_PER_ENTRY 倍
+        let min_buckets = repr.policy.min_buckets();
+        let grown = repr.policy.grow(repr.bucket_count);
+        let grow_limit = repr.bucket_count.saturating_mul(MAX_BUCKETS_PER_ENTRY).max(min_buckets);
+        if min_buckets > limit || grown > grow_limit {
+            return Err(SnapshotError::PolicyOutOfRange { min_buckets, grown, limit });
+        }
+
+        // 3. 原表在同样的策略下不需要扩容，按原顺序 put 回去也不会触发扩容
+        let mut map = Self::try_with_capacity_hasher_and_policy(repr.bucket_count, S::default(), repr.policy)
+            .map_err(SnapshotError::Alloc)?;
+        map.rehash_step = repr.rehash_step;
+        for (key, value) in repr.entries {
+            map.put(key, value);
+        }
+        Ok(map)
+    }
+}
+
+impl<'de, K, V, S, P> Deserialize<'de> for HashMap<K, V, S, P>
+where
+    K: Hash + Eq + Deserialize<'de>,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+    P: ResizePolicy + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = Repr::deserialize(deserializer)?;
+        Self::from_repr(repr).map_err(de::Error::custom)
+    }
+}
+
+// ------------------------------------------------------------------------------
+// LoadFactorPolicy：保存四个参数，恢复时走 try_new 重新校验
+// ------------------------------------------------------------------------------
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "LoadFactorPolicy")]
+struct PolicyRepr {
+    grow_above: f64,
+    shrink_below: f64,
+    factor: usize,
+    min_buckets: usize,
+}
+
+impl Serialize for LoadFactorPolicy {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        PolicyRepr {
+            grow_above: self.grow_above(),
+            shrink_below: self.shrink_below(),
+            factor: self.factor(),
+            min_buckets: self.min_buckets(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LoadFactorPolicy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PolicyRepr::deserialize(deserializer)?;
+        LoadFactorPolicy::try_new(repr.grow_above, repr.shrink_below, repr.factor, repr.min_buckets)
+            .map_err(de::Error::custom)
+    }
+}
+
+// ------------------------------------------------------------------------------
+// 二进制快照
+// ------------------------------------------------------------------------------
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    // 保存不需要构造哈希函数 / 策略，所以没有 Default 约束
+    pub fn to_snapshot(&self) -> Result<Vec<u8>, SnapshotError>
+    where
+        K: Serialize,
+        V: Serialize,
+        P: Serialize,
+    {
+        let mut bytes = Vec::with_capacity(SNAPSHOT_MAGIC.len() + 1);
+        bytes.extend_from_slice(SNAPSHOT_MAGIC);
+        bytes.push(SNAPSHOT_VERSION);
+        codec().serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    P: ResizePolicy + DeserializeOwned,
+{
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        // 1. 校验文件头
+        let body = bytes
+            .strip_prefix(SNAPSHOT_MAGIC.as_slice())
+            .ok_or(SnapshotError::BadMagic)?;
+        let (&version, body) = body.split_first().ok_or(SnapshotError::BadMagic)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        // 2. 解码后重建
+        let repr: Repr<K, V, P> = codec().deserialize(body)?;
+        Self::from_repr(repr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::Options;
+
+    use super::{codec, SnapshotError};
+    use crate::{FixedSeedState, HashMap, LoadFactorPolicy, ResizePolicy};
+
+    const DEFAULT_POLICY: &str = r#"{"grow_above":0.75,"shrink_below":0.25,"factor":2,"min_buckets":4}"#;
+
+    fn block_table(n: usize) -> HashMap<usize, usize> {
+        let mut map = HashMap::new(4);
+        for block in 0..n {
+            map.put(block, block * 10);
+        }
+        map
+    }
+
+    #[test]
+    fn test_json_round_trip_integer_keys() {
+        let map = block_table(100);
+        let json = serde_json::to_string(&map).unwrap();
+        let restored: HashMap<usize, usize> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.size, map.size);
+        assert_eq!(restored.len(), 100);
+        for block in 0..100 {
+            assert_eq!(restored.get(&block), Some(&(block * 10)));
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip_string_keys() {
+        let mut map: HashMap<String, Vec<u32>> = HashMap::new(16);
+        map.put("seq-0".to_string(), vec![3, 1, 4]);
+        map.put("seq-1".to_string(), vec![]);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert!(json.starts_with(
+            r#"{"bucket_count":16,"rehash_step":0,"policy":{"grow_above":0.75,"shrink_below":0.25,"factor":2,"min_buckets":4},"entries":["#
+        ));
+
+        let restored: HashMap<String, Vec<u32>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.size, 16);
+        assert_eq!(restored.get("seq-0"), Some(&vec![3, 1, 4]));
+        assert_eq!(restored.get("seq-1"), Some(&vec![]));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_integer_keys() {
+        let map = block_table(1000);
+        let bytes = map.to_snapshot().unwrap();
+        assert_eq!(&bytes[..5], b"HMAP\x02");
+
+        // varint：key / value 都小于 2^16，每个最多 3 字节
+        assert!(bytes.len() < 16 + 1000 * 6, "snapshot too large: {}", bytes.len());
+
+        let restored: HashMap<usize, usize> = HashMap::from_snapshot(&bytes).unwrap();
+        assert_eq!(restored.size, map.size);
+        assert_eq!(restored.len(), map.len());
+        for block in 0..1000 {
+            assert_eq!(restored.get(&block), Some(&(block * 10)));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_string_keys() {
+        let mut map: HashMap<String, String> = HashMap::new(4);
+        for i in 0..50 {
+            map.put(format!("block-{}", i), format!("gpu:{}", i % 4));
+        }
+        let bytes = map.to_snapshot().unwrap();
+        let restored: HashMap<String, String> = HashMap::from_snapshot(&bytes).unwrap();
+
+        assert_eq!(restored.size, map.size);
+        for i in 0..50 {
+            assert_eq!(restored.get(format!("block-{}", i).as_str()), Some(&format!("gpu:{}", i % 4)));
+        }
+    }
+
+    #[test]
+    fn test_restored_map_resizes_at_same_points() {
+        // 删到桶数量 > 必要值，再保存：恢复后不能按元素数重新"最优"分配桶
+        let mut map = block_table(20);
+        for block in 0..10 {
+            map.remove(&block);
+        }
+        assert_eq!(map.size, 32);
+
+        let mut restored: HashMap<usize, usize> =
+            HashMap::from_snapshot(&map.to_snapshot().unwrap()).unwrap();
+        assert_eq!(restored.size, 32);
+
+        // 之后同样的操作序列，两张表的 size 变化完全一致
+        for block in 100..200 {
+            map.put(block, block);
+            restored.put(block, block);
+            assert_eq!(map.size, restored.size);
+        }
+        for block in 100..200 {
+            map.remove(&block);
+            restored.remove(&block);
+            assert_eq!(map.size, restored.size);
+        }
+    }
+
+    #[test]
+    fn test_custom_policy_round_trips() {
+        // 扩容更晚、每次扩 4 倍、不缩容：恢复时如果换成默认策略，桶数量会马上对不上
+        let policy = LoadFactorPolicy::new(1.5, 0.25, 4, 8).no_shrink();
+        let mut map: HashMap<usize, usize> = HashMap::with_capacity_and_policy(8, policy);
+        for block in 0..40 {
+            map.put(block, block);
+        }
+        for block in 0..35 {
+            map.remove(&block);
+        }
+        assert_eq!(map.bucket_count(), 32);
+
+        let from_bytes: HashMap<usize, usize> = HashMap::from_snapshot(&map.to_snapshot().unwrap()).unwrap();
+        let from_json: HashMap<usize, usize> = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        for mut restored in [from_bytes, from_json] {
+            assert_eq!(*restored.policy(), policy);
+            assert_eq!(restored.bucket_count(), map.bucket_count());
+            let mut original = HashMap::with_capacity_and_policy(map.bucket_count(), policy);
+            for block in 35..40 {
+                original.put(block, block);
+            }
+            for block in 100..300 {
+                original.put(block, block);
+                restored.put(block, block);
+                assert_eq!(original.bucket_count(), restored.bucket_count());
+            }
+            for block in 100..300 {
+                original.remove(&block);
+                restored.remove(&block);
+                assert_eq!(original.bucket_count(), restored.bucket_count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_policy_is_an_error() {
+        // factor = 1 会让 LoadFactorPolicy::new panic，恢复时必须变成错误
+        let json = r#"{"bucket_count":4,"rehash_step":0,"policy":{"grow_above":0.75,"shrink_below":0.25,"factor":1,"min_buckets":4},"entries":[]}"#;
+        let err = serde_json::from_str::<HashMap<usize, usize>>(json).unwrap_err();
+        assert!(err.to_string().contains("resize factor must be at least 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_fixed_seed_restores_iteration_order() {
+        let mut map = HashMap::with_hasher(FixedSeedState::default());
+        for block in 0..300usize {
+            map.put(block * 7919, block);
+        }
+        let restored: HashMap<usize, usize, FixedSeedState> =
+            HashMap::from_snapshot(&map.to_snapshot().unwrap()).unwrap();
+
+        let order = |m: &HashMap<usize, usize, FixedSeedState>| m.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(order(&map), order(&restored));
+    }
+
+    #[test]
+    fn test_snapshot_during_incremental_rehash() {
+        let mut map = HashMap::with_capacity_and_hasher(4, FixedSeedState::new(3));
+        map.enable_incremental_rehash(2);
+        for block in 0..13usize {
+            map.put(block, block);
+        }
+        assert!(map.is_rehashing());
+
+        let restored: HashMap<usize, usize, FixedSeedState> =
+            HashMap::from_snapshot(&map.to_snapshot().unwrap()).unwrap();
+        assert!(!restored.is_rehashing());
+        assert_eq!(restored.size, map.size);
+        assert_eq!(restored.rehash_step, 2);
+        for block in 0..13 {
+            assert_eq!(restored.get(&block), Some(&block));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        type Map = HashMap<usize, usize>;
+
+        assert!(matches!(Map::from_snapshot(b"nope"), Err(SnapshotError::BadMagic)));
+        assert!(matches!(Map::from_snapshot(b"HMAP"), Err(SnapshotError::BadMagic)));
+        assert!(matches!(Map::from_snapshot(b"HMAP\x07"), Err(SnapshotError::UnsupportedVersion(7))));
+        // 版本 1 没有 policy，不能按默认策略恢复
+        assert!(matches!(Map::from_snapshot(b"HMAP\x01"), Err(SnapshotError::UnsupportedVersion(1))));
+
+        // 截断的快照
+        let bytes = block_table(10).to_snapshot().unwrap();
+        assert!(matches!(Map::from_snapshot(&bytes[..bytes.len() - 1]), Err(SnapshotError::Codec(_))));
+
+        // bucket_count = 0
+        let json = format!(r#"{{"bucket_count":0,"rehash_step":0,"policy":{},"entries":[]}}"#, DEFAULT_POLICY);
+        let err = serde_json::from_str::<Map>(&json).unwrap_err();
+        assert!(err.to_string().contains("bucket_count must be positive"));
+    }
+
+    #[test]
+    fn test_huge_bucket_count_is_rejected() {
+        type Map = HashMap<usize, usize>;
+
+        // 与 Repr 字段顺序相同，用来伪造快照
+        #[derive(serde::Serialize)]
+        struct Forged {
+            bucket_count: usize,
+            rehash_step: usize,
+            policy: LoadFactorPolicy,
+            entries: Vec<(usize, usize)>,
+        }
+        let forge = |bucket_count: usize| {
+            let mut bytes = b"HMAP\x02".to_vec();
+            let forged =
+                Forged { bucket_count, rehash_step: 0, policy: LoadFactorPolicy::default(), entries: vec![(1, 1), (2, 2)] };
+            codec().serialize_into(&mut bytes, &forged).unwrap();
+            bytes
+        };
+
+        for bucket_count in [usize::MAX / 2, usize::MAX, (1 << 20) + 1] {
+            match Map::from_snapshot(&forge(bucket_count)) {
+                Err(SnapshotError::TooManyBuckets { bucket_count: b, entries: 2 }) => assert_eq!(b, bucket_count),
+                other => panic!("bucket_count {}: {:?}", bucket_count, other.map(|m| m.bucket_count())),
+            }
+        }
+        let json = format!(r#"{{"bucket_count":{},"rehash_step":0,"policy":{},"entries":[]}}"#, usize::MAX / 2, DEFAULT_POLICY);
+        let err = serde_json::from_str::<Map>(&json).unwrap_err();
+        assert!(err.to_string().contains("too large"), "{}", err);
+
+        // 上限以内的稀疏表照常恢复：reserve 之后只放了几个元素
+        assert_eq!(Map::from_snapshot(&forge(1 << 20)).unwrap().bucket_count(), 1 << 20);
+        let mut sparse: Map = HashMap::new(4);
+        sparse.reserve(100_000);
+        sparse.put(7, 7);
+        let restored = Map::from_snapshot(&sparse.to_snapshot().unwrap()).unwrap();
+        assert_eq!(restored.bucket_count(), sparse.bucket_count());
+    }
+
+    #[test]
+    fn test_huge_policy_is_rejected() {
+        type Map = HashMap<usize, usize>;
+        let policy = |min_buckets: usize, factor: usize| {
+            format!(
+                r#"{{"bucket_count":4,"rehash_step":0,"policy":{{"grow_above":0.75,"shrink_below":0.0,"factor":{},"min_buckets":{}}},"entries":[[1,1]]}}"#,
+                factor, min_buckets
+            )
+        };
+
+        // min_buckets = 2^40：以前恢复成功，随后几次 put 就在扩容时 abort
+        let json = policy(1 << 40, 2);
+        let err = serde_json::from_str::<Map>(&json).unwrap_err();
+        assert!(err.to_string().contains("resize policy out of range"), "{}", err);
+
+        // factor 巨大：第一次扩容同样会分配 4 * 2^40 个桶
+        let err = serde_json::from_str::<Map>(&policy(4, 1 << 40)).unwrap_err();
+        assert!(err.to_string().contains("resize policy out of range"), "{}", err);
+
+        // 二进制快照走同一条检查
+        let huge: Map = serde_json::from_str(&policy(4, 2)).unwrap();
+        let mut bytes = huge.to_snapshot().unwrap();
+        let honest = LoadFactorPolicy::new(0.75, 0.0, 2, 4);
+        let forged = LoadFactorPolicy::new(0.75, 0.0, 2, 1 << 40);
+        let (honest, forged) = (codec().serialize(&honest).unwrap(), codec().serialize(&forged).unwrap());
+        let at = bytes.windows(honest.len()).position(|w| w == honest.as_slice()).unwrap();
+        bytes.splice(at..at + honest.len(), forged);
+        assert!(matches!(
+            Map::from_snapshot(&bytes),
+            Err(SnapshotError::PolicyOutOfRange { min_buckets, .. }) if min_buckets == 1 << 40
+        ));
+
+        // 上限以内照常恢复
+        let ok: Map = serde_json::from_str(&policy(1 << 10, 16)).unwrap();
+        assert_eq!(ok.policy().min_buckets(), 1 << 10);
+    }
+}
@@ -0,0 +1,206 @@
+// ==============================================================================
+// 运行时统计 - 看清哈希表的分布情况
+// ==============================================================================
+//
+// 【为什么需要】
+//   - size / count 是私有字段，模块外看不到表的负载和桶分布
+//   - 块 id 往往是有规律的（seq_id * 4096 + logical_idx），
+//     哈希函数对这种 id 退化时，平均 O(1) 会悄悄变成 O(n)，只有看链长才能发现
+//
+// 【统计项】
+//   - 负载因子 = count / size
+//   - 链长：每个桶里的元素个数，直方图 histogram[i] = 链长为 i 的桶数
+//   - 平均链长只统计非空桶：命中查找平均要比较 (mean + 1) / 2 次
+//   - 累计扩容 / 缩容次数：频繁在阈值附近来回扩缩说明初始容量选得不好
+//
+// 渐进式 rehash 进行中时，链长只统计新表，还没搬走的元素数单独列出
+// ==============================================================================
+
+use std::fmt;
+use std::hash::Hash;
+
+use crate::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapStats {
+    pub bucket_count: usize,             // 桶数量（size）
+    pub element_count: usize,            // 元素总数（count，包括还在旧表里的）
+    pub load_factor: f64,                // element_count / bucket_count
+    pub max_chain_len: usize,            // 最长的链
+    pub mean_chain_len: f64,             // 非空桶的平均链长，空表为 0
+    pub chain_len_histogram: Vec<usize>, // [i] = 链长为 i 的桶数，长度 = max_chain_len + 1
+    pub grow_count: usize,               // 累计扩容次数
+    pub shrink_count: usize,             // 累计缩容次数
+    pub pending_migration: usize,        // 渐进式 rehash 中还留在旧表的元素数
+}
+
+impl MapStats {
+    // 空桶数量
+    pub fn empty_buckets(&self) -> usize {
+        self.chain_len_histogram.first().copied().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for MapStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buckets {} | elements {} | load {:.2} | chain max {} mean {:.2} | empty {} | grow {} shrink {}",
+            self.bucket_count,
+            self.element_count,
+            self.load_factor,
+            self.max_chain_len,
+            self.mean_chain_len,
+            self.empty_buckets(),
+            self.grow_count,
+            self.shrink_count,
+        )?;
+        if self.pending_migration > 0 {
+            write!(f, " | pending {}", self.pending_migration)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, S, P> HashMap<K, V, S, P>
+where
+    K: Hash + Eq,
+{
+    // 遍历一次桶数组，O(size)
+    pub fn stats(&self) -> MapStats {
+        let max_chain_len = self.buckets.iter().map(|bucket| bucket.len()).max().unwrap_or(0);
+
+        let mut chain_len_histogram = vec![0; max_chain_len + 1];
+        for bucket in &self.buckets {
+            chain_len_histogram[bucket.len()] += 1;
+        }
+
+        let pending_migration: usize = self.old_buckets().iter().map(|bucket| bucket.len()).sum();
+        let non_empty = self.size - chain_len_histogram[0];
+        let mean_chain_len = if non_empty == 0 {
+            0.0
+        } else {
+            (self.count - pending_migration) as f64 / non_empty as f64
+        };
+
+        MapStats {
+            bucket_count: self.size,
+            element_count: self.count,
+            load_factor: self.count as f64 / self.size as f64,
+            max_chain_len,
+            mean_chain_len,
+            chain_len_histogram,
+            grow_count: self.grow_count,
+            shrink_count: self.shrink_count,
+            pending_migration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FixedSeedState, HashMap, HashMapTrait, IdentityBuildHasher};
+
+    #[test]
+    fn test_empty_map() {
+        let map: HashMap<usize, usize> = HashMap::new(4);
+        let stats = map.stats();
+        assert_eq!(stats.bucket_count, 4);
+        assert_eq!(stats.element_count, 0);
+        assert_eq!(stats.load_factor, 0.0);
+        assert_eq!(stats.max_chain_len, 0);
+        assert_eq!(stats.mean_chain_len, 0.0);
+        assert_eq!(stats.chain_len_histogram, vec![4]);
+        assert_eq!(stats.empty_buckets(), 4);
+    }
+
+    #[test]
+    fn test_histogram_matches_buckets() {
+        // identity 哈希下 key % 8 就是桶下标，分布可以手算
+        let mut map: HashMap<usize, usize, IdentityBuildHasher> = HashMapTrait::new(8);
+        for key in [0, 8, 16, 1, 9, 2] {
+            map.put(key, key);
+        }
+        let stats = map.stats();
+        assert_eq!(stats.bucket_count, 8);
+        assert_eq!(stats.element_count, 6);
+        assert_eq!(stats.load_factor, 0.75);
+        assert_eq!(stats.max_chain_len, 3);
+        assert_eq!(stats.chain_len_histogram, vec![5, 1, 1, 1]);
+        assert_eq!(stats.mean_chain_len, 2.0);
+        assert_eq!(stats.chain_len_histogram.iter().sum::<usize>(), stats.bucket_count);
+    }
+
+    #[test]
+    fn test_grow_and_shrink_counts() {
+        // 与 test_shrinking 相同的序列：4 -> 8 -> 16 -> 32，再 32 -> 16 -> 8
+        let mut map = HashMap::new(4);
+        for i in 0..20 {
+            map.put(i, i);
+        }
+        assert_eq!(map.stats().grow_count, 3);
+        assert_eq!(map.stats().shrink_count, 0);
+
+        for i in 0..17 {
+            map.remove(&i);
+        }
+        let stats = map.stats();
+        assert_eq!(stats.bucket_count, 8);
+        assert_eq!(stats.grow_count, 3);
+        assert_eq!(stats.shrink_count, 2);
+
+        // clear 一次性缩回 4 个桶，算一次缩容
+        map.clear();
+        assert_eq!(map.stats().shrink_count, 3);
+        assert_eq!(map.stats().bucket_count, 4);
+    }
+
+    #[test]
+    fn test_strided_ids_degenerate_with_identity_hash() {
+        // 500 个序列，每个 4 个逻辑块：id = seq * 4096 + idx。
+        // 2000 个元素最终是 4096 个桶，identity 哈希下 id % 4096 只剩 idx，
+        // 所有元素挤进 4 个桶
+        let ids: Vec<usize> = (0..500)
+            .flat_map(|seq| (0..4).map(move |idx| seq * 4096 + idx))
+            .collect();
+
+        let mut identity: HashMap<usize, usize, IdentityBuildHasher> = HashMapTrait::new(4);
+        let mut seeded = HashMap::with_hasher(FixedSeedState::new(1));
+        for &id in &ids {
+            identity.put(id, id);
+            seeded.put(id, id);
+        }
+
+        let degenerate = identity.stats();
+        let healthy = seeded.stats();
+        assert_eq!(degenerate.bucket_count, healthy.bucket_count);
+        assert_eq!(degenerate.bucket_count, 4096);
+        assert_eq!(degenerate.max_chain_len, 500);
+        assert_eq!(degenerate.bucket_count - degenerate.empty_buckets(), 4);
+        assert!(healthy.max_chain_len < 16, "{}", healthy);
+        assert!(healthy.mean_chain_len < 2.0, "{}", healthy);
+    }
+
+    #[test]
+    fn test_pending_migration_during_incremental_rehash() {
+        let mut map = HashMap::with_capacity_and_hasher(4, FixedSeedState::new(3));
+        map.enable_incremental_rehash(1);
+        for i in 0..4usize {
+            map.put(i, i);
+        }
+        assert!(map.is_rehashing());
+
+        let stats = map.stats();
+        assert_eq!(stats.element_count, 4);
+        assert_eq!(stats.bucket_count, 8);
+        let in_new: usize = stats
+            .chain_len_histogram
+            .iter()
+            .enumerate()
+            .map(|(len, buckets)| len * buckets)
+            .sum();
+        assert_eq!(in_new + stats.pending_migration, 4);
+        assert!(stats.pending_migration > 0);
+        assert!(stats.to_string().contains("pending"));
+    }
+}
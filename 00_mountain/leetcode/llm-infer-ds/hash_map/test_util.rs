@@ -0,0 +1,129 @@
+// ==============================================================================
+// 测试公用的检查 - 只在 cfg(test) 下编译
+// ==============================================================================
+//
+// 【为什么需要】
+//   1. HashMap / RobinHoodMap / SlabHashMap 的扩缩容语义完全相同（> 0.75 翻倍，
+//      < 0.25 减半，最少 4 个桶），同一份用例写一次，每种实现各调用一次
+//   2. 各模块的 churn 测试都要"随机插入 / 删除后与 std HashMap 一致"的对照
+//      （churn_against_std）；固定种子的随机序列用 bench_support::Lcg
+//   3. Seeded：没有 Default 的哈希函数，检查各实现的核心操作不要求 S: Default
+//
+// 【用法】
+//   在实现自己的 tests 模块里实现 Inspect（可以读私有字段），然后
+//   check_resize_semantics::<HashMap<usize, usize>>()
+//   let model = churn_against_std(&mut map, seed, steps, key_space, |map, step| ...);
+// ==============================================================================
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap as StdHashMap;
+use std::hash::{BuildHasher, Hasher};
+
+use bench_support::Lcg;
+
+use crate::HashMapTrait;
+
+// 只能通过构造函数拿到的哈希函数（比如带运行时种子）：故意不实现 Default
+#[derive(Clone)]
+pub(crate) struct Seeded(pub(crate) u64);
+
+impl BuildHasher for Seeded {
+    type Hasher = DefaultHasher;
+    fn build_hasher(&self) -> Self::Hasher {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u64(self.0);
+        hasher
+    }
+}
+
+// 读出桶 / 槽位数量和元素数量，各实现的字段名不同
+pub(crate) trait Inspect {
+    fn bucket_count(&self) -> usize;
+    fn element_count(&self) -> usize;
+}
+
+// 对应 hash_map.rs 里的 test_resize / test_large_volume / test_shrinking
+pub(crate) fn check_resize_semantics<M>()
+where
+    M: HashMapTrait<usize, usize> + Inspect,
+{
+    // 1. 初始大小为 4，阈值是 4 * 0.75 = 3：插入第 4 个元素才扩容
+    let mut map = M::new(4);
+    assert_eq!(map.bucket_count(), 4);
+    for i in 1..=3 {
+        map.put(i, i);
+    }
+    assert_eq!(map.bucket_count(), 4);
+    map.put(4, 4);
+    assert_eq!(map.bucket_count(), 8);
+    for i in 1..=4 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+
+    // 2. 大量插入，桶数量跟着增长，数据都能找回
+    let mut map = M::new(4);
+    for i in 0..1000 {
+        map.put(i, i * 10);
+    }
+    assert_eq!(map.element_count(), 1000);
+    assert!(map.bucket_count() >= 1000);
+    for i in 0..1000 {
+        assert_eq!(map.get(&i), Some(&(i * 10)));
+    }
+
+    // 3. 4 -> 8 (insert #4) -> 16 (insert #7) -> 32 (insert #13)
+    let mut map = M::new(4);
+    for i in 0..20 {
+        map.put(i, i);
+    }
+    assert_eq!(map.bucket_count(), 32);
+    assert_eq!(map.element_count(), 20);
+
+    // 剩 7 个 (7/32 < 0.25)，缩为 16
+    for i in 0..13 {
+        map.remove(&i);
+    }
+    assert_eq!(map.element_count(), 7);
+    assert_eq!(map.bucket_count(), 16);
+
+    // 剩 3 个 (3/16 < 0.25)，缩为 8
+    for i in 13..17 {
+        map.remove(&i);
+    }
+    assert_eq!(map.element_count(), 3);
+    assert_eq!(map.bucket_count(), 8);
+    assert_eq!(map.get(&19), Some(&19));
+}
+
+// 随机插入 / 覆盖 / 删除 steps 步（每 3 步删一次），每一步都与 std HashMap 对照，
+// 每步之后调用 check(map, step) 做实现自己的检查；返回最终的模型
+pub(crate) fn churn_against_std<M, F>(
+    map: &mut M,
+    seed: u64,
+    steps: usize,
+    key_space: usize,
+    mut check: F,
+) -> StdHashMap<usize, usize>
+where
+    M: HashMapTrait<usize, usize> + Inspect,
+    F: FnMut(&M, usize),
+{
+    let mut rng = Lcg::new(seed);
+    let mut model = StdHashMap::new();
+    for step in 0..steps {
+        let key = rng.below(key_space);
+        if step % 3 == 0 {
+            assert_eq!(map.remove(&key), model.remove(&key).is_some());
+        } else {
+            map.put(key, step);
+            model.insert(key, step);
+        }
+        assert_eq!(map.element_count(), model.len());
+        check(map, step);
+    }
+
+    for (key, value) in &model {
+        assert_eq!(map.get(key), Some(value));
+    }
+    model
+}
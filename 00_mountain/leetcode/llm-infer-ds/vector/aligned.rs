@@ -0,0 +1,303 @@
+// ==============================================================================
+// 对齐分配 - Vector::with_alignment + AlignedRows
+// ==============================================================================
+//
+// 【为什么需要】
+//   - Layout::array::<T> 只保证 T 自己的对齐（f32 是 4 字节）
+//   - AVX-512 一次读 64 字节：起点按 64 对齐时一条对齐 load 就够，不会跨缓存行
+//   - 多线程各写各的行：行首不对齐时两个线程可能写同一条缓存行（false sharing），
+//     互相把对方的缓存行作废，性能掉得很厉害
+//
+// 【Vector::with_alignment(cap, align)】
+//   align 记在 Vector 里，扩容 / 缩容 / 释放都用同一个 align 构造 Layout，
+//   所以不管 realloc 多少次，data 始终按 align 对齐（容量为 0 时的 dangling 指针也是）
+//
+// 【AlignedRows】
+//   二维矩阵 [rows, cols]，每行末尾补齐，使得每一行的起点都按 align 对齐：
+//     row_stride = cols 向上取整，使 row_stride × size_of::<T>() 是 align 的倍数
+//     第 i 行 = data[i * row_stride .. i * row_stride + cols]
+//   例：f32、cols = 10、align = 64 -> row_stride = 16，每行后面补 6 个 0
+// ==============================================================================
+
+use crate::{Global, RawAlloc, Tensor, Vector};
+
+// 常用对齐：一条缓存行 = 一次 AVX-512 load
+pub const CACHE_LINE: usize = 64;
+
+impl<T> Vector<T> {
+    pub fn with_alignment(capacity: usize, align: usize) -> Self {
+        Self::with_alignment_in(capacity, align, Global)
+    }
+}
+
+impl<T, A: RawAlloc> Vector<T, A> {
+    // align 必须是 2 的幂；比 align_of::<T>() 小时按 align_of::<T>() 算
+    pub fn with_alignment_in(capacity: usize, align: usize, alloc: A) -> Self {
+        assert!(align.is_power_of_two(), "alignment {} is not a power of two", align);
+        // 1. 先建一个空的 Vector，再把对齐换掉（此时还没有缓冲区，只需要换 dangling 指针）
+        let mut vector = Self::with_capacity_in(0, alloc);
+        vector.align = align.max(std::mem::align_of::<T>());
+        vector.data = Self::dangling(vector.align);
+        // 2. 之后所有分配都用 vector.align
+        vector.reserve_exact(capacity);
+        vector
+    }
+
+    // 缓冲区的对齐字节数
+    pub fn alignment(&self) -> usize {
+        self.align
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlignedRows<T> {
+    data: Vector<T>,   // rows × row_stride 个元素，补齐的部分是 T::default()
+    rows: usize,
+    cols: usize,
+    row_stride: usize, // 相邻两行起点之间隔几个元素
+}
+
+// 行长要是 unit 的倍数，每行的字节数才是 align 的倍数
+fn padded_row_len(cols: usize, elem_size: usize, align: usize) -> usize {
+    if elem_size == 0 {
+        return cols;
+    }
+    let unit = align / gcd(align, elem_size);
+    cols.checked_next_multiple_of(unit).expect("capacity overflow")
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<T: Clone + Default> AlignedRows<T> {
+    // rows × cols 个 T::default()
+    pub fn new(rows: usize, cols: usize, align: usize) -> Self {
+        assert!(cols > 0, "AlignedRows needs at least one column");
+        let mut data = Vector::with_alignment(0, align);
+        let row_stride = padded_row_len(cols, std::mem::size_of::<T>(), data.alignment());
+        let total = rows.checked_mul(row_stride).expect("capacity overflow");
+        data.reserve_exact(total);
+        data.extend(std::iter::repeat_n(T::default(), total));
+        Self { data, rows, cols, row_stride }
+    }
+
+    // 从按行紧密排列的 rows × cols 个元素构造
+    pub fn from_rows(values: &[T], rows: usize, cols: usize, align: usize) -> Self {
+        assert_eq!(values.len(), rows * cols, "expected {} x {} values", rows, cols);
+        let mut matrix = Self::new(rows, cols, align);
+        for (dst, src) in matrix.iter_rows_mut().zip(values.chunks(cols)) {
+            dst.clone_from_slice(src);
+        }
+        matrix
+    }
+}
+
+impl<T> AlignedRows<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.data.alignment()
+    }
+
+    // 第 i 行（不含补齐的部分）
+    pub fn row(&self, i: usize) -> &[T] {
+        assert!(i < self.rows, "row {} out of range ({} rows)", i, self.rows);
+        let start = i * self.row_stride;
+        &self.data[start..start + self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        assert!(i < self.rows, "row {} out of range ({} rows)", i, self.rows);
+        let start = i * self.row_stride;
+        &mut self.data[start..start + self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        let cols = self.cols;
+        self.data.chunks(self.row_stride).map(move |row| &row[..cols])
+    }
+
+    // 各行互不重叠，可以分给不同线程同时写
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let cols = self.cols;
+        self.data.chunks_mut(self.row_stride).map(move |row| &mut row[..cols])
+    }
+
+    // 整块存储，包括每行后面补齐的元素
+    pub fn as_padded_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    // 变成 [rows, cols]、strides = [row_stride, 1] 的 Tensor 视图，存储（和对齐）原样保留
+    pub fn into_tensor(self) -> Tensor<T> {
+        let (rows, cols, row_stride) = (self.rows, self.cols, self.row_stride);
+        Tensor::from_vector(self.data, &[rows, row_stride])
+            .and_then(|padded| padded.narrow(1, 0, cols))
+            .expect("padded storage matches [rows, row_stride]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlignedRows, CACHE_LINE};
+    use crate::{BumpAlloc, GrowthPolicy, PoolAlloc, RawAlloc, Vector};
+
+    fn assert_aligned<T, A: RawAlloc>(v: &Vector<T, A>, align: usize) {
+        assert!(
+            (v.as_ptr() as usize).is_multiple_of(align),
+            "{:p} not aligned to {} (len {}, capacity {})",
+            v.as_ptr(),
+            align,
+            v.len(),
+            v.capacity()
+        );
+    }
+
+    #[test]
+    fn test_alignment_survives_every_resize() {
+        let policies = [GrowthPolicy::Exact, GrowthPolicy::OneAndHalf, GrowthPolicy::Double, GrowthPolicy::PageRounded];
+        for align in [16, CACHE_LINE, 4096] {
+            for policy in policies {
+                let mut v: Vector<f32> = Vector::with_alignment(0, align);
+                v.set_growth_policy(policy);
+                assert_eq!(v.alignment(), align);
+                assert_aligned(&v, align);
+
+                for i in 0..3000 {
+                    v.push(i as f32);
+                    assert_aligned(&v, align);
+                }
+                // pop 会触发自动缩容
+                for _ in 0..2990 {
+                    v.pop();
+                    assert_aligned(&v, align);
+                }
+                v.reserve_exact(100);
+                assert_aligned(&v, align);
+                v.shrink_to_fit();
+                assert_aligned(&v, align);
+                assert_eq!(v, [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+                // 容量为 0 时的 dangling 指针也对齐
+                v.clear();
+                v.shrink_to_fit();
+                assert_eq!(v.capacity(), 0);
+                assert_aligned(&v, align);
+                v.push(1.0);
+                assert_aligned(&v, align);
+            }
+        }
+    }
+
+    #[test]
+    fn test_alignment_with_custom_allocators() {
+        let pool = PoolAlloc::new(1 << 20);
+        let mut pooled: Vector<u8, &PoolAlloc> = Vector::with_alignment_in(3, 128, &pool);
+        let arena = BumpAlloc::new(1 << 16);
+        let _offset: Vector<u8, &BumpAlloc> = Vector::with_capacity_in(1, &arena);
+        let mut bumped: Vector<u8, &BumpAlloc> = Vector::with_alignment_in(3, 128, &arena);
+        for i in 0..1000 {
+            pooled.push(i as u8);
+            bumped.push(i as u8);
+            assert_aligned(&pooled, 128);
+            assert_aligned(&bumped, 128);
+        }
+        bumped.truncate(10);
+        bumped.shrink_to_fit();
+        assert_aligned(&bumped, 128);
+        assert_eq!(pooled[999], (999 % 256) as u8);
+    }
+
+    #[test]
+    fn test_alignment_is_at_least_natural_and_cloned() {
+        let v: Vector<u64> = Vector::with_alignment(4, 1);
+        assert_eq!(v.alignment(), 8);
+        assert_eq!(Vector::<u32>::new().alignment(), 4);
+
+        let mut wide: Vector<u32> = Vector::with_alignment(1, 256);
+        wide.extend_from_slice(&[1, 2, 3]);
+        let cloned = wide.clone();
+        assert_eq!(cloned.alignment(), 256);
+        assert_aligned(&cloned, 256);
+
+        let zst: Vector<()> = Vector::with_alignment(10, 64);
+        assert_aligned(&zst, 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a power of two")]
+    fn test_alignment_must_be_power_of_two() {
+        let _ = Vector::<f32>::with_alignment(4, 48);
+    }
+
+    #[test]
+    fn test_aligned_rows_pad_each_row() {
+        let values: Vec<f32> = (0..50).map(|i| i as f32).collect();
+        let m = AlignedRows::from_rows(&values, 5, 10, CACHE_LINE);
+        assert_eq!(m.row_stride(), 16);
+        assert_eq!(m.as_padded_slice().len(), 5 * 16);
+        for (i, row) in m.iter_rows().enumerate() {
+            assert_eq!(row, &values[i * 10..(i + 1) * 10]);
+            assert!((row.as_ptr() as usize).is_multiple_of(CACHE_LINE));
+            assert_eq!(row, m.row(i));
+        }
+        // 补齐的部分是 T::default()
+        assert_eq!(&m.as_padded_slice()[10..16], &[0.0; 6]);
+
+        // 3 字节的元素：每行要补到 64 个元素（192 字节）才能让下一行对齐
+        let odd: AlignedRows<[u8; 3]> = AlignedRows::new(4, 5, CACHE_LINE);
+        assert_eq!(odd.row_stride(), 64);
+        for row in odd.iter_rows() {
+            assert!((row.as_ptr() as usize).is_multiple_of(CACHE_LINE));
+        }
+    }
+
+    #[test]
+    fn test_aligned_rows_into_tensor() {
+        let values: Vec<f32> = (0..15).map(|i| i as f32).collect();
+        let m = AlignedRows::from_rows(&values, 3, 5, 32);
+        assert_eq!(m.row_stride(), 8);
+        let t = m.into_tensor();
+        assert_eq!(t.shape(), &[3, 5]);
+        assert_eq!(t.strides(), &[8, 1]);
+        assert_eq!(t[[2, 4]], 14.0);
+        assert!(!t.is_contiguous());
+        assert_eq!(t.contiguous().to_vector(), values);
+        assert_eq!(t.storage().alignment(), 32);
+    }
+
+    #[test]
+    fn test_rows_written_from_threads() {
+        let mut m: AlignedRows<u64> = AlignedRows::new(8, 3, CACHE_LINE);
+        std::thread::scope(|scope| {
+            for (i, row) in m.iter_rows_mut().enumerate() {
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        for x in row.iter_mut() {
+                            *x += i as u64;
+                        }
+                    }
+                });
+            }
+        });
+        for i in 0..8 {
+            assert_eq!(m.row(i), &[1000 * i as u64; 3]);
+        }
+        m.row_mut(0)[0] = 7;
+        assert_eq!(m.row(0)[0], 7);
+    }
+}
@@ -0,0 +1,149 @@
+// ==============================================================================
+// Benchmark: 块表的创建 / 追加 / 释放 - Vec vs Vector vs SmallVector<_, 8>
+// ==============================================================================
+//
+// 运行：cargo bench -p vector --bench block_list_churn
+//
+// 两部分：
+//   1. 建一个有 k 个块的块表再丢掉（一个短序列的一生），k = 1 / 4 / 8 / 16
+//   2. 调度器 churn：LIVE 个槽位，每一步随机挑一个槽位
+//        - 空槽位：新序列进来，目标长度大多是 1..=8 个块，偶尔是 16..64 个块的长序列
+//        - 没写满：decode 到块边界，追加一个块
+//        - 写满了：序列结束，块表被 drop
+// 同时用计数的全局分配器（bench_support）统计 alloc / realloc 次数，取 ROUNDS 轮中的最好成绩
+// ==============================================================================
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bench_support::{best_of_counting, ns_per_op, AllocCount, CountingAlloc, Lcg};
+use vector::{SmallVector, Vector};
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// ==========================================
+// 三种块表
+// ==========================================
+
+trait BlockList: Default {
+    fn push_block(&mut self, block: usize);
+    fn blocks(&self) -> &[usize];
+}
+
+impl BlockList for Vec<usize> {
+    fn push_block(&mut self, block: usize) {
+        self.push(block);
+    }
+
+    fn blocks(&self) -> &[usize] {
+        self
+    }
+}
+
+impl BlockList for Vector<usize> {
+    fn push_block(&mut self, block: usize) {
+        self.push(block);
+    }
+
+    fn blocks(&self) -> &[usize] {
+        self
+    }
+}
+
+impl BlockList for SmallVector<usize, 8> {
+    fn push_block(&mut self, block: usize) {
+        self.push(block);
+    }
+
+    fn blocks(&self) -> &[usize] {
+        self
+    }
+}
+
+// ==========================================
+// 负载
+// ==========================================
+
+const OPS: usize = 200_000;
+const LIVE: usize = 256;
+const ROUNDS: usize = 5;
+
+// 大多数序列很短，5% 是长序列
+fn target_len(rng: &mut Lcg) -> usize {
+    if rng.below(20) == 0 {
+        16 + rng.below(49)
+    } else {
+        1 + rng.below(8)
+    }
+}
+
+// 1. 一个序列的一生：建表、追加 k 个块、drop
+fn create_and_drop<L: BlockList>(k: usize) -> Duration {
+    let start = Instant::now();
+    for i in 0..OPS {
+        let mut list = L::default();
+        for b in 0..k {
+            list.push_block(black_box(i + b));
+        }
+        black_box(list.blocks());
+    }
+    start.elapsed()
+}
+
+// 2. 调度器 churn
+fn churn<L: BlockList>() -> Duration {
+    let mut rng = Lcg::new(42);
+    let mut slots: Vec<Option<(L, usize)>> = (0..LIVE).map(|_| None).collect();
+    let mut next_block = 0;
+
+    let start = Instant::now();
+    for _ in 0..OPS {
+        let slot = &mut slots[rng.below(LIVE)];
+        match slot {
+            None => {
+                let mut list = L::default();
+                list.push_block(next_block);
+                next_block += 1;
+                *slot = Some((list, target_len(&mut rng)));
+            }
+            Some((list, target)) if list.blocks().len() < *target => {
+                list.push_block(next_block);
+                next_block += 1;
+            }
+            Some(_) => {
+                black_box(slot.take());
+            }
+        }
+    }
+    black_box(&slots);
+    start.elapsed()
+}
+
+fn best_of(run: impl FnMut() -> Duration) -> (Duration, AllocCount) {
+    best_of_counting(ROUNDS, run)
+}
+
+fn report(workload: &str, name: &str, (elapsed, allocs): (Duration, AllocCount)) {
+    println!(
+        "{:<12} {:<18} {:>7.1} ns/op | alloc {:>7} realloc {:>7}",
+        workload,
+        name,
+        ns_per_op(elapsed, OPS),
+        allocs.allocs,
+        allocs.reallocs,
+    );
+}
+
+fn main() {
+    for k in [1, 4, 8, 16] {
+        let workload = format!("k={}", k);
+        report(&workload, "Vec", best_of(|| create_and_drop::<Vec<usize>>(k)));
+        report(&workload, "Vector", best_of(|| create_and_drop::<Vector<usize>>(k)));
+        report(&workload, "SmallVector<_, 8>", best_of(|| create_and_drop::<SmallVector<usize, 8>>(k)));
+    }
+
+    report("churn", "Vec", best_of(churn::<Vec<usize>>));
+    report("churn", "Vector", best_of(churn::<Vector<usize>>));
+    report("churn", "SmallVector<_, 8>", best_of(churn::<SmallVector<usize, 8>>));
+}
@@ -0,0 +1,315 @@
+// ==============================================================================
+// 扩容策略 - GrowthPolicy + reserve / shrink_to_fit + TryReserveError
+// ==============================================================================
+//
+// 【为什么需要】
+//   - 固定 ×2 扩容：连续 push 是均摊 O(1)，但最坏会浪费一半内存
+//   - 大缓冲区（权重、KV 池）往往一开始就知道要多大，应该一次 reserve 到位
+//   - 容量算术溢出、分配失败都应该交给调用方处理，而不是在 Layout::array(..).unwrap() 里 panic
+//
+// 【四种策略】（required = 至少要放下的元素个数）
+//   Exact:       新容量 = required，不多要一个（内存最省，但每次 push 都可能 realloc）
+//   OneAndHalf:  max(required, capacity × 1.5)（folly / MSVC 的选择，旧块更容易被分配器复用）
+//   Double:      max(required, capacity × 2)（默认，与之前的行为相同）
+//   PageRounded: 先按 ×2 算，再把字节数向上取整到 4 KiB 页（大缓冲区不浪费半页）
+//
+// 【缩容】
+//   pop 后元素个数不到容量的 1/4 时缩到一半；Exact 不自动缩容
+//   （Exact 下每次 push 都会扩容，再自动缩容就会来回抖动），需要时显式调用 shrink_to_fit
+// ==============================================================================
+
+use std::alloc::Layout;
+use std::error::Error;
+use std::fmt;
+
+use crate::{AllocError, RawAlloc, Vector};
+
+// PageRounded 取整的粒度
+pub const PAGE_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrowthPolicy {
+    Exact,
+    OneAndHalf,
+    #[default]
+    Double,
+    PageRounded,
+}
+
+impl GrowthPolicy {
+    // 当前容量放不下 required 个元素时，扩到多少
+    pub fn grow(self, capacity: usize, required: usize, elem_size: usize) -> Result<usize, TryReserveError> {
+        let amortized = match self {
+            GrowthPolicy::Exact => required,
+            GrowthPolicy::OneAndHalf => capacity.saturating_add(capacity / 2),
+            GrowthPolicy::Double | GrowthPolicy::PageRounded => capacity.saturating_mul(2),
+        };
+        let target = required.max(amortized);
+        match self {
+            GrowthPolicy::PageRounded => round_to_page(target, elem_size),
+            _ => Ok(target),
+        }
+    }
+
+    // pop 之后要不要缩容，缩到多少；None 表示不缩
+    pub fn shrink_target(self, len: usize, capacity: usize, elem_size: usize) -> Option<usize> {
+        if self == GrowthPolicy::Exact || len == 0 || len >= capacity / 4 {
+            return None;
+        }
+        let target = match self {
+            GrowthPolicy::PageRounded => round_to_page(capacity / 2, elem_size).ok()?,
+            _ => capacity / 2,
+        };
+        // 不到一页的缓冲区取整之后可能根本没变小
+        (target < capacity).then_some(target)
+    }
+}
+
+// 把 count 个元素占的字节数向上取整到整页，再换算回元素个数
+fn round_to_page(count: usize, elem_size: usize) -> Result<usize, TryReserveError> {
+    if elem_size == 0 {
+        return Ok(count);
+    }
+    let bytes = count.checked_mul(elem_size).ok_or(TryReserveError::CapacityOverflow)?;
+    let rounded = bytes.checked_next_multiple_of(PAGE_SIZE).ok_or(TryReserveError::CapacityOverflow)?;
+    Ok(rounded / elem_size)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryReserveError {
+    CapacityOverflow,                                // 元素个数或字节数溢出（Layout 要求总字节数不超过 isize::MAX）
+    AllocError { layout: Layout, cause: AllocError }, // 分配器拒绝了这次分配，cause 说明原因（池超预算 / 系统分配失败）
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => write!(f, "capacity overflow"),
+            TryReserveError::AllocError { layout, cause } => {
+                write!(f, "memory allocation of {} bytes failed: {}", layout.size(), cause)
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TryReserveError::CapacityOverflow => None,
+            TryReserveError::AllocError { cause, .. } => Some(cause),
+        }
+    }
+}
+
+// 不返回 Result 的接口（push / reserve / ...）遇到错误时的统一出口，与 std::Vec 的行为一致
+pub(crate) fn handle_reserve_error(err: TryReserveError) -> ! {
+    match err {
+        TryReserveError::CapacityOverflow => panic!("capacity overflow"),
+        TryReserveError::AllocError { layout, .. } => std::alloc::handle_alloc_error(layout),
+    }
+}
+
+impl<T, A: RawAlloc> Vector<T, A> {
+    // with_capacity_in 的可失败版本：分配失败时返回错误，不 abort
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let mut vector = Self::with_capacity_in(0, alloc);
+        vector.try_reserve_exact(capacity)?;
+        Ok(vector)
+    }
+
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.policy
+    }
+
+    // 只影响之后的扩容 / 缩容，不会立刻改变容量
+    pub fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.policy = policy;
+    }
+
+    // 保证还能再放 additional 个元素，按扩容策略多留余量
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self.size.checked_add(additional).ok_or(TryReserveError::CapacityOverflow)?;
+        self.try_grow_to(required)
+    }
+
+    // 保证还能再放 additional 个元素，不多留余量
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self.size.checked_add(additional).ok_or(TryReserveError::CapacityOverflow)?;
+        if required <= self.capacity {
+            return Ok(());
+        }
+        self.try_resize(required)
+    }
+
+    // 需要扩容但分配失败时不 abort：把值连同错误一起还给调用方
+    // （PoolAlloc 超预算时，调用方可以先换出 / 抢占别的序列再重试）
+    pub fn try_push(&mut self, value: T) -> Result<(), (T, TryReserveError)> {
+        if let Err(err) = self.try_reserve(1) {
+            return Err((value, err));
+        }
+        self.push(value);
+        Ok(())
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_reserve_error(err);
+        }
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve_exact(additional) {
+            handle_reserve_error(err);
+        }
+    }
+
+    // 容量缩到刚好放下现有元素（空 Vector 会释放缓冲区）
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    // 容量缩到 max(len, min_capacity)；本来就不比它大时什么都不做
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let target = self.size.max(min_capacity);
+        if target < self.capacity {
+            self.resize(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GrowthPolicy, TryReserveError, PAGE_SIZE};
+    use crate::Vector;
+
+    // 从空 Vector 开始 push n 次，记录每次容量变化后的值
+    fn capacities(policy: GrowthPolicy, n: usize) -> Vec<usize> {
+        let mut v: Vector<u64> = Vector::with_capacity(0);
+        v.set_growth_policy(policy);
+        let mut seen = Vec::new();
+        for i in 0..n {
+            v.push(i as u64);
+            if seen.last() != Some(&v.capacity()) {
+                seen.push(v.capacity());
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn test_policies_grow_capacity() {
+        assert_eq!(capacities(GrowthPolicy::Exact, 6), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(capacities(GrowthPolicy::OneAndHalf, 20), vec![1, 2, 3, 4, 6, 9, 13, 19, 28]);
+        assert_eq!(capacities(GrowthPolicy::Double, 20), vec![1, 2, 4, 8, 16, 32]);
+        // u64 一页放 512 个
+        assert_eq!(capacities(GrowthPolicy::PageRounded, 1500), vec![512, 1024, 2048]);
+        assert_eq!(Vector::<u8>::new().growth_policy(), GrowthPolicy::Double);
+    }
+
+    #[test]
+    fn test_page_rounded_capacity_fills_whole_pages() {
+        // 24 字节的元素：4096 不是 24 的倍数，取整后的字节数不超过整页
+        let mut v: Vector<[u64; 3]> = Vector::with_capacity(0);
+        v.set_growth_policy(GrowthPolicy::PageRounded);
+        for i in 0..1000 {
+            v.push([i; 3]);
+            let bytes = v.capacity() * 24;
+            assert!(bytes.next_multiple_of(PAGE_SIZE) - bytes < 24);
+        }
+        assert_eq!(v[999], [999; 3]);
+    }
+
+    #[test]
+    fn test_reserve_and_reserve_exact() {
+        let mut v: Vector<u32> = (0..3).collect();
+        v.reserve_exact(10);
+        assert_eq!(v.capacity(), 13);
+
+        // 容量够用时什么都不做，缓冲区也不会换
+        let ptr = v.as_ptr();
+        v.reserve(10);
+        v.reserve_exact(5);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 13);
+
+        // reserve 按策略多留：max(3 + 20, 13 × 2)
+        v.reserve(20);
+        assert_eq!(v.capacity(), 26);
+        assert_eq!(v, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_shrink_to_fit_and_shrink_to() {
+        let mut v: Vector<String> = (0..100).map(|i| i.to_string()).collect();
+        v.truncate(10);
+        v.shrink_to(20);
+        assert_eq!(v.capacity(), 20);
+        v.shrink_to(50); // 不会反过来扩容
+        assert_eq!(v.capacity(), 20);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 10);
+        assert_eq!(v[9], "9");
+
+        v.clear();
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 0);
+        v.push("again".to_string());
+        assert_eq!(v, ["again"]);
+    }
+
+    #[test]
+    fn test_errors_instead_of_panics() {
+        let mut v: Vector<u64> = (0..4).collect();
+        assert_eq!(v.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        // 元素个数不溢出，但字节数超过 isize::MAX
+        assert_eq!(v.try_reserve_exact(usize::MAX / 4), Err(TryReserveError::CapacityOverflow));
+        assert_eq!(
+            GrowthPolicy::PageRounded.grow(0, usize::MAX / 2, 8),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        // 字节数合法，但没有分配器给得出这么多内存
+        let huge = isize::MAX as usize / 8 - 8;
+        assert!(matches!(v.try_reserve_exact(huge), Err(TryReserveError::AllocError { .. })));
+
+        // 失败后 Vector 原封不动
+        assert_eq!(v, [0, 1, 2, 3]);
+        assert_eq!(v.capacity(), 4);
+        assert_eq!(TryReserveError::CapacityOverflow.to_string(), "capacity overflow");
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_panics_on_overflow() {
+        let mut v: Vector<u64> = Vector::new();
+        v.reserve(usize::MAX / 4);
+    }
+
+    #[test]
+    fn test_shrink_on_pop_follows_policy() {
+        let mut exact: Vector<u32> = (0..64).collect();
+        exact.set_growth_policy(GrowthPolicy::Exact);
+        let mut double: Vector<u32> = (0..64).collect();
+        let (exact_cap, double_cap) = (exact.capacity(), double.capacity());
+        for _ in 0..60 {
+            exact.pop();
+            double.pop();
+        }
+        assert_eq!(exact.capacity(), exact_cap);
+        assert!(double.capacity() < double_cap);
+        assert_eq!(double, [0, 1, 2, 3]);
+
+        assert_eq!(GrowthPolicy::PageRounded.shrink_target(10, 1024, 4), None); // 已经只有一页
+        assert_eq!(GrowthPolicy::PageRounded.shrink_target(10, 4096, 4), Some(2048));
+    }
+
+    #[test]
+    fn test_zst_reserve_is_free() {
+        let mut v: Vector<()> = Vector::new();
+        v.push(());
+        v.reserve(1_000_000);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), usize::MAX);
+        assert_eq!(v.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+        assert_eq!(v.len(), 1);
+    }
+}
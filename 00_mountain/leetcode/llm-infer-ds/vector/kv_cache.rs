@@ -0,0 +1,378 @@
+// ==============================================================================
+// KvCache - 用 Vector<f32> 当块池的分页 KV Cache（PagedAttention 的 CPU 版）
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - vLLM CacheEngine: 启动时一次性分配 num_blocks 个物理块，之后只在块之间调度
+//   - vLLM BlockTable:  每个序列一个块列表，逻辑块 i -> 物理块 block_list[i]
+//
+// 【内存布局】
+//   k_pool / v_pool 各是一个 Vector<f32>，按物理块切分：
+//     block -> layer -> token(0..block_size) -> head -> head_dim
+//   一个块 = num_layers × block_size × num_heads × head_dim 个 f32，
+//   同一个物理块在所有层里都属于同一个序列（与 vLLM 相同，块表只需要一份）
+//
+// 【学习重点】
+//   1. 预分配：池子在 new() 里一次分配好，运行期间不再 alloc，也不会碎片化
+//   2. 逻辑连续、物理不连续：一个序列的 token 散落在多个物理块里，
+//      gather 按块表把它们拷贝成连续的 [tokens, heads, head_dim]（注意力 kernel 的输入）
+//   3. 逐层追加：前向计算一层一层地做，每层各自记录写到了第几个 token；
+//      哪一层先写满最后一个块，就由它分配新块，后面的层直接复用
+//   4. free：序列结束后块回到空闲栈，下一个序列马上可以复用
+//
+// 【错误处理】
+//   所有操作失败时都不修改缓存的状态，返回 KvCacheError
+// ==============================================================================
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{Tensor, Vector};
+
+pub type SeqId = usize;
+
+// 块的大小：一个物理块跨越所有层，而不是每层各自一套块
+//   - 每层里的那一段是 block_size × num_heads × head_dim 个 f32（K、V 各一份），
+//     也就是"每层一个块"的大小；num_layers 段拼在一起才是一个物理块
+//   - 好处是块表只需要一份：某个序列的逻辑块 i 在每一层都是同一个物理块
+//   - 代价是分配粒度：num_blocks 限制的是 token 数，不能让不同层各用不同数量的块
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KvCacheConfig {
+    pub num_layers: usize,
+    pub num_heads: usize,
+    pub head_dim: usize,
+    pub block_size: usize, // 每个块放几个 token
+    pub num_blocks: usize, // 物理块总数
+}
+
+impl KvCacheConfig {
+    // 一个 token 在一层里的 K（或 V）有多少个 f32
+    pub fn token_elems(&self) -> usize {
+        self.num_heads * self.head_dim
+    }
+
+    // 一个物理块在 K（或 V）池里占多少个 f32（所有层加起来）
+    pub fn block_elems(&self) -> usize {
+        self.num_layers * self.block_size * self.token_elems()
+    }
+
+    // 整个 K（或 V）池有多少个 f32；任何一步乘法溢出时返回 None
+    fn pool_elems(&self) -> Option<usize> {
+        [self.num_layers, self.block_size, self.num_heads, self.head_dim]
+            .into_iter()
+            .try_fold(self.num_blocks, usize::checked_mul)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvCacheError {
+    OutOfBlocks,                                        // 空闲块用完了，调度器应该抢占某个序列
+    UnknownSequence(SeqId),                             // 序列不存在（从未写入，或已经 free）
+    LayerOutOfRange { layer: usize, num_layers: usize }, // 层号超出 num_layers
+    ShapeMismatch { expected: usize, actual: usize },   // k / v 的长度不是 num_heads * head_dim
+}
+
+impl fmt::Display for KvCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KvCacheError::OutOfBlocks => write!(f, "no free kv cache blocks"),
+            KvCacheError::UnknownSequence(seq) => write!(f, "unknown sequence {}", seq),
+            KvCacheError::LayerOutOfRange { layer, num_layers } => {
+                write!(f, "layer {} out of range ({} layers)", layer, num_layers)
+            }
+            KvCacheError::ShapeMismatch { expected, actual } => {
+                write!(f, "expected {} values per token, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl Error for KvCacheError {}
+
+// 一个序列的状态
+#[derive(Debug, Clone, Default)]
+struct Sequence {
+    blocks: Vec<usize>,     // 块表：逻辑块 i -> 物理块
+    num_tokens: Vec<usize>, // 每层已经写了几个 token
+}
+
+#[derive(Debug)]
+pub struct KvCache {
+    config: KvCacheConfig,
+    k_pool: Vector<f32>,              // 所有块的 K
+    v_pool: Vector<f32>,              // 所有块的 V
+    free: Vec<usize>,                 // 空闲物理块（栈）
+    seqs: HashMap<SeqId, Sequence>,   // 序列 -> 块表
+}
+
+impl KvCache {
+    pub fn new(config: KvCacheConfig) -> Self {
+        assert!(config.block_size > 0, "block_size must be positive");
+        // 检查过总数不溢出之后，token_elems / block_elems 这些部分乘积也都不会溢出
+        let pool_elems = config
+            .pool_elems()
+            .expect("kv cache pool size overflows usize (num_blocks × num_layers × block_size × num_heads × head_dim)");
+        Self {
+            config,
+            k_pool: std::iter::repeat_n(0.0, pool_elems).collect(),
+            v_pool: std::iter::repeat_n(0.0, pool_elems).collect(),
+            // 倒序入栈，让 pop 先拿到 0 号块
+            free: (0..config.num_blocks).rev().collect(),
+            seqs: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &KvCacheConfig {
+        &self.config
+    }
+
+    pub fn num_free_blocks(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn num_sequences(&self) -> usize {
+        self.seqs.len()
+    }
+
+    pub fn contains(&self, seq: SeqId) -> bool {
+        self.seqs.contains_key(&seq)
+    }
+
+    // 序列在某一层已经写了几个 token
+    pub fn num_tokens(&self, seq: SeqId, layer: usize) -> Option<usize> {
+        self.seqs.get(&seq)?.num_tokens.get(layer).copied()
+    }
+
+    // 序列的块表
+    pub fn block_list(&self, seq: SeqId) -> Option<&[usize]> {
+        self.seqs.get(&seq).map(|s| s.blocks.as_slice())
+    }
+
+    // 两个池子一共占多少字节
+    pub fn pool_bytes(&self) -> usize {
+        (self.k_pool.len() + self.v_pool.len()) * std::mem::size_of::<f32>()
+    }
+
+    // (物理块, 层, 块内位置) -> 池子里的起始下标
+    fn slot_offset(&self, block: usize, layer: usize, offset: usize) -> usize {
+        let c = &self.config;
+        ((block * c.num_layers + layer) * c.block_size + offset) * c.token_elems()
+    }
+
+    fn check_layer(&self, layer: usize) -> Result<(), KvCacheError> {
+        if layer >= self.config.num_layers {
+            return Err(KvCacheError::LayerOutOfRange { layer, num_layers: self.config.num_layers });
+        }
+        Ok(())
+    }
+
+    // 把一个 token 某一层的 K / V 追加到序列末尾，返回它在序列里的位置。
+    // 序列第一次出现时自动创建
+    pub fn append_token(&mut self, seq: SeqId, layer: usize, k: &[f32], v: &[f32]) -> Result<usize, KvCacheError> {
+        // 1. 检查参数
+        self.check_layer(layer)?;
+        let expected = self.config.token_elems();
+        for actual in [k.len(), v.len()] {
+            if actual != expected {
+                return Err(KvCacheError::ShapeMismatch { expected, actual });
+            }
+        }
+
+        // 2. 这一层要写的位置；块表不够长就先分配一个新块（失败时什么都没改）
+        let position = self.num_tokens(seq, layer).unwrap_or(0);
+        let logical = position / self.config.block_size;
+        let allocated = self.block_list(seq).map_or(0, |blocks| blocks.len());
+        let new_block = if logical == allocated {
+            Some(self.free.pop().ok_or(KvCacheError::OutOfBlocks)?)
+        } else {
+            None
+        };
+
+        let num_layers = self.config.num_layers;
+        let state = self.seqs.entry(seq).or_insert_with(|| Sequence {
+            blocks: Vec::new(),
+            num_tokens: vec![0; num_layers],
+        });
+        if let Some(block) = new_block {
+            state.blocks.push(block);
+        }
+        let block = state.blocks[logical];
+        state.num_tokens[layer] += 1;
+
+        // 3. 写入池子
+        let start = self.slot_offset(block, layer, position % self.config.block_size);
+        self.k_pool[start..start + expected].copy_from_slice(k);
+        self.v_pool[start..start + expected].copy_from_slice(v);
+        Ok(position)
+    }
+
+    // 按块表把序列某一层的 K / V 拷贝成连续的 [tokens, num_heads, head_dim]
+    pub fn gather(&self, seq: SeqId, layer: usize) -> Result<(Tensor<f32>, Tensor<f32>), KvCacheError> {
+        self.check_layer(layer)?;
+        let state = self.seqs.get(&seq).ok_or(KvCacheError::UnknownSequence(seq))?;
+        let c = &self.config;
+        let num_tokens = state.num_tokens[layer];
+        let token_elems = c.token_elems();
+
+        let mut k = Vector::with_capacity(num_tokens * token_elems);
+        let mut v = Vector::with_capacity(num_tokens * token_elems);
+        // 每个块里这一层的 token 是连续的：整段拷贝，最后一块可能没写满
+        for (logical, &block) in state.blocks.iter().enumerate() {
+            let tokens = num_tokens.saturating_sub(logical * c.block_size).min(c.block_size);
+            if tokens == 0 {
+                break;
+            }
+            let start = self.slot_offset(block, layer, 0);
+            let end = start + tokens * token_elems;
+            k.extend_from_slice(&self.k_pool[start..end]);
+            v.extend_from_slice(&self.v_pool[start..end]);
+        }
+
+        let shape = [num_tokens, c.num_heads, c.head_dim];
+        let k = Tensor::from_vector(k, &shape).expect("gathered tokens match shape");
+        let v = Tensor::from_vector(v, &shape).expect("gathered tokens match shape");
+        Ok((k, v))
+    }
+
+    // 序列结束：所有块回到空闲栈，返回释放的块数
+    pub fn free(&mut self, seq: SeqId) -> Result<usize, KvCacheError> {
+        let state = self.seqs.remove(&seq).ok_or(KvCacheError::UnknownSequence(seq))?;
+        let freed = state.blocks.len();
+        self.free.extend(state.blocks);
+        Ok(freed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KvCache, KvCacheConfig, KvCacheError};
+
+    fn config(num_layers: usize, block_size: usize, num_blocks: usize) -> KvCacheConfig {
+        KvCacheConfig { num_layers, num_heads: 2, head_dim: 3, block_size, num_blocks }
+    }
+
+    // 可以从值反推出 (seq, layer, token)，方便检查 gather 的结果
+    fn token(seq: usize, layer: usize, pos: usize, sign: f32) -> Vec<f32> {
+        (0..6).map(|i| sign * (seq * 10_000 + layer * 1000 + pos * 10 + i) as f32).collect()
+    }
+
+    fn append(cache: &mut KvCache, seq: usize, layer: usize, pos: usize) -> Result<usize, KvCacheError> {
+        cache.append_token(seq, layer, &token(seq, layer, pos, 1.0), &token(seq, layer, pos, -1.0))
+    }
+
+    fn expected(seq: usize, layer: usize, tokens: usize, sign: f32) -> Vec<f32> {
+        (0..tokens).flat_map(|pos| token(seq, layer, pos, sign)).collect()
+    }
+
+    #[test]
+    fn test_append_and_gather() {
+        let mut cache = KvCache::new(config(1, 4, 8));
+        for pos in 0..6 {
+            assert_eq!(append(&mut cache, 7, 0, pos), Ok(pos));
+        }
+        // 6 个 token，每块 4 个：两个块
+        assert_eq!(cache.block_list(7), Some(&[0, 1][..]));
+        assert_eq!(cache.num_free_blocks(), 6);
+
+        let (k, v) = cache.gather(7, 0).unwrap();
+        assert_eq!(k.shape(), &[6, 2, 3]);
+        assert_eq!(k.to_vector(), expected(7, 0, 6, 1.0));
+        assert_eq!(v.to_vector(), expected(7, 0, 6, -1.0));
+        assert_eq!(k[[5, 1, 2]], token(7, 0, 5, 1.0)[5]);
+    }
+
+    #[test]
+    fn test_layers_share_the_block_list() {
+        let mut cache = KvCache::new(config(3, 2, 8));
+        // 前向一层一层地做：每个 token 依次写 3 层
+        for pos in 0..5 {
+            for layer in 0..3 {
+                append(&mut cache, 1, layer, pos).unwrap();
+            }
+        }
+        // 5 个 token / 每块 2 个 = 3 个块，所有层共用
+        assert_eq!(cache.block_list(1).unwrap().len(), 3);
+        for layer in 0..3 {
+            assert_eq!(cache.num_tokens(1, layer), Some(5));
+            let (k, v) = cache.gather(1, layer).unwrap();
+            assert_eq!(k.to_vector(), expected(1, layer, 5, 1.0));
+            assert_eq!(v.to_vector(), expected(1, layer, 5, -1.0));
+        }
+
+        // 某一层可以领先：第 0 层先写第 6 个 token，分配第 4 个块
+        append(&mut cache, 1, 0, 5).unwrap();
+        append(&mut cache, 1, 0, 6).unwrap();
+        assert_eq!(cache.block_list(1).unwrap().len(), 4);
+        assert_eq!(cache.gather(1, 2).unwrap().0.shape()[0], 5);
+    }
+
+    #[test]
+    fn test_free_and_reuse_non_contiguous_blocks() {
+        let mut cache = KvCache::new(config(2, 2, 6));
+        // 两个序列交替追加：块表交错 [0, 2, 4] 和 [1, 3, 5]
+        for pos in 0..6 {
+            for seq in [10, 20] {
+                for layer in 0..2 {
+                    append(&mut cache, seq, layer, pos).unwrap();
+                }
+            }
+        }
+        assert_eq!(cache.block_list(10), Some(&[0, 2, 4][..]));
+        assert_eq!(cache.num_free_blocks(), 0);
+        assert_eq!(append(&mut cache, 30, 0, 0), Err(KvCacheError::OutOfBlocks));
+        assert!(!cache.contains(30));
+
+        assert_eq!(cache.free(10), Ok(3));
+        assert_eq!(cache.num_free_blocks(), 3);
+        assert_eq!(cache.free(10), Err(KvCacheError::UnknownSequence(10)));
+
+        // 新序列复用序列 10 的块，序列 20 的数据不受影响
+        for pos in 0..5 {
+            append(&mut cache, 30, 1, pos).unwrap();
+        }
+        assert_eq!(cache.block_list(30).unwrap().len(), 3);
+        assert_eq!(cache.gather(30, 1).unwrap().0.to_vector(), expected(30, 1, 5, 1.0));
+        assert_eq!(cache.gather(30, 0).unwrap().0.numel(), 0);
+        assert_eq!(cache.gather(20, 1).unwrap().1.to_vector(), expected(20, 1, 6, -1.0));
+    }
+
+    #[test]
+    fn test_invalid_arguments_leave_state_unchanged() {
+        let mut cache = KvCache::new(config(2, 4, 2));
+        assert_eq!(
+            cache.append_token(1, 2, &[0.0; 6], &[0.0; 6]),
+            Err(KvCacheError::LayerOutOfRange { layer: 2, num_layers: 2 })
+        );
+        assert_eq!(
+            cache.append_token(1, 0, &[0.0; 6], &[0.0; 5]),
+            Err(KvCacheError::ShapeMismatch { expected: 6, actual: 5 })
+        );
+        assert!(!cache.contains(1));
+        assert_eq!(cache.num_free_blocks(), 2);
+        assert_eq!(cache.gather(1, 0).unwrap_err(), KvCacheError::UnknownSequence(1));
+        assert!(cache.gather(1, 5).is_err());
+    }
+
+    #[test]
+    fn test_pool_is_preallocated() {
+        let cfg = config(4, 16, 32);
+        let mut cache = KvCache::new(cfg);
+        assert_eq!(cfg.block_elems(), 4 * 16 * 6);
+        assert_eq!(cache.pool_bytes(), 2 * 32 * cfg.block_elems() * 4);
+
+        let k_ptr = cache.k_pool.as_ptr();
+        for pos in 0..100 {
+            append(&mut cache, 0, 3, pos).unwrap();
+        }
+        // 追加 token 只在池子里写，不会重新分配
+        assert_eq!(cache.k_pool.as_ptr(), k_ptr);
+        assert_eq!(cache.num_free_blocks(), 32 - 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "kv cache pool size overflows usize")]
+    fn test_pool_size_overflow_panics() {
+        KvCache::new(KvCacheConfig { num_layers: 80, num_heads: 64, head_dim: 128, block_size: 16, num_blocks: usize::MAX / 1024 });
+    }
+}
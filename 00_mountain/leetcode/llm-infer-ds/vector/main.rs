@@ -0,0 +1,54 @@
+// ==============================================================================
+// Vector 教学演示 - cargo run -p vector
+// ==============================================================================
+//
+// Vector 本身在 vector.rs（lib，供 KV Cache / Tensor 等模块复用），
+// 这里保留原来 vector.rs 末尾分步骤测试的 main
+// ==============================================================================
+
+// ==============================================================================
+// 【Phase 3: 语法教学 - 分步骤实现和测试】
+// ==============================================================================
+
+use vector::Vector;
+
+fn main() {
+    println!("=== Rust Vector 教学测试 ===\n");
+
+    // 【第一步：测试基本创建】
+    // 任务：创建 Vector 实例并检查初始状态
+    // 提示：Vector<i32> v = Vector::new();
+    // println!("[Step 1] Vector 创建完成, size={}, capacity={}", v.size(), v.capacity());
+    let mut v = Vector::<i32>::new();
+    println!("[Step 1] Vector 创建完成, size={}, capacity={}", v.size(), v.capacity());
+
+    // 【第二步：测试 push 和扩容】
+    // 任务：添加元素并观察扩容
+    // 提示：for i in 1..=8 { v.push(i); }
+    // println!("[Step 2] 扩容后 size={}, capacity={}", v.size(), v.capacity());
+    for i in 1..=8{
+        v.push(i);
+    }
+    println!("[Step 2] 扩容后 size={}, capacity={}", v.size(), v.capacity());
+
+    // 【第三步：测试索引访问】
+    // 任务：使用 [] 语法访问元素
+    // 提示：println!("[Step 3] 第一个元素: {}", v[0]);
+    println!("[Step 3] 第三个元素: {}", v[2]);
+
+    // 【第四步：测试 stride】
+    // 任务：实现步长访问
+    // 提示：let result = v.stride(1, 2);
+    // println!("[Step 4] stride 结果: {:?}", result);
+    let result = v.stride(v.size() - 1, -2);
+    println!("[Step 4] stride 结果: {:?}", result);
+
+    // 【第五步：测试 pop】
+    // 任务：弹出元素并观察缩容
+    // 提示：while let Some(val) = v.pop() { print!("{} ", val); }
+    while let Some(val) = v.pop() {
+        print!("{}", val);
+    }
+    println!();
+    println!("运行: cargo run -p vector");
+}
\ No newline at end of file
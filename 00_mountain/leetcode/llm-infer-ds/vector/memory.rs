@@ -0,0 +1,418 @@
+// ==============================================================================
+// 内存记账 - 按类别统计 Vector 分配了多少字节（当前值 / 峰值 / 次数）
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - torch.cuda.memory_allocated() / max_memory_allocated() / reset_peak_memory_stats()
+//   - vLLM profile_run：先跑一次最大 batch 量出激活值峰值，剩下的显存才分给 KV Cache
+//
+// 【怎么打开】（opt-in）
+//   记账是一个包在分配器外面的 Tracked<A>，只有用它创建的 Vector 才会被统计：
+//     let k: Vector<f32, Tracked> = Vector::with_capacity_in(n, Tracked::new(Category::KvCache));
+//     let w: Vector<f32, Tracked<&PoolAlloc>> = Vector::with_capacity_in(n, Tracked::with_tracker(Category::Weights, &TRACKER, &pool));
+//   普通的 Vector<T>（Global）没有任何额外开销
+//
+// 【统计什么】（每个类别一份，再加一份 total）
+//   current_bytes:     现在还活着的字节数
+//   peak_bytes:        current_bytes 的历史最大值（reset_peak() 之后重新计）
+//   live_allocations:  现在还活着的分配个数
+//   total_allocations: 累计 allocate 次数
+//   reallocations:     累计 reallocate 次数（扩容 / 缩容）
+//
+// 【多线程】
+//   全部是原子计数，Tracked 可以 Copy 到多个线程里；
+//   snapshot() 逐个读计数器，不是一个全局一致的快照，画曲线足够了
+//
+// 【输出】
+//   snapshot() 返回 MemorySnapshot（Display 是一张表）；
+//   打开 serde feature 后可以直接序列化成 JSON 发给可视化大屏
+// ==============================================================================
+
+use std::alloc::Layout;
+use std::fmt;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{AllocError, Global, RawAlloc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Category {
+    KvCache,
+    Activations,
+    Weights,
+}
+
+impl Category {
+    pub const ALL: [Category; 3] = [Category::KvCache, Category::Activations, Category::Weights];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::KvCache => "kv_cache",
+            Category::Activations => "activations",
+            Category::Weights => "weights",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CategoryStats {
+    pub current_bytes: usize,
+    pub peak_bytes: usize,
+    pub live_allocations: usize,
+    pub total_allocations: usize,
+    pub reallocations: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MemorySnapshot {
+    pub kv_cache: CategoryStats,
+    pub activations: CategoryStats,
+    pub weights: CategoryStats,
+    pub total: CategoryStats, // 所有类别加起来；peak 是总量的峰值，不是各类峰值之和
+}
+
+impl MemorySnapshot {
+    pub fn get(&self, category: Category) -> CategoryStats {
+        match category {
+            Category::KvCache => self.kv_cache,
+            Category::Activations => self.activations,
+            Category::Weights => self.weights,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Category, CategoryStats)> + '_ {
+        Category::ALL.into_iter().map(|category| (category, self.get(category)))
+    }
+}
+
+impl fmt::Display for MemorySnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.iter().map(|(category, stats)| (category.name(), stats)).chain([("total", self.total)]);
+        for (name, stats) in rows {
+            writeln!(
+                f,
+                "{:<12} current {:>12} B | peak {:>12} B | live {:>6} | allocs {:>8} | reallocs {:>8}",
+                name,
+                stats.current_bytes,
+                stats.peak_bytes,
+                stats.live_allocations,
+                stats.total_allocations,
+                stats.reallocations,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// 一个类别的计数器
+#[derive(Debug)]
+struct Counters {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    live: AtomicUsize,
+    allocs: AtomicUsize,
+    reallocs: AtomicUsize,
+}
+
+impl Counters {
+    const fn new() -> Self {
+        Self {
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            live: AtomicUsize::new(0),
+            allocs: AtomicUsize::new(0),
+            reallocs: AtomicUsize::new(0),
+        }
+    }
+
+    fn grow(&self, bytes: usize) {
+        let now = self.current.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.current.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    fn on_alloc(&self, bytes: usize) {
+        self.grow(bytes);
+        self.live.fetch_add(1, Ordering::Relaxed);
+        self.allocs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_dealloc(&self, bytes: usize) {
+        self.shrink(bytes);
+        self.live.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn on_realloc(&self, old: usize, new: usize) {
+        if new > old {
+            self.grow(new - old);
+        } else {
+            self.shrink(old - new);
+        }
+        self.reallocs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset_peak(&self) {
+        self.peak.store(self.current.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> CategoryStats {
+        CategoryStats {
+            current_bytes: self.current.load(Ordering::Relaxed),
+            peak_bytes: self.peak.load(Ordering::Relaxed),
+            live_allocations: self.live.load(Ordering::Relaxed),
+            total_allocations: self.allocs.load(Ordering::Relaxed),
+            reallocations: self.reallocs.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// 一组按类别的计数器。通常用全局的那个（MemoryTracker::global()），
+// 测试 / 实验想要互不干扰时可以自己建一个 static
+#[derive(Debug)]
+pub struct MemoryTracker {
+    categories: [Counters; Category::ALL.len()],
+    total: Counters,
+}
+
+static GLOBAL_TRACKER: MemoryTracker = MemoryTracker::new();
+
+impl MemoryTracker {
+    pub const fn new() -> Self {
+        Self { categories: [Counters::new(), Counters::new(), Counters::new()], total: Counters::new() }
+    }
+
+    pub fn global() -> &'static MemoryTracker {
+        &GLOBAL_TRACKER
+    }
+
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            kv_cache: self.categories[Category::KvCache.index()].stats(),
+            activations: self.categories[Category::Activations.index()].stats(),
+            weights: self.categories[Category::Weights.index()].stats(),
+            total: self.total.stats(),
+        }
+    }
+
+    // 峰值从当前值重新开始计（比如每个 step 开始时调用一次，看单步的峰值）
+    pub fn reset_peak(&self) {
+        for counters in &self.categories {
+            counters.reset_peak();
+        }
+        self.total.reset_peak();
+    }
+
+    fn counters(&self, category: Category) -> [&Counters; 2] {
+        [&self.categories[category.index()], &self.total]
+    }
+}
+
+impl Default for MemoryTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 全局计数器的快照
+pub fn snapshot() -> MemorySnapshot {
+    MemoryTracker::global().snapshot()
+}
+
+pub fn reset_peak() {
+    MemoryTracker::global().reset_peak()
+}
+
+// 记账分配器：把每次分配记到 tracker 的 category 下，真正的分配交给 inner
+#[derive(Debug, Clone, Copy)]
+pub struct Tracked<A: RawAlloc = Global> {
+    category: Category,
+    tracker: &'static MemoryTracker,
+    inner: A,
+}
+
+impl Tracked {
+    // 记到全局计数器，内存来自 Global
+    pub fn new(category: Category) -> Self {
+        Self::with_tracker(category, MemoryTracker::global(), Global)
+    }
+}
+
+impl<A: RawAlloc> Tracked<A> {
+    pub fn with_tracker(category: Category, tracker: &'static MemoryTracker, inner: A) -> Self {
+        Self { category, tracker, inner }
+    }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+}
+
+// 只记成功的分配：池超预算之类的失败不会改变计数
+unsafe impl<A: RawAlloc> RawAlloc for Tracked<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let ptr = self.inner.allocate(layout)?;
+        for counters in self.tracker.counters(self.category) {
+            counters.on_alloc(layout.size());
+        }
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { self.inner.deallocate(ptr, layout) };
+        for counters in self.tracker.counters(self.category) {
+            counters.on_dealloc(layout.size());
+        }
+    }
+
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = unsafe { self.inner.reallocate(ptr, old_layout, new_layout)? };
+        for counters in self.tracker.counters(self.category) {
+            counters.on_realloc(old_layout.size(), new_layout.size());
+        }
+        Ok(new_ptr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Category, CategoryStats, MemoryTracker, Tracked};
+    use crate::{PoolAlloc, Vector};
+
+    fn tracked(category: Category, tracker: &'static MemoryTracker) -> Tracked {
+        Tracked::with_tracker(category, tracker, crate::Global)
+    }
+
+    #[test]
+    fn test_current_and_peak_per_category() {
+        static TRACKER: MemoryTracker = MemoryTracker::new();
+        let mut kv: Vector<f32, Tracked> = Vector::with_capacity_in(256, tracked(Category::KvCache, &TRACKER));
+        let weights: Vector<f32, Tracked> = Vector::with_capacity_in(1024, tracked(Category::Weights, &TRACKER));
+        let snap = TRACKER.snapshot();
+        assert_eq!(
+            snap.kv_cache,
+            CategoryStats { current_bytes: 1024, peak_bytes: 1024, live_allocations: 1, total_allocations: 1, reallocations: 0 }
+        );
+        assert_eq!(snap.weights.current_bytes, 4096);
+        assert_eq!(snap.activations, CategoryStats::default());
+        assert_eq!(snap.total.current_bytes, 5120);
+
+        // 扩容走 realloc：字节数变了，分配个数不变
+        kv.extend_from_slice(&[0.0; 300]);
+        let snap = TRACKER.snapshot();
+        assert_eq!(snap.kv_cache.current_bytes, 512 * 4);
+        assert_eq!(snap.kv_cache.live_allocations, 1);
+        assert_eq!(snap.kv_cache.reallocations, 1);
+
+        // 释放后 current 回落，peak 保留
+        drop(kv);
+        let snap = TRACKER.snapshot();
+        assert_eq!(snap.kv_cache.current_bytes, 0);
+        assert_eq!(snap.kv_cache.peak_bytes, 2048);
+        assert_eq!(snap.kv_cache.live_allocations, 0);
+        assert_eq!(snap.total.peak_bytes, 2048 + 4096);
+
+        TRACKER.reset_peak();
+        let snap = TRACKER.snapshot();
+        assert_eq!(snap.kv_cache.peak_bytes, 0);
+        assert_eq!(snap.total.peak_bytes, 4096);
+        drop(weights);
+        assert_eq!(TRACKER.snapshot().total.current_bytes, 0);
+    }
+
+    #[test]
+    fn test_peak_within_a_step() {
+        // 一个"前向 step"：激活值申请又释放，reset_peak 之后能单独看到这一步的峰值
+        static TRACKER: MemoryTracker = MemoryTracker::new();
+        let _weights: Vector<u8, Tracked> = Vector::with_capacity_in(1000, tracked(Category::Weights, &TRACKER));
+        for step in 1..=3 {
+            TRACKER.reset_peak();
+            {
+                let _a: Vector<u8, Tracked> = Vector::with_capacity_in(100 * step, tracked(Category::Activations, &TRACKER));
+                let _b: Vector<u8, Tracked> = Vector::with_capacity_in(10, tracked(Category::Activations, &TRACKER));
+            }
+            let snap = TRACKER.snapshot();
+            assert_eq!(snap.activations.current_bytes, 0);
+            assert_eq!(snap.activations.peak_bytes, 100 * step + 10);
+            assert_eq!(snap.total.peak_bytes, 1000 + 100 * step + 10);
+        }
+        assert_eq!(TRACKER.snapshot().activations.total_allocations, 6);
+    }
+
+    #[test]
+    fn test_composes_with_pool_and_ignores_failures() {
+        static TRACKER: MemoryTracker = MemoryTracker::new();
+        let gpu = PoolAlloc::new(64);
+        let alloc = Tracked::with_tracker(Category::KvCache, &TRACKER, &gpu);
+        let mut v: Vector<u64, Tracked<&PoolAlloc>> = Vector::with_capacity_in(8, alloc);
+        v.extend_from_slice(&[1; 8]);
+        assert!(v.try_push(9).is_err());
+        let snap = TRACKER.snapshot();
+        assert_eq!(snap.kv_cache.current_bytes, 64);
+        assert_eq!(snap.kv_cache.reallocations, 0);
+        assert_eq!(v.allocator().category(), Category::KvCache);
+    }
+
+    #[test]
+    fn test_threads_share_a_tracker() {
+        static TRACKER: MemoryTracker = MemoryTracker::new();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for n in 0..200 {
+                        let mut v: Vector<u32, Tracked> = Vector::with_capacity_in(0, tracked(Category::Activations, &TRACKER));
+                        v.extend(0..n);
+                    }
+                });
+            }
+        });
+        let snap = TRACKER.snapshot();
+        assert_eq!(snap.activations.current_bytes, 0);
+        assert_eq!(snap.activations.live_allocations, 0);
+        assert!(snap.activations.peak_bytes >= 199 * 4);
+    }
+
+    #[test]
+    fn test_global_tracker_and_display() {
+        // 全局计数器可能被别的测试同时使用，只看差值
+        let before = super::snapshot().weights;
+        let v: Vector<f32, Tracked> = Vector::with_capacity_in(10, Tracked::new(Category::Weights));
+        let after = super::snapshot().weights;
+        assert_eq!(after.total_allocations - before.total_allocations, 1);
+        drop(v);
+        super::reset_peak();
+
+        let text = super::snapshot().to_string();
+        for name in ["kv_cache", "activations", "weights", "total"] {
+            assert!(text.contains(name), "{}", text);
+        }
+        assert_eq!(Category::KvCache.to_string(), "kv_cache");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_serializes_to_json() {
+        static TRACKER: MemoryTracker = MemoryTracker::new();
+        let _v: Vector<u8, Tracked> = Vector::with_capacity_in(16, tracked(Category::KvCache, &TRACKER));
+        let json = serde_json::to_value(TRACKER.snapshot()).unwrap();
+        assert_eq!(json["kv_cache"]["current_bytes"], 16);
+        assert_eq!(json["total"]["live_allocations"], 1);
+        assert_eq!(serde_json::to_value(Category::KvCache).unwrap(), "kv_cache");
+    }
+}
@@ -0,0 +1,458 @@
+// ==============================================================================
+// RawAlloc - Vector 的分配器参数：模拟 "GPU 显存池" / "CPU swap 池"
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - vLLM 的 gpu_memory_utilization：显存按固定预算切给 KV Cache，超出就要 swap / 抢占
+//   - vLLM CPU swap space：换出的 KV 块放在另一块有上限的 CPU 内存里
+//   - PyTorch CUDACachingAllocator：OOM 时报告"要了多少、已用多少、总共多少"
+//
+// 【结构】
+//   RawAlloc:  最小的分配器接口（allocate / deallocate / reallocate），Vector<T, A> 的 A
+//   Global:    直接转发给 std::alloc，是 A 的默认值，Vector<T> 的行为与之前完全相同
+//   PoolAlloc: 有字节上限的池，超出预算时返回 AllocError::OutOfMemory，可以被多个 Vector 共享
+//   BumpAlloc: 一次性申请一大块，按顺序切；单个释放不回收，reset() 一次全部回收
+//
+// 【共享】
+//   &A 也实现了 RawAlloc：Vector<f32, &PoolAlloc> 借用同一个池，
+//   池的预算是所有借用它的 Vector 加起来的
+//
+// 【为什么是 unsafe trait】
+//   Vector 完全信任分配器返回的指针（对齐、大小、不与别的分配重叠），
+//   实现错了就是 UB，所以实现者必须用 unsafe impl 承诺这一点
+// ==============================================================================
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocError {
+    OutOfMemory { requested: usize, in_use: usize, limit: usize }, // 池 / arena 的预算不够
+    SystemFailure,                                                 // 系统分配器返回了 null
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocError::OutOfMemory { requested, in_use, limit } => write!(
+                f,
+                "out of memory: tried to allocate {} bytes ({} of {} bytes in use)",
+                requested, in_use, limit
+            ),
+            AllocError::SystemFailure => write!(f, "system allocator returned null"),
+        }
+    }
+}
+
+impl Error for AllocError {}
+
+/// # Safety
+/// - allocate / reallocate 成功时返回的指针按 layout 对齐，至少有 layout.size() 字节可用，
+///   在 deallocate 之前不会再分给别人
+/// - 调用方只会把本分配器返回的指针、连同分配时的 layout 交给 deallocate / reallocate
+/// - 调用方不会用 size 为 0 的 layout 调用（Vector 对 ZST 和容量 0 从不分配）
+pub unsafe trait RawAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// # Safety
+    /// ptr 来自本分配器，layout 与分配时相同
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// 把 ptr 的前 min(old, new) 字节搬到大小为 new_layout 的新内存里。
+    /// 失败时返回 Err，旧内存仍然有效。默认实现是 allocate + copy + deallocate
+    ///
+    /// # Safety
+    /// ptr 来自本分配器，old_layout 与分配时相同
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        unsafe { realloc_by_copy(self, ptr, old_layout, new_layout) }
+    }
+}
+
+unsafe fn realloc_by_copy<A: RawAlloc + ?Sized>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<u8>, AllocError> {
+    let new_ptr = alloc.allocate(new_layout)?;
+    unsafe {
+        let count = old_layout.size().min(new_layout.size());
+        std::ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), count);
+        alloc.deallocate(ptr, old_layout);
+    }
+    Ok(new_ptr)
+}
+
+// 借用同一个分配器：多个 Vector 共享一个池
+unsafe impl<A: RawAlloc + ?Sized> RawAlloc for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        unsafe { (**self).reallocate(ptr, old_layout, new_layout) }
+    }
+}
+
+// ==============================================================================
+// Global - std::alloc 的薄包装
+// ==============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl RawAlloc for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        NonNull::new(unsafe { std::alloc::alloc(layout) }).ok_or(AllocError::SystemFailure)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { std::alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        // std::alloc::realloc 只能保持原来的对齐
+        if old_layout.align() != new_layout.align() {
+            return unsafe { realloc_by_copy(self, ptr, old_layout, new_layout) };
+        }
+        let new_ptr = unsafe { std::alloc::realloc(ptr.as_ptr(), old_layout, new_layout.size()) };
+        NonNull::new(new_ptr).ok_or(AllocError::SystemFailure)
+    }
+}
+
+// ==============================================================================
+// PoolAlloc - 有字节上限的池
+// ==============================================================================
+//
+// 内存本身仍然来自 Global，池只负责记账：in_use + 请求 > limit 时拒绝。
+// in_use 用原子变量，&PoolAlloc 可以跨线程共享（多个 worker 往同一块"显存"里分配）
+
+#[derive(Debug)]
+pub struct PoolAlloc {
+    limit: usize,
+    in_use: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl PoolAlloc {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, in_use: AtomicUsize::new(0), peak: AtomicUsize::new(0) }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Relaxed)
+    }
+
+    pub fn available(&self) -> usize {
+        self.limit - self.in_use()
+    }
+
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    // 先占预算再分配：两个线程同时分配时不会一起越过上限
+    fn charge(&self, bytes: usize) -> Result<(), AllocError> {
+        let mut in_use = self.in_use.load(Ordering::Relaxed);
+        loop {
+            let next = match in_use.checked_add(bytes) {
+                Some(next) if next <= self.limit => next,
+                _ => return Err(AllocError::OutOfMemory { requested: bytes, in_use, limit: self.limit }),
+            };
+            match self.in_use.compare_exchange_weak(in_use, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => {
+                    self.peak.fetch_max(next, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(actual) => in_use = actual,
+            }
+        }
+    }
+
+    fn refund(&self, bytes: usize) {
+        self.in_use.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+unsafe impl RawAlloc for PoolAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.charge(layout.size())?;
+        Global.allocate(layout).inspect_err(|_| self.refund(layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { Global.deallocate(ptr, layout) };
+        self.refund(layout.size());
+    }
+
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        // 只对增长的部分记账；缩小时先搬完再退还
+        let (old, new) = (old_layout.size(), new_layout.size());
+        if new > old {
+            self.charge(new - old)?;
+        }
+        match unsafe { Global.reallocate(ptr, old_layout, new_layout) } {
+            Ok(new_ptr) => {
+                if new < old {
+                    self.refund(old - new);
+                }
+                Ok(new_ptr)
+            }
+            Err(err) => {
+                if new > old {
+                    self.refund(new - old);
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+// ==============================================================================
+// BumpAlloc - arena：顺序切分一大块内存
+// ==============================================================================
+//
+// 一次前向计算里的临时激活值：生命周期相同，一起申请、一起丢弃。
+//   allocate:   offset 按对齐向上取整后往后推，O(1)
+//   deallocate: 只有最后一次分配能退回（栈式），其余的等 reset
+//   reallocate: 最后一次分配且后面还有空间时原地扩，Vector 在 arena 里 push 不用搬数据
+//   reset:      需要 &mut self，借用检查器保证此时没有 Vector 还在用 arena 里的内存
+
+pub struct BumpAlloc {
+    chunk: NonNull<u8>,
+    layout: Layout,
+    offset: Cell<usize>, // 下一次分配从 chunk + offset 开始（取整前）
+    last: Cell<usize>,   // 最后一次分配的起点，用来栈式回退 / 原地扩
+}
+
+impl BumpAlloc {
+    // chunk 按缓存行对齐，常见类型的对齐要求都不需要额外跳过字节
+    const CHUNK_ALIGN: usize = 64;
+
+    pub fn new(capacity: usize) -> Self {
+        let layout = Layout::from_size_align(capacity.max(1), Self::CHUNK_ALIGN).expect("arena too large");
+        let chunk = Global.allocate(layout).unwrap_or_else(|_| std::alloc::handle_alloc_error(layout));
+        Self { chunk, layout, offset: Cell::new(0), last: Cell::new(usize::MAX) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+
+    // 回收所有分配
+    pub fn reset(&mut self) {
+        self.offset.set(0);
+        self.last.set(usize::MAX);
+    }
+
+    // 在 chunk 里找一块按 layout 对齐的空间，返回起点偏移
+    fn bump(&self, layout: Layout) -> Result<usize, AllocError> {
+        let base = self.chunk.as_ptr() as usize;
+        let out_of_memory = AllocError::OutOfMemory {
+            requested: layout.size(),
+            in_use: self.offset.get(),
+            limit: self.capacity(),
+        };
+        let start = (base + self.offset.get())
+            .checked_next_multiple_of(layout.align())
+            .ok_or(out_of_memory)?
+            - base;
+        let end = start.checked_add(layout.size()).ok_or(out_of_memory)?;
+        if end > self.capacity() {
+            return Err(out_of_memory);
+        }
+        self.offset.set(end);
+        self.last.set(start);
+        Ok(start)
+    }
+
+    fn offset_of(&self, ptr: NonNull<u8>) -> usize {
+        ptr.as_ptr() as usize - self.chunk.as_ptr() as usize
+    }
+}
+
+unsafe impl RawAlloc for BumpAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let start = self.bump(layout)?;
+        Ok(unsafe { NonNull::new_unchecked(self.chunk.as_ptr().add(start)) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        // 释放的是最后一次分配：offset 退回去，这块空间马上可以再用
+        if self.offset_of(ptr) == self.last.get() {
+            self.offset.set(self.last.get());
+            self.last.set(usize::MAX);
+        }
+    }
+
+    unsafe fn reallocate(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let start = self.offset_of(ptr);
+        let aligned = (ptr.as_ptr() as usize).is_multiple_of(new_layout.align());
+        if start == self.last.get() && aligned {
+            // 最后一次分配：直接挪 offset，不搬数据
+            let end = start.checked_add(new_layout.size());
+            if let Some(end) = end.filter(|&end| end <= self.capacity()) {
+                self.offset.set(end);
+                return Ok(ptr);
+            }
+            return Err(AllocError::OutOfMemory {
+                requested: new_layout.size(),
+                in_use: self.offset.get(),
+                limit: self.capacity(),
+            });
+        }
+        unsafe { realloc_by_copy(self, ptr, old_layout, new_layout) }
+    }
+}
+
+impl Drop for BumpAlloc {
+    fn drop(&mut self) {
+        unsafe { Global.deallocate(self.chunk, self.layout) }
+    }
+}
+
+impl fmt::Debug for BumpAlloc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BumpAlloc").field("used", &self.used()).field("capacity", &self.capacity()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocError, BumpAlloc, PoolAlloc};
+    use crate::{TryReserveError, Vector};
+
+    #[test]
+    fn test_pool_tracks_bytes_across_vectors() {
+        let gpu = PoolAlloc::new(1024);
+        let mut a: Vector<f32, &PoolAlloc> = Vector::with_capacity_in(64, &gpu);
+        let mut b: Vector<f32, &PoolAlloc> = Vector::with_capacity_in(32, &gpu);
+        assert_eq!(gpu.in_use(), 96 * 4);
+
+        a.extend_from_slice(&[1.0; 64]);
+        b.extend_from_slice(&[2.0; 32]);
+        a.truncate(16);
+        a.shrink_to_fit();
+        assert_eq!(gpu.in_use(), (16 + 32) * 4);
+        drop(a);
+        assert_eq!(gpu.in_use(), 32 * 4);
+        assert_eq!(gpu.peak(), 96 * 4);
+        drop(b);
+        assert_eq!(gpu.in_use(), 0);
+    }
+
+    #[test]
+    fn test_pool_reports_typed_oom_through_try_push() {
+        let gpu = PoolAlloc::new(64);
+        let mut v: Vector<u64, &PoolAlloc> = Vector::new_in(&gpu);
+        for i in 0..8 {
+            v.try_push(i).unwrap();
+        }
+        // 第 9 个要扩到 16 × 8 字节，超出 64 字节的预算：值原样退回，Vector 不变
+        let (value, err) = v.try_push(8).unwrap_err();
+        assert_eq!(value, 8);
+        match err {
+            TryReserveError::AllocError { cause, .. } => {
+                assert_eq!(cause, AllocError::OutOfMemory { requested: 64, in_use: 64, limit: 64 })
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert_eq!(v, [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(gpu.in_use(), 64);
+
+        // 腾出空间之后可以继续
+        v.truncate(2);
+        v.shrink_to_fit();
+        v.try_push(2).unwrap();
+        assert_eq!(v, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_swap_between_pools() {
+        // 显存放不下第二个序列的 KV：换出到 CPU 池
+        let gpu = PoolAlloc::new(256);
+        let cpu = PoolAlloc::new(4096);
+        let mut seq0: Vector<f32, &PoolAlloc> = Vector::with_capacity_in(48, &gpu);
+        seq0.extend_from_slice(&[0.5; 48]);
+        assert!(Vector::<f32, &PoolAlloc>::new_in(&gpu).try_reserve_exact(48).is_err());
+
+        let mut swapped: Vector<f32, &PoolAlloc> = Vector::with_capacity_in(seq0.len(), &cpu);
+        swapped.extend_from_slice(&seq0);
+        drop(seq0);
+        assert_eq!(gpu.in_use(), 0);
+        assert_eq!(cpu.in_use(), 48 * 4);
+
+        let mut seq1: Vector<f32, &PoolAlloc> = Vector::new_in(&gpu);
+        seq1.try_reserve_exact(48).unwrap();
+        assert_eq!(swapped[47], 0.5);
+    }
+
+    #[test]
+    fn test_bump_grows_last_allocation_in_place() {
+        let arena = BumpAlloc::new(8192);
+        let mut v: Vector<u32, &BumpAlloc> = Vector::new_in(&arena);
+        v.push(0);
+        let ptr = v.as_ptr();
+        for i in 1..500 {
+            v.push(i);
+        }
+        // 一直是 arena 里最后一块：原地扩，从没搬过
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(arena.used(), v.capacity() * 4);
+
+        // 后面又分配了别的，再扩就只能搬
+        let other: Vector<u8, &BumpAlloc> = Vector::with_capacity_in(8, &arena);
+        v.reserve_exact(v.capacity() - v.len() + 1);
+        assert_ne!(v.as_ptr(), ptr);
+        assert_eq!(v.iter().copied().sum::<u32>(), (0..500).sum::<u32>());
+        drop(other);
+    }
+
+    #[test]
+    fn test_bump_oom_and_reset() {
+        let mut arena = BumpAlloc::new(128);
+        {
+            let mut v: Vector<u64, &BumpAlloc> = Vector::new_in(&arena);
+            for i in 0..16 {
+                v.try_push(i).unwrap();
+            }
+            assert!(matches!(
+                v.try_push(16),
+                Err((16, TryReserveError::AllocError { cause: AllocError::OutOfMemory { limit: 128, .. }, .. }))
+            ));
+            let mut strings: Vector<String, &BumpAlloc> = Vector::with_capacity_in(0, &arena);
+            assert!(strings.try_push("no room".to_string()).is_err());
+            assert!(Vector::<u8, &BumpAlloc>::try_with_capacity_in(1, &arena).is_err());
+        }
+        // 最后一块释放时已经退回，reset 之后整个 arena 都能再用
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+        let v: Vector<u8, &BumpAlloc> = Vector::with_capacity_in(128, &arena);
+        assert_eq!(v.capacity(), 128);
+    }
+
+    #[test]
+    fn test_bump_respects_alignment() {
+        let arena = BumpAlloc::new(1024);
+        let _byte: Vector<u8, &BumpAlloc> = Vector::with_capacity_in(3, &arena);
+        let wide: Vector<u128, &BumpAlloc> = Vector::with_capacity_in(4, &arena);
+        assert!((wide.as_ptr() as usize).is_multiple_of(std::mem::align_of::<u128>()));
+        assert_eq!(arena.used(), 16 + 64);
+    }
+}
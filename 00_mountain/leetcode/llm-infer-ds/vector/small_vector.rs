@@ -0,0 +1,421 @@
+// ==============================================================================
+// SmallVector<T, N> - 前 N 个元素放在结构体内部，放不下再搬到堆上
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - vLLM BlockTable：大多数序列只有几个块，块表却是 list，每个序列至少一次堆分配
+//   - LLVM SmallVector / Rust smallvec：短列表完全不碰分配器
+//
+// 【结构】
+//   Inline { buf: [MaybeUninit<T>; N], len }: buf[..len] 是活着的元素，其余未初始化
+//   Heap(Vector<T>):                          超过 N 个之后，所有元素搬进一个 Vector
+//
+// 【学习重点】
+//   1. MaybeUninit：数组里未初始化的槽位不能被当作 T 读取或 drop，
+//      与 Vector 的 [size, capacity) 是同一个道理，只是内存在结构体里而不是堆上
+//   2. spill：第 N + 1 次 push 时把 N 个元素按位搬进 Vector（只是 memcpy，不调用 clone / drop）
+//   3. 搬到堆上之后就留在堆上（与 smallvec 相同），pop 不会搬回来；
+//      需要时显式调用 shrink_to_fit
+//   4. drop 语义与 Vector 相同：push 进来的元素恰好被 drop 一次
+// ==============================================================================
+
+use std::mem::MaybeUninit;
+
+use crate::Vector;
+
+enum Storage<T, const N: usize> {
+    Inline { buf: [MaybeUninit<T>; N], len: usize },
+    Heap(Vector<T>),
+}
+
+pub struct SmallVector<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+impl<T, const N: usize> SmallVector<T, N> {
+    pub fn new() -> Self {
+        Self { storage: Storage::Inline { buf: [const { MaybeUninit::uninit() }; N], len: 0 } }
+    }
+
+    // capacity 不超过 N 时不分配
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            return Self::new();
+        }
+        Self { storage: Storage::Heap(Vector::with_capacity(capacity)) }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Heap(vector) => vector.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { .. } => N,
+            Storage::Heap(vector) => vector.capacity(),
+        }
+    }
+
+    // 元素是否已经搬到了堆上
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    pub fn push(&mut self, value: T) {
+        // 1. 内联放得下：直接写进 buf[len]
+        if let Storage::Inline { buf, len } = &mut self.storage {
+            if *len < N {
+                buf[*len].write(value);
+                *len += 1;
+                return;
+            }
+            // 2. 放不下：搬到堆上（容量翻倍，和 Vector 的扩容一致）
+            self.spill((N * 2).max(1));
+        }
+        // 3. 已经在堆上：交给 Vector
+        if let Storage::Heap(vector) = &mut self.storage {
+            vector.push(value);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.storage {
+            Storage::Inline { buf, len } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                // buf[len] 已经不在 [0, len) 里了，读出来之后不会再被 drop
+                Some(unsafe { buf[*len].assume_init_read() })
+            }
+            Storage::Heap(vector) => vector.pop(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    // 与 Vector::truncate 相同：先改长度再 drop，元素 Drop panic 时最多泄漏
+    pub fn truncate(&mut self, new_len: usize) {
+        match &mut self.storage {
+            Storage::Inline { buf, len } => {
+                if new_len >= *len {
+                    return;
+                }
+                let old_len = *len;
+                *len = new_len;
+                unsafe {
+                    let tail = std::ptr::slice_from_raw_parts_mut(buf.as_mut_ptr().add(new_len) as *mut T, old_len - new_len);
+                    std::ptr::drop_in_place(tail);
+                }
+            }
+            Storage::Heap(vector) => vector.truncate(new_len),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        match &self.storage {
+            // buf[..len] 都已初始化
+            Storage::Inline { buf, len } => unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const T, *len) },
+            Storage::Heap(vector) => vector.as_slice(),
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match &mut self.storage {
+            Storage::Inline { buf, len } => unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut T, *len) },
+            Storage::Heap(vector) => vector.as_mut_slice(),
+        }
+    }
+
+    // 已经在堆上且元素不超过 N 个时搬回内联存储，否则把堆上的容量缩到刚好
+    pub fn shrink_to_fit(&mut self) {
+        let Storage::Heap(vector) = &mut self.storage else {
+            return;
+        };
+        if vector.len() > N {
+            vector.shrink_to_fit();
+            return;
+        }
+        let mut buf = [const { MaybeUninit::uninit() }; N];
+        let len = vector.len();
+        unsafe {
+            // 按位搬回来，Vector 只剩一个空缓冲区，随后被释放
+            std::ptr::copy_nonoverlapping(vector.as_ptr(), buf.as_mut_ptr() as *mut T, len);
+            vector.size = 0;
+        }
+        self.storage = Storage::Inline { buf, len };
+    }
+
+    // 把内联的元素按位搬进容量为 capacity 的 Vector
+    fn spill(&mut self, capacity: usize) {
+        let Storage::Inline { buf, len } = &mut self.storage else {
+            return;
+        };
+        let mut vector = Vector::with_capacity(capacity.max(*len));
+        unsafe {
+            std::ptr::copy_nonoverlapping(buf.as_ptr() as *const T, vector.as_mut_ptr(), *len);
+            vector.size = *len;
+        }
+        // 元素的所有权已经交给 vector：先把 len 清零，旧的 Inline 被覆盖时不会再 drop 它们
+        *len = 0;
+        self.storage = Storage::Heap(vector);
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVector<T, N> {
+    fn drop(&mut self) {
+        // 内联的元素要自己 drop；Heap 的 Vector 会在之后被自动 drop
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for SmallVector<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> std::ops::Deref for SmallVector<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> std::ops::DerefMut for SmallVector<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, I: std::slice::SliceIndex<[T]>, const N: usize> std::ops::Index<I> for SmallVector<T, N> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T, I: std::slice::SliceIndex<[T]>, const N: usize> std::ops::IndexMut<I> for SmallVector<T, N> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVector<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> std::fmt::Debug for SmallVector<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<SmallVector<U, M>> for SmallVector<T, N> {
+    fn eq(&self, other: &SmallVector<U, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallVector<T, N> {}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for SmallVector<T, N> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for SmallVector<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVector<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // 提前知道放不下时一次搬到堆上，避免先填满内联再 spill
+        let additional = iter.size_hint().0;
+        if let Storage::Heap(vector) = &mut self.storage {
+            vector.reserve(additional);
+        } else if self.len().saturating_add(additional) > N {
+            self.spill(self.len().saturating_add(additional));
+        }
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVector<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut small = SmallVector::new();
+        small.extend(iter);
+        small
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVector<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVector<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SmallVector;
+    use crate::test_util::{counters, DropCounter};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_inline_until_full_then_spill() {
+        let mut v: SmallVector<usize, 4> = SmallVector::new();
+        assert_eq!(v.capacity(), 4);
+        for i in 0..4 {
+            v.push(i);
+        }
+        assert!(!v.spilled());
+        v.push(4);
+        assert!(v.spilled());
+        assert_eq!(v.capacity(), 8);
+        assert_eq!(v, [0, 1, 2, 3, 4]);
+
+        assert_eq!(v.get(4), Some(&4));
+        assert_eq!(v.get(5), None);
+        v[0] = 10;
+        assert_eq!(&v[..2], &[10, 1]);
+        *v.get_mut(1).unwrap() = 11;
+        assert_eq!(v.iter().sum::<usize>(), 10 + 11 + 2 + 3 + 4);
+    }
+
+    #[test]
+    fn test_pop_inline_and_spilled() {
+        let mut v: SmallVector<String, 2> = SmallVector::new();
+        assert_eq!(v.pop(), None);
+        v.push("a".to_string());
+        v.push("b".to_string());
+        assert_eq!(v.pop().as_deref(), Some("b"));
+        v.push("c".to_string());
+        v.push("d".to_string());
+        assert!(v.spilled());
+        assert_eq!(v.pop().as_deref(), Some("d"));
+        assert_eq!(v.pop().as_deref(), Some("c"));
+        // 搬到堆上之后就留在堆上
+        assert!(v.spilled());
+        v.shrink_to_fit();
+        assert!(!v.spilled());
+        assert_eq!(v, ["a"]);
+    }
+
+    #[test]
+    fn test_each_element_dropped_exactly_once() {
+        let drops = Rc::new(Cell::new(0));
+        // 内联
+        drop(counters::<SmallVector<DropCounter, 8>>(5, &drops));
+        assert_eq!(drops.get(), 5);
+
+        // spill 时按位搬运，不能 drop
+        drops.set(0);
+        let mut v: SmallVector<DropCounter, 4> = counters(4, &drops);
+        v.push(DropCounter { id: 4, drops: Rc::clone(&drops) });
+        assert_eq!(drops.get(), 0);
+        v.truncate(2);
+        assert_eq!(drops.get(), 3);
+        let popped = v.pop().unwrap();
+        assert_eq!(popped.id, 1);
+        drop(popped);
+        assert_eq!(drops.get(), 4);
+        v.shrink_to_fit();
+        assert_eq!(drops.get(), 4);
+        assert_eq!(v[0].id, 0);
+        drop(v);
+        assert_eq!(drops.get(), 5);
+
+        // 内联 truncate / clear
+        drops.set(0);
+        let mut v: SmallVector<DropCounter, 8> = counters(6, &drops);
+        v.truncate(4);
+        assert_eq!(drops.get(), 2);
+        v.clear();
+        assert_eq!(drops.get(), 6);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_with_capacity_extend_and_clone() {
+        assert!(!SmallVector::<u8, 16>::with_capacity(16).spilled());
+        assert!(SmallVector::<u8, 16>::with_capacity(17).spilled());
+
+        // 一次 extend 超过 N：直接搬到堆上，不会先填满内联
+        let mut v: SmallVector<u32, 4> = SmallVector::new();
+        v.extend(0..10);
+        assert!(v.spilled());
+        assert_eq!(v.capacity(), 10);
+
+        let cloned = v.clone();
+        assert_eq!(cloned, v);
+        let inline: SmallVector<u32, 16> = v.iter().copied().collect();
+        assert!(!inline.spilled());
+        assert_eq!(inline, cloned);
+        assert_eq!(format!("{:?}", SmallVector::<u8, 2>::from_iter([1, 2])), "[1, 2]");
+    }
+
+    #[test]
+    fn test_zero_inline_capacity_and_zst() {
+        let mut v: SmallVector<u64, 0> = SmallVector::new();
+        assert_eq!(v.capacity(), 0);
+        v.push(7);
+        assert!(v.spilled());
+        assert_eq!(v, [7]);
+
+        let mut units: SmallVector<(), 2> = SmallVector::new();
+        for _ in 0..100 {
+            units.push(());
+        }
+        assert_eq!(units.len(), 100);
+        assert_eq!(units.pop(), Some(()));
+    }
+
+    #[test]
+    fn test_inline_storage_lives_in_the_struct() {
+        let v: SmallVector<u32, 8> = (0..8).collect();
+        let start = &v as *const _ as usize;
+        let end = start + std::mem::size_of_val(&v);
+        let ptr = v.as_ptr() as usize;
+        assert!(start <= ptr && ptr < end);
+    }
+}
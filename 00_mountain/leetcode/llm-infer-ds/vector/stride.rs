@@ -0,0 +1,308 @@
+// ==============================================================================
+// 步长迭代器 - stride() 的零拷贝版本
+// ==============================================================================
+//
+// 【为什么需要】
+//   - stride() 把元素 clone 进一个新的 Vec：要求 T: Clone，每次调用都分配一次
+//   - 参数不合法时直接 panic，调用方没有机会处理
+//   - 这里返回借用原数据的迭代器，参数不合法时返回 StrideError
+//
+// 【语义】
+//   stride_iter(start, step):       从 start 出发，每次走 step（可以为负），走出 [0, len) 为止
+//   stride_iter_range(range, step): 只在 range 内走；正步长从 range.start 出发，
+//                                   负步长从 range.end - 1 出发（类似 Python 的 xs[a:b][::step]）
+//   元素个数在创建时就算好：ExactSizeIterator，也可以从后往前走（DoubleEndedIterator）
+//
+// 【stride_iter_mut 为什么是安全的】
+//   step != 0，所以每个下标最多出现一次，同时交出去的 &mut T 不会指向同一个元素
+// ==============================================================================
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{RawAlloc, Vector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrideError {
+    ZeroStep,                                                  // 步长为 0，永远走不出去
+    StartOutOfBounds { start: usize, len: usize },             // 起点不在 [0, len) 内
+    RangeOutOfBounds { start: usize, end: usize, len: usize }, // range 超出 [0, len] 或 start > end
+}
+
+impl fmt::Display for StrideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrideError::ZeroStep => write!(f, "stride can not be zero!"),
+            StrideError::StartOutOfBounds { start, len } => {
+                write!(f, "start index {} out of bounds (len {})!", start, len)
+            }
+            StrideError::RangeOutOfBounds { start, end, len } => {
+                write!(f, "range {}..{} out of bounds (len {})!", start, end, len)
+            }
+        }
+    }
+}
+
+impl Error for StrideError {}
+
+// 两种迭代器共用的游标：第 k 个元素的下标 = first + k * step
+#[derive(Debug, Clone)]
+struct Cursor {
+    first: usize,
+    step: isize,
+    front: usize,     // 已经从前面取走了几个
+    remaining: usize, // 还剩几个
+}
+
+impl Cursor {
+    // 从 first 出发，走出 [low, high) 之前一共能取到几个元素（first 必须在区间内）
+    fn new(first: usize, step: isize, low: usize, high: usize) -> Self {
+        let stride = step.unsigned_abs();
+        let span = if step > 0 { high - 1 - first } else { first - low };
+        Cursor { first, step, front: 0, remaining: span / stride + 1 }
+    }
+
+    fn empty(step: isize) -> Self {
+        Cursor { first: 0, step, front: 0, remaining: 0 }
+    }
+
+    fn index_of(&self, k: usize) -> usize {
+        // k < 元素个数，算出来的下标一定在 [0, len) 内，不会溢出
+        (self.first as isize + k as isize * self.step) as usize
+    }
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let index = self.index_of(self.front);
+        self.front += 1;
+        self.remaining -= 1;
+        Some(index)
+    }
+
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.index_of(self.front + self.remaining))
+    }
+}
+
+impl<T, A: RawAlloc> Vector<T, A> {
+    fn stride_cursor(&self, start: usize, step: isize) -> Result<Cursor, StrideError> {
+        if start >= self.size {
+            return Err(StrideError::StartOutOfBounds { start, len: self.size });
+        }
+        if step == 0 {
+            return Err(StrideError::ZeroStep);
+        }
+        Ok(Cursor::new(start, step, 0, self.size))
+    }
+
+    fn stride_range_cursor<R>(&self, range: R, step: isize) -> Result<Cursor, StrideError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.saturating_add(1),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.size,
+        };
+        if start > end || end > self.size {
+            return Err(StrideError::RangeOutOfBounds { start, end, len: self.size });
+        }
+        if step == 0 {
+            return Err(StrideError::ZeroStep);
+        }
+        if start == end {
+            return Ok(Cursor::empty(step));
+        }
+        let first = if step > 0 { start } else { end - 1 };
+        Ok(Cursor::new(first, step, start, end))
+    }
+
+    // 任务12b：零拷贝的步长访问，返回 &T
+    pub fn stride_iter(&self, start: usize, step: isize) -> Result<StrideIter<'_, T>, StrideError> {
+        let cursor = self.stride_cursor(start, step)?;
+        Ok(StrideIter { slice: self.as_slice(), cursor })
+    }
+
+    // 可变版本：可以原地修改每隔 step 个的元素
+    pub fn stride_iter_mut(&mut self, start: usize, step: isize) -> Result<StrideIterMut<'_, T>, StrideError> {
+        let cursor = self.stride_cursor(start, step)?;
+        Ok(StrideIterMut { data: self.data, cursor, marker: PhantomData })
+    }
+
+    // 只在 range 内按 step 走
+    pub fn stride_iter_range<R>(&self, range: R, step: isize) -> Result<StrideIter<'_, T>, StrideError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let cursor = self.stride_range_cursor(range, step)?;
+        Ok(StrideIter { slice: self.as_slice(), cursor })
+    }
+
+    pub fn stride_iter_range_mut<R>(&mut self, range: R, step: isize) -> Result<StrideIterMut<'_, T>, StrideError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let cursor = self.stride_range_cursor(range, step)?;
+        Ok(StrideIterMut { data: self.data, cursor, marker: PhantomData })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StrideIter<'a, T> {
+    slice: &'a [T],
+    cursor: Cursor,
+}
+
+impl<'a, T> Iterator for StrideIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.cursor.next().map(|i| &self.slice[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.remaining, Some(self.cursor.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for StrideIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back().map(|i| &self.slice[i])
+    }
+}
+
+impl<T> ExactSizeIterator for StrideIter<'_, T> {}
+
+// 裸指针 + PhantomData：借用检查器不允许从 &mut [T] 里反复切出生命周期为 'a 的 &mut T
+pub struct StrideIterMut<'a, T> {
+    data: *mut T,
+    cursor: Cursor,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for StrideIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        // 下标互不相同且都在 [0, len) 内，见文件头
+        self.cursor.next().map(|i| unsafe { &mut *self.data.add(i) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cursor.remaining, Some(self.cursor.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for StrideIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back().map(|i| unsafe { &mut *self.data.add(i) })
+    }
+}
+
+impl<T> ExactSizeIterator for StrideIterMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use super::StrideError;
+    use crate::Vector;
+
+    fn numbers(n: usize) -> Vector<usize> {
+        (0..n).collect()
+    }
+
+    #[test]
+    fn test_positive_and_negative_steps() {
+        let v = numbers(10);
+        let forward: Vec<usize> = v.stride_iter(1, 3).unwrap().copied().collect();
+        assert_eq!(forward, vec![1, 4, 7]);
+        let backward: Vec<usize> = v.stride_iter(9, -4).unwrap().copied().collect();
+        assert_eq!(backward, vec![9, 5, 1]);
+        let all: Vec<usize> = v.stride_iter(0, 1).unwrap().copied().collect();
+        assert_eq!(all, (0..10).collect::<Vec<_>>());
+        // 步长比长度还大：只有起点
+        assert_eq!(v.stride_iter(3, 100).unwrap().count(), 1);
+        assert_eq!(v.stride_iter(3, isize::MIN).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_exact_size_and_double_ended() {
+        let v = numbers(10);
+        let mut iter = v.stride_iter(0, 2).unwrap();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&8));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.rev().copied().collect::<Vec<_>>(), vec![6, 4, 2]);
+    }
+
+    #[test]
+    fn test_range_bounded() {
+        let v = numbers(20);
+        let evens: Vec<usize> = v.stride_iter_range(10..20, 2).unwrap().copied().collect();
+        assert_eq!(evens, vec![10, 12, 14, 16, 18]);
+        let reversed: Vec<usize> = v.stride_iter_range(..=5, -2).unwrap().copied().collect();
+        assert_eq!(reversed, vec![5, 3, 1]);
+        assert_eq!(v.stride_iter_range(4..4, 1).unwrap().len(), 0);
+        assert_eq!(v.stride_iter_range(.., 7).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_errors_instead_of_panics() {
+        let v = numbers(4);
+        assert_eq!(v.stride_iter(4, 1).unwrap_err(), StrideError::StartOutOfBounds { start: 4, len: 4 });
+        assert_eq!(v.stride_iter(0, 0).unwrap_err(), StrideError::ZeroStep);
+        assert_eq!(
+            v.stride_iter_range(2..5, 1).unwrap_err(),
+            StrideError::RangeOutOfBounds { start: 2, end: 5, len: 4 }
+        );
+        assert!(v.stride_iter_range((Bound::Included(3), Bound::Excluded(2)), 1).is_err());
+        assert!(v.stride_iter_range(.., 0).is_err());
+
+        let empty: Vector<usize> = Vector::with_capacity(0);
+        assert!(empty.stride_iter(0, 1).is_err());
+        assert_eq!(empty.stride_iter_range(.., -1).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_mutable_iteration_without_clone() {
+        // String 不是 Copy，也不需要 Clone
+        let mut v: Vector<String> = (0..6).map(|i| i.to_string()).collect();
+        for s in v.stride_iter_mut(5, -2).unwrap() {
+            s.push('!');
+        }
+        assert_eq!(v, ["0", "1!", "2", "3!", "4", "5!"]);
+
+        for s in v.stride_iter_range_mut(0..4, 3).unwrap().rev() {
+            s.insert(0, '#');
+        }
+        assert_eq!(v, ["#0", "1!", "2", "#3!", "4", "5!"]);
+    }
+
+    #[test]
+    fn test_stride_wraps_stride_iter() {
+        let v = numbers(8);
+        assert_eq!(v.stride(7, -2), vec![7, 5, 3, 1]);
+        assert_eq!(v.stride(0, 3), v.stride_iter(0, 3).unwrap().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "stride can not be zero!")]
+    fn test_stride_still_panics_on_zero() {
+        numbers(3).stride(0, 0);
+    }
+}
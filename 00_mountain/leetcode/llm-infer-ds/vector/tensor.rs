@@ -0,0 +1,626 @@
+// ==============================================================================
+// Tensor - shape / strides / offset 描述的 N 维视图
+// ==============================================================================
+//
+// 【对应引擎模块】
+//   - KV Cache: [layers, heads, tokens, head_dim] 形状的大张量
+//   - 注意力里的 transpose(q, k) / 按 head 切片 / 按 token 截取前缀
+//
+// 【结构】
+//   storage: Arc<Vector<T>>   底层一维存储，多个视图可以共享
+//   shape:   每一维的大小
+//   strides: 每一维下标 +1 时，在 storage 里跨过多少个元素
+//   offset:  第一个元素在 storage 里的位置
+//   元素 [i0, i1, ..., in] 的位置 = offset + Σ ik * strides[k]
+//
+// 【学习重点】
+//   1. 零拷贝视图：transpose / permute / slice / narrow 只改 shape / strides / offset，
+//      共享同一个 storage（Arc 克隆一次引用计数）
+//   2. 连续性：strides 等于按 shape 算出的行主序 strides 时才是连续的，
+//      只有连续的张量才能 view 成新形状；reshape 在不连续时先 contiguous() 拷贝一份
+//   3. 写时复制：修改元素时如果 storage 被别的视图共享，先拷贝一份（Arc::make_mut），
+//      别的视图看到的数据不会被改掉
+//
+// 【用法】
+//   let kv = Tensor::zeros(&[layers, heads, tokens, head_dim]);
+//   let layer0_head3 = kv.select(0, 0)?.select(0, 3)?;     // [tokens, head_dim]
+//   let prefix = layer0_head3.narrow(0, 0, 16)?;            // 前 16 个 token
+//   let k_t = prefix.transpose(0, 1)?;                      // [head_dim, 16]，不连续
+// ==============================================================================
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use crate::Vector;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TensorError {
+    // 元素个数与形状不符（构造 / view / reshape）
+    ShapeMismatch { numel: usize, shape: Vec<usize> },
+    // 维度下标超出 ndim
+    DimOutOfRange { dim: usize, ndim: usize },
+    // permute 的参数不是 0..ndim 的一个排列
+    InvalidPermutation(Vec<usize>),
+    // slice / narrow / select 的范围超出该维大小
+    RangeOutOfBounds { dim: usize, start: usize, end: usize, size: usize },
+    // view 要求连续的布局，不连续时先 contiguous() 或改用 reshape
+    NotContiguous,
+}
+
+impl fmt::Display for TensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TensorError::ShapeMismatch { numel, shape } => {
+                write!(f, "shape {:?} is invalid for {} elements", shape, numel)
+            }
+            TensorError::DimOutOfRange { dim, ndim } => {
+                write!(f, "dimension {} out of range for a {}-d tensor", dim, ndim)
+            }
+            TensorError::InvalidPermutation(dims) => write!(f, "{:?} is not a permutation of the dimensions", dims),
+            TensorError::RangeOutOfBounds { dim, start, end, size } => {
+                write!(f, "range {}..{} out of bounds for dimension {} of size {}", start, end, dim, size)
+            }
+            TensorError::NotContiguous => write!(f, "tensor is not contiguous"),
+        }
+    }
+}
+
+impl Error for TensorError {}
+
+pub struct Tensor<T> {
+    storage: Arc<Vector<T>>, // 一维存储，视图之间共享
+    shape: Vec<usize>,       // 每一维的大小
+    strides: Vec<usize>,     // 每一维的步长（以元素为单位）
+    offset: usize,           // 第一个元素在 storage 中的位置
+}
+
+// 克隆只复制元数据、共享 storage，所以不要求 T: Clone（derive 会加上这个约束）
+impl<T> Clone for Tensor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: Arc::clone(&self.storage),
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            offset: self.offset,
+        }
+    }
+}
+
+// 行主序（最后一维最密）的 strides：[a, b, c] -> [b * c, c, 1]
+pub fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for dim in (0..shape.len().saturating_sub(1)).rev() {
+        strides[dim] = strides[dim + 1] * shape[dim + 1];
+    }
+    strides
+}
+
+// 形状对应的元素个数，乘法溢出返回 None
+// 大小为 0 的维度按 1 参与检查：[0, n, m] 虽然没有元素，contiguous_strides 仍要算 n * m
+fn checked_numel(shape: &[usize]) -> Option<usize> {
+    let span = shape.iter().try_fold(1usize, |acc, &size| acc.checked_mul(size.max(1)))?;
+    Some(if shape.contains(&0) { 0 } else { span })
+}
+
+impl<T> Tensor<T> {
+    // 接管一个 Vector，按行主序解释成 shape 形状
+    pub fn from_vector(data: Vector<T>, shape: &[usize]) -> Result<Self, TensorError> {
+        if checked_numel(shape) != Some(data.len()) {
+            return Err(TensorError::ShapeMismatch { numel: data.len(), shape: shape.to_vec() });
+        }
+        Ok(Self {
+            storage: Arc::new(data),
+            shape: shape.to_vec(),
+            strides: contiguous_strides(shape),
+            offset: 0,
+        })
+    }
+
+    // 元素个数溢出 usize 时 panic（与 Vec::with_capacity 的 capacity overflow 相同）
+    pub fn full(shape: &[usize], value: T) -> Self
+    where
+        T: Clone,
+    {
+        let Some(numel) = checked_numel(shape) else {
+            panic!("shape {:?} overflows usize", shape);
+        };
+        let data: Vector<T> = std::iter::repeat_n(value, numel).collect();
+        Self::from_vector(data, shape).expect("numel matches shape")
+    }
+
+    pub fn zeros(shape: &[usize]) -> Self
+    where
+        T: Clone + Default,
+    {
+        Self::full(shape, T::default())
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    // 构造和 view 时已经检查过溢出，之后的视图只会缩小或重排维度
+    pub fn numel(&self) -> usize {
+        checked_numel(&self.shape).expect("shape checked at construction")
+    }
+
+    // 底层一维存储（包括不属于这个视图的元素）
+    pub fn storage(&self) -> &Vector<T> {
+        &self.storage
+    }
+
+    // 两个张量是否共享同一块存储：用来确认某个操作没有拷贝
+    pub fn shares_storage(&self, other: &Tensor<T>) -> bool {
+        Arc::ptr_eq(&self.storage, &other.storage)
+    }
+
+    // 是否是行主序连续布局：大小为 1 的维度步长无所谓，跳过
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for dim in (0..self.ndim()).rev() {
+            if self.shape[dim] == 1 {
+                continue;
+            }
+            if self.strides[dim] != expected {
+                return false;
+            }
+            expected *= self.shape[dim];
+        }
+        true
+    }
+
+    // ==========================================
+    // 下标访问
+    // ==========================================
+
+    // 多维下标 -> storage 中的位置，维数不对或越界返回 None
+    fn position(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.ndim() {
+            return None;
+        }
+        let mut pos = self.offset;
+        for ((&i, &size), &stride) in index.iter().zip(&self.shape).zip(&self.strides) {
+            if i >= size {
+                return None;
+            }
+            pos += i * stride;
+        }
+        Some(pos)
+    }
+
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        let pos = self.position(index)?;
+        Some(&self.storage[pos])
+    }
+
+    // 写时复制：storage 被其他视图共享时，先把整个 storage 拷贝一份
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T>
+    where
+        T: Clone,
+    {
+        let pos = self.position(index)?;
+        Some(&mut Arc::make_mut(&mut self.storage)[pos])
+    }
+
+    // 按逻辑顺序（行主序）遍历元素，不要求连续
+    pub fn iter(&self) -> TensorIter<'_, T> {
+        TensorIter { tensor: self, index: vec![0; self.ndim()], remaining: self.numel() }
+    }
+
+    // ==========================================
+    // 零拷贝视图：只改 shape / strides / offset
+    // ==========================================
+
+    fn check_dim(&self, dim: usize) -> Result<(), TensorError> {
+        if dim >= self.ndim() {
+            return Err(TensorError::DimOutOfRange { dim, ndim: self.ndim() });
+        }
+        Ok(())
+    }
+
+    // 连续时换一个形状，共享 storage；不连续返回 NotContiguous
+    pub fn view(&self, shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+        if checked_numel(shape) != Some(self.numel()) {
+            return Err(TensorError::ShapeMismatch { numel: self.numel(), shape: shape.to_vec() });
+        }
+        if !self.is_contiguous() {
+            return Err(TensorError::NotContiguous);
+        }
+        Ok(Tensor {
+            storage: Arc::clone(&self.storage),
+            shape: shape.to_vec(),
+            strides: contiguous_strides(shape),
+            offset: self.offset,
+        })
+    }
+
+    // 交换两个维度
+    pub fn transpose(&self, dim0: usize, dim1: usize) -> Result<Tensor<T>, TensorError> {
+        self.check_dim(dim0)?;
+        self.check_dim(dim1)?;
+        let mut t = self.clone();
+        t.shape.swap(dim0, dim1);
+        t.strides.swap(dim0, dim1);
+        Ok(t)
+    }
+
+    // 按 dims 重新排列维度：新的第 k 维是原来的第 dims[k] 维
+    pub fn permute(&self, dims: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let mut seen = vec![false; self.ndim()];
+        if dims.len() != self.ndim() {
+            return Err(TensorError::InvalidPermutation(dims.to_vec()));
+        }
+        for &dim in dims {
+            if dim >= self.ndim() || seen[dim] {
+                return Err(TensorError::InvalidPermutation(dims.to_vec()));
+            }
+            seen[dim] = true;
+        }
+
+        let mut t = self.clone();
+        t.shape = dims.iter().map(|&d| self.shape[d]).collect();
+        t.strides = dims.iter().map(|&d| self.strides[d]).collect();
+        Ok(t)
+    }
+
+    // 第 dim 维只保留 [start, start + len)，维数不变
+    pub fn narrow(&self, dim: usize, start: usize, len: usize) -> Result<Tensor<T>, TensorError> {
+        self.check_dim(dim)?;
+        let size = self.shape[dim];
+        let end = start.checked_add(len).filter(|&end| end <= size);
+        let Some(end) = end else {
+            return Err(TensorError::RangeOutOfBounds { dim, start, end: start.saturating_add(len), size });
+        };
+
+        let mut t = self.clone();
+        t.shape[dim] = end - start;
+        // 长度为 0 时不移动 offset，避免指向 storage 之外
+        if end > start {
+            t.offset += start * self.strides[dim];
+        }
+        Ok(t)
+    }
+
+    // narrow 的 range 写法：t.slice(2, ..16)、t.slice(0, 3..5)
+    pub fn slice<R>(&self, dim: usize, range: R) -> Result<Tensor<T>, TensorError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.check_dim(dim)?;
+        let size = self.shape[dim];
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.saturating_add(1),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => size,
+        };
+        if start > end || end > size {
+            return Err(TensorError::RangeOutOfBounds { dim, start, end, size });
+        }
+        self.narrow(dim, start, end - start)
+    }
+
+    // 取第 dim 维的第 index 个，结果少一维
+    pub fn select(&self, dim: usize, index: usize) -> Result<Tensor<T>, TensorError> {
+        let mut t = self.narrow(dim, index, 1)?;
+        t.shape.remove(dim);
+        t.strides.remove(dim);
+        Ok(t)
+    }
+}
+
+impl<T: Clone> Tensor<T> {
+    // 连续的张量直接共享 storage；否则按逻辑顺序拷贝到新的 Vector
+    pub fn contiguous(&self) -> Tensor<T> {
+        if self.is_contiguous() {
+            return self.clone();
+        }
+        let data: Vector<T> = self.iter().cloned().collect();
+        Tensor::from_vector(data, &self.shape).expect("numel matches shape")
+    }
+
+    // 能 view 就 view，不连续时先拷贝成连续的再 view
+    pub fn reshape(&self, shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+        match self.view(shape) {
+            Err(TensorError::NotContiguous) => self.contiguous().view(shape),
+            result => result,
+        }
+    }
+
+    // 按逻辑顺序拷贝出所有元素
+    pub fn to_vector(&self) -> Vector<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> std::ops::Index<&[usize]> for Tensor<T> {
+    type Output = T;
+
+    fn index(&self, index: &[usize]) -> &T {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!("index {:?} out of bounds for shape {:?}", index, self.shape),
+        }
+    }
+}
+
+impl<T: Clone> std::ops::IndexMut<&[usize]> for Tensor<T> {
+    fn index_mut(&mut self, index: &[usize]) -> &mut T {
+        let shape = self.shape.clone();
+        match self.get_mut(index) {
+            Some(value) => value,
+            None => panic!("index {:?} out of bounds for shape {:?}", index, shape),
+        }
+    }
+}
+
+// t[[i, j, k]]：下标个数在编译期已知时的写法
+impl<T, const N: usize> std::ops::Index<[usize; N]> for Tensor<T> {
+    type Output = T;
+
+    fn index(&self, index: [usize; N]) -> &T {
+        &self[&index[..]]
+    }
+}
+
+impl<T: Clone, const N: usize> std::ops::IndexMut<[usize; N]> for Tensor<T> {
+    fn index_mut(&mut self, index: [usize; N]) -> &mut T {
+        &mut self[&index[..]]
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tensor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tensor")
+            .field("shape", &self.shape)
+            .field("strides", &self.strides)
+            .field("offset", &self.offset)
+            .field("data", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// 逻辑顺序遍历：像里程表一样从最后一维开始进位
+pub struct TensorIter<'a, T> {
+    tensor: &'a Tensor<T>,
+    index: Vec<usize>, // 下一个要返回的元素的多维下标
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for TensorIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let tensor = self.tensor;
+        let pos = tensor.offset + self.index.iter().zip(&tensor.strides).map(|(i, s)| i * s).sum::<usize>();
+        self.remaining -= 1;
+
+        // 进位：最后一维 +1，满了归零并向前一维进位
+        for dim in (0..self.index.len()).rev() {
+            self.index[dim] += 1;
+            if self.index[dim] < tensor.shape[dim] {
+                break;
+            }
+            self.index[dim] = 0;
+        }
+
+        Some(&tensor.storage[pos])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for TensorIter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{contiguous_strides, Tensor, TensorError};
+    use crate::Vector;
+
+    fn arange(shape: &[usize]) -> Tensor<usize> {
+        let numel = shape.iter().product();
+        Tensor::from_vector((0..numel).collect(), shape).unwrap()
+    }
+
+    fn values(t: &Tensor<usize>) -> Vec<usize> {
+        t.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_construction_and_indexing() {
+        assert_eq!(contiguous_strides(&[2, 3, 4]), vec![12, 4, 1]);
+        assert!(contiguous_strides(&[]).is_empty());
+
+        let t = arange(&[2, 3, 4]);
+        assert_eq!(t.ndim(), 3);
+        assert_eq!(t.numel(), 24);
+        assert_eq!(t.strides(), &[12, 4, 1]);
+        assert_eq!(t[[1, 2, 3]], 23);
+        assert_eq!(t.get(&[0, 1, 2]), Some(&6));
+        assert_eq!(t.get(&[2, 0, 0]), None);
+        assert_eq!(t.get(&[0, 0]), None);
+
+        let err = Tensor::from_vector(Vector::from(vec![1, 2, 3]), &[2, 2]).unwrap_err();
+        assert_eq!(err, TensorError::ShapeMismatch { numel: 3, shape: vec![2, 2] });
+    }
+
+    #[test]
+    fn test_view_shares_storage() {
+        let t = arange(&[2, 6]);
+        let v = t.view(&[3, 4]).unwrap();
+        assert!(v.shares_storage(&t));
+        assert_eq!(v[[2, 1]], 9);
+        assert_eq!(values(&v), values(&t));
+        assert!(matches!(t.view(&[5]), Err(TensorError::ShapeMismatch { .. })));
+    }
+
+    #[test]
+    fn test_overflowing_shape_is_rejected() {
+        // usize::MAX * 2 溢出：不能回绕成一个很小的元素个数
+        let huge = [usize::MAX, 2];
+        let err = Tensor::from_vector(Vector::from(vec![1, 2]), &huge).unwrap_err();
+        assert_eq!(err, TensorError::ShapeMismatch { numel: 2, shape: huge.to_vec() });
+
+        // 有 0 维时没有元素，但 strides 仍然会溢出
+        assert!(Tensor::<usize>::from_vector(Vector::new(), &[0, usize::MAX, 2]).is_err());
+        assert_eq!(Tensor::<usize>::from_vector(Vector::new(), &[0, 3]).unwrap().numel(), 0);
+
+        let t = arange(&[2, 3]);
+        let err = t.view(&[usize::MAX, 2, 3]).unwrap_err();
+        assert_eq!(err, TensorError::ShapeMismatch { numel: 6, shape: vec![usize::MAX, 2, 3] });
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn test_full_panics_on_overflowing_shape() {
+        let _ = Tensor::full(&[usize::MAX, 2], 0u8);
+    }
+
+    #[test]
+    fn test_transpose_and_permute() {
+        let t = arange(&[2, 3]);
+        let tt = t.transpose(0, 1).unwrap();
+        assert!(tt.shares_storage(&t));
+        assert_eq!(tt.shape(), &[3, 2]);
+        assert!(!tt.is_contiguous());
+        assert_eq!(values(&tt), vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(tt.view(&[6]).unwrap_err(), TensorError::NotContiguous);
+
+        // [layers, heads, tokens, head_dim] -> [layers, tokens, heads, head_dim]
+        let kv = arange(&[2, 3, 4, 5]);
+        let p = kv.permute(&[0, 2, 1, 3]).unwrap();
+        assert_eq!(p.shape(), &[2, 4, 3, 5]);
+        assert_eq!(p[[1, 3, 2, 4]], kv[[1, 2, 3, 4]]);
+
+        assert_eq!(kv.permute(&[0, 1, 1, 3]).unwrap_err(), TensorError::InvalidPermutation(vec![0, 1, 1, 3]));
+        assert_eq!(kv.permute(&[0, 1]).unwrap_err(), TensorError::InvalidPermutation(vec![0, 1]));
+        assert_eq!(kv.transpose(0, 4).unwrap_err(), TensorError::DimOutOfRange { dim: 4, ndim: 4 });
+    }
+
+    #[test]
+    fn test_slice_narrow_select() {
+        let t = arange(&[4, 5]);
+        let rows = t.slice(0, 1..3).unwrap();
+        assert_eq!(rows.shape(), &[2, 5]);
+        assert_eq!(rows.offset(), 5);
+        assert!(rows.is_contiguous());
+        assert_eq!(values(&rows), (5..15).collect::<Vec<_>>());
+
+        let cols = t.narrow(1, 2, 2).unwrap();
+        assert!(!cols.is_contiguous());
+        assert_eq!(values(&cols), vec![2, 3, 7, 8, 12, 13, 17, 18]);
+
+        let col = t.select(1, 4).unwrap();
+        assert_eq!(col.shape(), &[4]);
+        assert_eq!(values(&col), vec![4, 9, 14, 19]);
+
+        assert_eq!(t.slice(0, 2..2).unwrap().numel(), 0);
+        assert_eq!(t.slice(0, ..=3).unwrap().shape(), &[4, 5]);
+        assert_eq!(
+            t.slice(1, 3..6).unwrap_err(),
+            TensorError::RangeOutOfBounds { dim: 1, start: 3, end: 6, size: 5 }
+        );
+        assert!(t.narrow(0, 3, 2).is_err());
+        assert!(t.select(2, 0).is_err());
+    }
+
+    #[test]
+    fn test_contiguous_and_reshape() {
+        let t = arange(&[2, 3]);
+        // 连续：共享 storage，不拷贝
+        assert!(t.contiguous().shares_storage(&t));
+
+        let tt = t.transpose(0, 1).unwrap();
+        let c = tt.contiguous();
+        assert!(!c.shares_storage(&t));
+        assert!(c.is_contiguous());
+        assert_eq!(c.storage().as_slice(), &[0, 3, 1, 4, 2, 5]);
+
+        // 不连续的 reshape 会先拷贝
+        let r = tt.reshape(&[6]).unwrap();
+        assert_eq!(values(&r), vec![0, 3, 1, 4, 2, 5]);
+        assert!(!r.shares_storage(&t));
+        let r = t.reshape(&[3, 2]).unwrap();
+        assert!(r.shares_storage(&t));
+
+        // 带 offset 的连续切片也能直接 view
+        let rows = arange(&[4, 4]).slice(0, 2..4).unwrap();
+        let flat = rows.view(&[8]).unwrap();
+        assert_eq!(values(&flat), (8..16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_size_one_dims_do_not_break_contiguity() {
+        let t = arange(&[3, 1, 4]);
+        let p = t.permute(&[1, 0, 2]).unwrap();
+        assert!(p.is_contiguous());
+        assert!(p.view(&[12]).is_ok());
+        assert!(t.select(0, 1).unwrap().is_contiguous());
+    }
+
+    #[test]
+    fn test_copy_on_write() {
+        let mut t: Tensor<f32> = Tensor::zeros(&[2, 2]);
+        let view = t.transpose(0, 1).unwrap();
+        t[[0, 1]] = 1.5;
+        // view 还是旧数据，t 已经有了自己的 storage
+        assert!(!t.shares_storage(&view));
+        assert_eq!(view[[1, 0]], 0.0);
+        assert_eq!(t[[0, 1]], 1.5);
+
+        // 不共享时原地修改
+        let before = t.storage().as_ptr();
+        *t.get_mut(&[1, 1]).unwrap() = 2.0;
+        assert_eq!(t.storage().as_ptr(), before);
+        assert_eq!(t.to_vector(), [0.0, 1.5, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_kv_cache_layout() {
+        let (layers, heads, tokens, head_dim) = (2, 4, 8, 16);
+        let kv = arange(&[layers, heads, tokens, head_dim]);
+
+        // 第 1 层第 2 个 head 的前 3 个 token
+        let k = kv.select(0, 1).unwrap().select(0, 2).unwrap().narrow(0, 0, 3).unwrap();
+        assert_eq!(k.shape(), &[3, head_dim]);
+        assert!(k.is_contiguous());
+        assert!(k.shares_storage(&kv));
+        assert_eq!(k[[2, 5]], kv[[1, 2, 2, 5]]);
+
+        // K^T：[head_dim, 3]，与 q [1, head_dim] 相乘前的布局
+        let kt = k.transpose(0, 1).unwrap();
+        assert_eq!(kt.shape(), &[head_dim, 3]);
+        assert_eq!(kt[[5, 2]], k[[2, 5]]);
+        assert_eq!(kt.contiguous().strides(), &[3, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds for shape")]
+    fn test_index_out_of_bounds_panics() {
+        let t = arange(&[2, 2]);
+        let _ = t[[0, 2]];
+    }
+}
@@ -0,0 +1,41 @@
+// ==============================================================================
+// 测试公用的夹具 - 只在 cfg(test) 下编译
+// ==============================================================================
+//
+// 【为什么需要】
+//   Vector / SmallVector 都要检查"每个元素恰好被 drop 一次"（截断、缩容、
+//   溢出到堆、IntoIter 提前丢弃 ...），同一个计数元素写一次，两边共用
+//
+// 【用法】
+//   let drops = Rc::new(Cell::new(0));
+//   let v: Vector<DropCounter> = counters(8, &drops);
+//   drop(v);
+//   assert_eq!(drops.get(), 8);
+// ==============================================================================
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+// drop 时给共享计数器加一，用来检查每个元素恰好被 drop 一次
+#[derive(Debug)]
+pub(crate) struct DropCounter {
+    pub(crate) id: usize,
+    pub(crate) drops: Rc<Cell<usize>>,
+}
+
+impl Clone for DropCounter {
+    fn clone(&self) -> Self {
+        DropCounter { id: self.id, drops: Rc::clone(&self.drops) }
+    }
+}
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+// id 为 0..n 的计数元素，收集成任意容器（Vector / SmallVector<_, N> / Vec）
+pub(crate) fn counters<C: FromIterator<DropCounter>>(n: usize, drops: &Rc<Cell<usize>>) -> C {
+    (0..n).map(|id| DropCounter { id, drops: Rc::clone(drops) }).collect()
+}
@@ -39,17 +39,65 @@
 // ==============================================================================
 
 
+// N 维视图：shape / strides / offset 描述的 Tensor，storage 是共享的 Vector
+pub mod tensor;
+pub use tensor::{Tensor, TensorError, TensorIter};
+
+// 借用原数据的步长迭代器：支持负步长、range 限定，参数错误返回 StrideError
+pub mod stride;
+pub use stride::{StrideError, StrideIter, StrideIterMut};
+
+// 分页 KV Cache：Vector<f32> 当物理块池，每个序列一张块表
+pub mod kv_cache;
+pub use kv_cache::{KvCache, KvCacheConfig, KvCacheError};
+
+// 扩容策略：GrowthPolicy、reserve / shrink_to_fit、TryReserveError
+pub mod growth;
+pub use growth::{GrowthPolicy, TryReserveError};
+
+// 分配器参数：Global（默认）、有上限的 PoolAlloc、arena 式的 BumpAlloc
+pub mod raw_alloc;
+pub use raw_alloc::{AllocError, BumpAlloc, Global, PoolAlloc, RawAlloc};
+
+// 按缓存行 / SIMD 宽度对齐：Vector::with_alignment 和每行补齐到对齐的 AlignedRows
+pub mod aligned;
+pub use aligned::AlignedRows;
+
+// 前 N 个元素内联存放的 SmallVector：短块表不用堆分配
+pub mod small_vector;
+pub use small_vector::SmallVector;
+
+// 可选的内存记账：Tracked 分配器按 kv_cache / activations / weights 统计当前值和峰值
+pub mod memory;
+pub use memory::{Category, CategoryStats, MemorySnapshot, MemoryTracker, Tracked};
+
+// 测试公用的夹具：Vector / SmallVector 的 drop 计数元素
+#[cfg(test)]
+mod test_util;
+
+
 // 任务1：定义 Vector 结构体
 // 语法桥接：
 // - struct Vector<T> 类似 C++ 的 template<typename T> class Vector
 // - *mut T 是"可变裸指针"，类似 C++ 的 T*
 // - usize 是"size type"，类似 C++ 的 size_t
-struct Vector<T> {
+// - A: RawAlloc = Global 类似 C++ 的 template<typename T, typename Alloc = std::allocator<T>>
+//
+// data 永远不是 null：容量为 0 或 T 是零大小类型（ZST）时用按 align 对齐的 dangling 指针，
+// 这样 [0, size) 随时可以安全地转成 &[T]（slice::from_raw_parts 要求指针非空且对齐）
+pub struct Vector<T, A: RawAlloc = Global> {
     data: *mut T,       // 裸指针：直接指向内存地址，不受Rust所有权系统管理
     size: usize,        // 当前有多少元素
-    capacity: usize,    // 总共能放多少元素
+    capacity: usize,    // 总共能放多少元素（ZST 为 usize::MAX）
+    policy: GrowthPolicy, // 扩容 / 缩容策略（见 growth.rs）
+    alloc: A,             // 缓冲区从哪里来（见 raw_alloc.rs），Global 不占空间
+    align: usize,         // 缓冲区的对齐字节数：默认 align_of::<T>()，with_alignment 可以调大（见 aligned.rs）
 }
 
+// 与 Vec<T> 相同：Vector 独占自己的缓冲区，T 和分配器能跨线程，Vector<T, A> 就能跨线程
+unsafe impl<T: Send, A: RawAlloc + Send> Send for Vector<T, A> {}
+unsafe impl<T: Sync, A: RawAlloc + Sync> Sync for Vector<T, A> {}
+
 // ==============================================================================
 // 【思维模型：Rust 的内存管理哲学】
 // ==============================================================================
@@ -61,19 +109,21 @@ struct Vector<T> {
 // - impl<T> Drop for Vector<T> 意思是"为 Vector<T> 实现 Drop trait"
 // - drop() 在对象销毁时自动调用
 // - unsafe 因为我们要手动释放内存
-impl<T> Drop for Vector<T> {
+// - 先 drop 所有元素，再释放缓冲区：只 dealloc 不 drop 的话，
+//   Vector<String> 里每个 String 自己的堆内存都会泄漏
+impl<T, A: RawAlloc> Drop for Vector<T, A> {
     fn drop(&mut self) {
-        // 【你来实现】在这里释放内存
-        // 提示：如果 data 不为空，需要调用 dealloc
-        // unsafe { ... } 包围危险操作
-        // 1. 检查 self.data 是否为空
-        // 2. 如果不为空，创建 layout
-        // 3. 调用 dealloc 释放内存
-
-        if !self.data.is_null(){
+        // 1. drop 前 size 个活着的元素（String 等拥有堆内存的类型在这里释放）
+        // 2. 检查是否真的分配过内存（容量为 0 或 ZST 时 data 只是 dangling 指针）
+        // 3. 如果分配过，创建 layout，调用 dealloc 释放内存
+
+        self.clear();
+
+        if self.owns_allocation() {
             unsafe {
-                let layout = std::alloc::Layout::array::<T>(self.capacity).unwrap();
-                std::alloc::dealloc(self.data as *mut u8, layout);
+                // 分配时这个 layout 已经算过一次，这里不可能失败
+                let layout = Self::layout_for(self.capacity, self.align).expect("layout checked at allocation");
+                self.alloc.deallocate(std::ptr::NonNull::new_unchecked(self.data as *mut u8), layout);
             }
         }
     }
@@ -101,11 +151,10 @@ impl<T> Drop for Vector<T> {
 //    步骤 4: 返回 data[size] 的值
 //
 // 4. resize() 扩容：
-//    步骤 1: 计算新容量 (capacity * 2 或 1)
-//    步骤 2: 分配新内存 (alloc)
-//    步骤 3: 复制旧数据到新内存 (ptr::copy)
-//    步骤 4: 释放旧内存 (dealloc)
-//    步骤 5: 更新指针和容量
+//    步骤 1: 按 GrowthPolicy 计算新容量（默认 capacity * 2 或 1），溢出时返回 TryReserveError
+//    步骤 2: 还没有缓冲区就 alloc，已经有了就 realloc（分配器能原地扩就不用搬数据）
+//    步骤 3: 新容量为 0 时直接 dealloc
+//    步骤 4: 更新指针和容量
 //
 // 5. get() 获取元素：
 //    步骤 1: 检查索引有效性
@@ -129,27 +178,52 @@ impl<T> Vector<T> {
 
     // 任务4：实现带初始容量的构造函数 with_capacity()
     pub fn with_capacity(initial_capacity: usize) -> Self {
+        Self::with_capacity_in(initial_capacity, Global)
+    }
+}
+
+impl<T, A: RawAlloc> Vector<T, A> {
+    // 用指定的分配器（PoolAlloc / BumpAlloc ...）创建，见 raw_alloc.rs
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(4, alloc)
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    #[allow(clippy::redundant_field_names)] // 保留下面 capacity:capacity 的写法，对照注释里的简写语法
+    pub fn with_capacity_in(initial_capacity: usize, alloc: A) -> Self {
         // 【你来实现】带初始容量的构造函数
         // 语法桥接：
         // - let capacity = 确定容量（0则保持0，否则用参数）
-        // - let data = 如果容量>0，分配内存，否则 null_mut()
-        // - unsafe { alloc(layout) } 分配内存
+        // - let data = 如果容量>0，分配内存，否则 dangling 指针
+        // - alloc.allocate(layout) 向分配器要内存（Global 就是 std::alloc::alloc）
         // - Layout::array::<T>(capacity) 创建数组布局
 
+        // ZST 不占内存：永远不分配，容量视为无限
+        if Self::is_zst() {
+            let align = std::mem::align_of::<T>();
+            return Self { data: Self::dangling(align), size: 0, capacity: usize::MAX, policy: GrowthPolicy::default(), alloc, align };
+        }
+
         let capacity = if initial_capacity == 0 { 0 } else { initial_capacity };
+        let align = std::mem::align_of::<T>();
 
         let data = if capacity > 0 {
             // 标准写法: 先构造 Layout，再 unsafe 分配内存，再转换为 T 指针
-            let layout = std::alloc::Layout::array::<T>(capacity).unwrap();
-            unsafe {
-                std::alloc::alloc(layout)
+            // 元素个数 × size_of::<T>() 溢出时与 std::Vec 一样 panic "capacity overflow"
+            let layout = Self::layout_for(capacity, align).unwrap_or_else(|err| growth::handle_reserve_error(err));
+            match alloc.allocate(layout) {
+                Ok(ptr) => ptr.as_ptr() as *mut T,
+                Err(cause) => growth::handle_reserve_error(TryReserveError::AllocError { layout, cause }),
             }
         }else {
-            std::ptr::null_mut()
+            Self::dangling(align)
         };
 
         // 可以不写成 capacity: capacity，只写 capacity 是因为结构体字段和变量同名时的 Rust 简写语法
-        Self { data: data as *mut T, size: 0, capacity:capacity }
+        Self { data, size: 0, capacity:capacity, policy: GrowthPolicy::default(), alloc, align }
 
     }
 
@@ -174,8 +248,9 @@ impl<T> Vector<T> {
         // 步骤 1: if size >= capacity { resize() }
         // 步骤 2: unsafe { ptr::write(data.add(size), value) }
        
+        // 容量为 0 时 capacity * 2 还是 0，所以交给 grow_to：至少扩到 size + 1
         if self.size >= self.capacity {
-            self.resize(self.capacity * 2); // 内部加分号：因为这是side effect操作
+            self.grow_to(self.size.checked_add(1).expect("capacity overflow")); // 内部加分号：因为这是side effect操作
         } // 外部不加分号：又不是let result = if condition {};
 
         unsafe {
@@ -207,8 +282,9 @@ impl<T> Vector<T> {
             std::ptr::read(self.data.add(self.size))
         };
             
-        if self.size > 0 && self.size < self.capacity / 4 {
-           self.resize(self.capacity / 2);
+        // 默认：不到 1/4 时缩到一半；具体规则由 GrowthPolicy 决定
+        if let Some(new_capacity) = self.policy.shrink_target(self.size, self.capacity, std::mem::size_of::<T>()) {
+           self.resize(new_capacity);
         }
 
         Some(value)
@@ -246,151 +322,658 @@ impl<T> Vector<T> {
     // - fn index(&self, index: usize) -> &Self::Output
 }
 
+// Vector::new() 有了公开的无参构造，按惯例同时提供 Default
+impl<T, A: RawAlloc + Default> Default for Vector<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
 // 实现 Index trait 让 Vector 支持 v[0] 语法
-impl<T> std::ops::Index<usize> for Vector<T> {
-    type Output = T;
+// 与 Vec 一样对 I: SliceIndex<[T]> 泛型：只实现 Index<usize> 的话，
+// 编译器不会再通过 Deref 去找切片的 Index<Range>，v[1..3] 就写不了
+impl<T, A: RawAlloc, I: std::slice::SliceIndex<[T]>> std::ops::Index<I> for Vector<T, A> {
+    type Output = I::Output;
 
-    fn index(&self, index: usize) -> &Self::Output {
+    fn index(&self, index: I) -> &Self::Output {
         // 【你来实现】Index trait
-        // 类似 get() 但遇到无效索引时 panic        
-        // assert!(index < size);
-        // unsafe { &*data.add(index) }
-
-        assert!(index < self.size);
-
-        unsafe {
-            &*self.data.add(index)
-        }
+        // 类似 get() 但遇到无效索引时 panic
+        // 越界检查交给切片：v[i] / v[a..b] 越界都会 panic
+        &self.as_slice()[index]
     }
 }
 
 // 实现 IndexMut trait 让 Vector 支持 v[0] = value 语法
-impl<T> std::ops::IndexMut<usize> for Vector<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+impl<T, A: RawAlloc, I: std::slice::SliceIndex<[T]>> std::ops::IndexMut<I> for Vector<T, A> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
         // 【你来实现】IndexMut trait
         // 类似 index() 但返回可变引用 &mut
-        // assert!(index < size);
-        // unsafe { &mut *data.add(index) }
-
-        assert!(index < self.size);
-
-        unsafe {
-            &mut * self.data.add(index)
-        }
+        &mut self.as_mut_slice()[index]
     }
 }
 
-impl<T> Vector<T> {
+impl<T, A: RawAlloc> Vector<T, A> {
     // 任务11：实现 resize() 方法 - 核心扩容逻辑
+    // 容量为 new_capacity 的缓冲区；出错时与 std::Vec 一样 panic / handle_alloc_error
     fn resize(&mut self, new_capacity: usize) {
+        if let Err(err) = self.try_resize(new_capacity) {
+            growth::handle_reserve_error(err);
+        }
+    }
+
+    // (元素个数, 对齐) -> Layout；总字节数超过 isize::MAX 时是 CapacityOverflow
+    // 扩容 / 缩容 / 释放都用同一个 align，所以 with_alignment 的对齐在整个生命周期里不变
+    fn layout_for(capacity: usize, align: usize) -> Result<std::alloc::Layout, TryReserveError> {
+        std::alloc::Layout::array::<T>(capacity)
+            .and_then(|layout| layout.align_to(align))
+            .map_err(|_| TryReserveError::CapacityOverflow)
+    }
+
+    fn try_resize(&mut self, new_capacity: usize) -> Result<(), TryReserveError> {
         // 0. 这里的核心决策：我们使用 alloc 还是 realloc?
-        // 通常 std::Vec 会用realloc来优化，但为了教学，我们修正你的 alloc/copy 逻辑。
+        // 已经有缓冲区时用 realloc：分配器能原地扩 / 缩时连拷贝都省了，
+        // 不能时它自己 alloc + copy + dealloc，和我们手写的一样
 
-        // 1.计算新的 Layout
-        let new_layout = if new_capacity > 0 {
-            Some(std::alloc::Layout::array::<T>(new_capacity).unwrap())
-        } else {
-            None
-        };
+        // ZST 没有缓冲区可换，只需要按"强制截断"语义处理 size
+        if Self::is_zst() {
+            self.truncate(new_capacity);
+            return Ok(());
+        }
 
-        // 2. 分配新内存
-        let new_data = if let Some(layout) = new_layout {
-            unsafe {
-                let ptr = std::alloc::alloc(layout) as *mut T;
+        // 1. 先算新的 Layout：溢出时直接返回，Vector 保持原样
+        let new_layout = Self::layout_for(new_capacity, self.align)?;
+        if new_capacity == self.capacity {
+            return Ok(());
+        }
 
-                // 检查内存分配是否成功
-                if ptr.is_null(){
-                    std::alloc::handle_alloc_error(layout);
-                }
+        // 2. 缩到比元素个数还小（"强制截断"语义，与 truncate 相同）：
+        //    realloc 会把放不下的元素直接丢掉而不 drop，而先 truncate 再 realloc 的话，
+        //    realloc 失败时元素已经没了。所以先分配新缓冲区、搬走要保留的元素，
+        //    成功之后再 drop 剩下的元素、释放旧缓冲区；分配失败时 Vector 不变
+        if new_capacity < self.size && new_capacity > 0 {
+            return self.try_shrink_below_len(new_capacity, new_layout);
+        }
 
-                ptr
+        // 3. 换缓冲区
+        let new_data = if new_capacity == 0 {
+            // 3a. 缩到 0：释放不会失败，先 drop 全部元素再释放旧内存，换成 dangling
+            self.truncate(0);
+            unsafe {
+                let old_layout = Self::layout_for(self.capacity, self.align)?;
+                self.alloc.deallocate(std::ptr::NonNull::new_unchecked(self.data as *mut u8), old_layout);
+            }
+            Self::dangling(self.align)
+        } else if !self.owns_allocation() {
+            // 3b. 还没有缓冲区（容量为 0，data 是 dangling）：只能 alloc
+            match self.alloc.allocate(new_layout) {
+                Ok(ptr) => ptr.as_ptr() as *mut T,
+                Err(cause) => return Err(TryReserveError::AllocError { layout: new_layout, cause }),
             }
         } else {
-            std::ptr::null_mut()
+            // 3c. realloc：旧内容（前 min(旧, 新) 字节）由分配器搬过去
+            // 注： realloc 必须传入当初 alloc 时完全一致的 layout
+            // 失败时旧缓冲区仍然有效，Vector 不变
+            let old_layout = Self::layout_for(self.capacity, self.align)?;
+            let old_ptr = unsafe { std::ptr::NonNull::new_unchecked(self.data as *mut u8) };
+            match unsafe { self.alloc.reallocate(old_ptr, old_layout, new_layout) } {
+                Ok(ptr) => ptr.as_ptr() as *mut T,
+                Err(cause) => return Err(TryReserveError::AllocError { layout: new_layout, cause }),
+            }
         };
 
-        // 3. 决定要拷贝多少数据
-        // 如果你在缩容，你不能拷贝 self.size 为了自身健壮性你只能拷贝 min(self.size, new_capacity)
-        let copy_count = if self.size < new_capacity { self.size } else {
-            new_capacity
-        };
+        // 4. 更新结构体成员：走到这里时 size <= new_capacity，没有元素被丢弃
+        self.data = new_data;
+        self.capacity = new_capacity;
+        Ok(())
+    }
 
-        // 4. 复制旧数据
-        if !self.data.is_null() && copy_count > 0 {
-            unsafe {
-                std::ptr::copy_nonoverlapping(self.data, new_data, copy_count);
-            }
-        }
+    // try_resize 的第 2 步：0 < new_capacity < size
+    fn try_shrink_below_len(&mut self, new_capacity: usize, new_layout: std::alloc::Layout) -> Result<(), TryReserveError> {
+        // 1. 先分配：失败时还什么都没动
+        let old_layout = Self::layout_for(self.capacity, self.align)?;
+        let new_data = match self.alloc.allocate(new_layout) {
+            Ok(ptr) => ptr.as_ptr() as *mut T,
+            Err(cause) => return Err(TryReserveError::AllocError { layout: new_layout, cause }),
+        };
 
-        // 5. 释放旧内存
-        if !self.data.is_null() {
-            unsafe {
-                // 注： dealloc 必须使用当初 alloc 时完全一致的 capacity
-                let old_layout = std::alloc::Layout::array::<T>(self.capacity).unwrap();
-                std::alloc::dealloc(self.data as *mut u8, old_layout);
-            }
+        // 2. 前 new_capacity 个元素按位搬到新缓冲区，Vector 立刻切过去
+        let (old_data, old_size) = (self.data, self.size);
+        unsafe {
+            std::ptr::copy_nonoverlapping(old_data, new_data, new_capacity);
         }
-
-        // 6. 更新结构体成员
         self.data = new_data;
         self.capacity = new_capacity;
+        self.size = new_capacity;
 
-        // 如果缩容了，size 也必须被截断！
-        if self.size > new_capacity {
-            self.size = new_capacity;
+        // 3. 放不下的元素还在旧缓冲区里：drop 掉再释放
+        //    （drop 中途 panic 时旧缓冲区泄漏，但不会 double drop）
+        unsafe {
+            let tail = std::ptr::slice_from_raw_parts_mut(old_data.add(new_capacity), old_size - new_capacity);
+            std::ptr::drop_in_place(tail);
+            self.alloc.deallocate(std::ptr::NonNull::new_unchecked(old_data as *mut u8), old_layout);
         }
-
-        // 潜在问题： 如果 T 拥有堆内存 （比如 String）, 被截断丢弃的那些元素 （从 new_capacity 到 old_size） 需要被手动drop()掉，否则会内存泄漏！但这取决于 resize 设计定义上是 ”仅仅调整容量“ 还是 ”强制截断“。
-
+        Ok(())
     }
 
     // 任务12：实现 stride() 方法 - Tensor 风格访问
+    // 现在只是 stride_iter 的薄包装（见 stride.rs）：clone 出一份，参数不合法时 panic
     pub fn stride(&self, start_index: usize, stride: isize) -> Vec<T>
     where
         T: Clone, // 必须有 Clone, 因为我们要把数据复制一份带走
     {
+        match self.stride_iter(start_index, stride) {
+            Ok(iter) => iter.cloned().collect(),
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+// ==============================================================================
+// 【Phase 5: 元素语义 - 增删改都要对 drop 负责】
+// ==============================================================================
+//
+// 裸指针管理的缓冲区里，"哪些槽位是活着的元素"完全由 size 决定：
+//   - [0, size) 里的元素由 Vector 负责 drop，[size, capacity) 是未初始化内存
+//   - 移走一个元素（ptr::read）之后，它的槽位必须马上不再被当作活着的元素，
+//     否则 Drop 时会 double drop
+//   - 先改 size 再 drop 元素：元素的 Drop panic 时最多泄漏，不会 double drop
+// ==============================================================================
 
-        // 1. 检查参数 
-        // start_index 是 usize , 正整数 本身不可能 小于 0， 所以无需检查 < 0
-        if start_index >= self.size {
-            panic!("start index out of bounds!")
+impl<T, A: RawAlloc> Vector<T, A> {
+    fn is_zst() -> bool {
+        std::mem::size_of::<T>() == 0
+    }
+
+    // 非空、按 align 对齐、但不指向任何分配的指针
+    // （align 为 align_of::<T>() 时就是 NonNull::dangling()）
+    fn dangling(align: usize) -> *mut T {
+        std::ptr::without_provenance_mut(align)
+    }
+
+    // 只有非 ZST 且容量 > 0 时 data 才来自 alloc，才需要 dealloc
+    fn owns_allocation(&self) -> bool {
+        !Self::is_zst() && self.capacity > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    // 容量不足 min_capacity 时按 GrowthPolicy 扩容（默认至少翻倍，保证连续 push 是均摊 O(1)）
+    fn grow_to(&mut self, min_capacity: usize) {
+        if let Err(err) = self.try_grow_to(min_capacity) {
+            growth::handle_reserve_error(err);
+        }
+    }
+
+    fn try_grow_to(&mut self, min_capacity: usize) -> Result<(), TryReserveError> {
+        if min_capacity <= self.capacity {
+            return Ok(());
+        }
+        let new_capacity = self.policy.grow(self.capacity, min_capacity, std::mem::size_of::<T>())?;
+        self.try_resize(new_capacity)
+    }
+
+    // 任务13：实现 insert() - 在 index 处插入，后面的元素整体后移一格
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.size, "insertion index {} out of bounds (len {})", index, self.size);
+
+        // 1. 确保还有一个空位
+        self.grow_to(self.size + 1);
+
+        unsafe {
+            // 2. [index, size) 后移一格，区间重叠所以用 copy（memmove）而不是 copy_nonoverlapping
+            let p = self.data.add(index);
+            std::ptr::copy(p, p.add(1), self.size - index);
+            // 3. 写入新值（空出来的槽位是未初始化的，write 不会 drop 旧值）
+            std::ptr::write(p, value);
+        }
+        self.size += 1;
+    }
+
+    // 任务14：实现 remove() - 取出 index 处的元素，后面的元素整体前移一格
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.size, "removal index {} out of bounds (len {})", index, self.size);
+
+        unsafe {
+            let p = self.data.add(index);
+            // 1. 把元素移出来，槽位变成"已搬走"
+            let value = std::ptr::read(p);
+            // 2. 后面的元素前移一格，覆盖掉已搬走的槽位
+            std::ptr::copy(p.add(1), p, self.size - index - 1);
+            self.size -= 1;
+            value
+        }
+    }
+
+    // 任务15：实现 swap_remove() - O(1) 删除，用最后一个元素填坑（不保持顺序）
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.size, "swap_remove index {} out of bounds (len {})", index, self.size);
+
+        unsafe {
+            let last = self.data.add(self.size - 1);
+            let p = self.data.add(index);
+            let value = std::ptr::read(p);
+            // index == size - 1 时源和目标是同一个位置，copy 允许重叠
+            std::ptr::copy(last, p, 1);
+            self.size -= 1;
+            value
+        }
+    }
+
+    // 任务16：实现 truncate() - 只保留前 len 个元素，其余的 drop 掉，容量不变
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.size {
+            return;
         }
 
-        if stride == 0 {
-            panic!("stride can not be zero!")
-        } 
+        // 1. 先缩短 size：被截掉的元素的 Drop 如果 panic，Vector 不会再碰它们
+        let tail = std::ptr::slice_from_raw_parts_mut(unsafe { self.data.add(len) }, self.size - len);
+        self.size = len;
 
-        // 2. 创建result 向量
-        // 直接用标准库 Vec::new() , 如果想优化性能，可以用Vec::with_capacity(预估大小)
-        let mut result = Vec::new();
+        // 2. 一次性 drop 整个尾部
+        unsafe {
+            std::ptr::drop_in_place(tail);
+        }
+    }
 
-        // 3. 遍历逻辑
-        // usize 无符号 mix isize 有符号，那安全的做法就是把所有坐标都暂时当成isize 有符号来处理
-        let mut current_pos = start_index as isize;
-        let limit = self.size as isize;
+    // 任务17：实现 clear() - drop 所有元素，容量不变
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
 
-        loop {
-            // 检查当前位置是否越界
-            //  如果是正步长不能超过limit 
-            //  如果是负步长不能小于0
-            if current_pos < 0 || current_pos >= limit {
-                break;
+    // 任务18：实现 retain() - 只保留 f 返回 true 的元素，保持原有顺序
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.size;
+        // 处理期间 size 设为 0：f 或元素的 Drop panic 时只会泄漏，不会 double drop
+        self.size = 0;
+
+        let mut kept = 0;
+        for i in 0..len {
+            unsafe {
+                let cur = self.data.add(i);
+                if f(&*cur) {
+                    // 保留：前移到 kept 的位置（kept < i 时两者不重叠）
+                    if kept != i {
+                        std::ptr::copy_nonoverlapping(cur, self.data.add(kept), 1);
+                    }
+                    kept += 1;
+                } else {
+                    std::ptr::drop_in_place(cur);
+                }
             }
+        }
+
+        self.size = kept;
+    }
 
-            // 4. 获取元素并push
-            // 需要把isize转回usize才能去访问内存
-            let idx = current_pos as usize;
+    // 任务19：实现 extend_from_slice() - 逐个 clone 追加到末尾
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        // 1. 一次扩到位，避免循环里多次扩容
+        self.grow_to(self.size + other.len());
 
-            if let Some(val) = self.get(idx) {
-                result.push(val.clone());
+        // 2. 每写一个就 size += 1：clone panic 时已经写入的元素仍然归 Vector 管
+        for item in other {
+            unsafe {
+                std::ptr::write(self.data.add(self.size), item.clone());
             }
+            self.size += 1;
+        }
+    }
 
-            // 移动一步
-            current_pos += stride;
+    // 任务20：实现 drain() - 把 range 内的元素按顺序移出，剩下的元素前移补齐
+    //
+    // 迭代期间 size 被设为 range.start：
+    //   - 已经 yield 出去的元素归调用方，range 里剩下的由 Drain 负责 drop
+    //   - 有人 mem::forget(drain) 时，range 和尾部元素只会泄漏，不会 double drop
+    // Drain 被 drop 时把尾部 [end, old_len) 搬到 start 处，再恢复 size
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: std::ops::RangeBounds<usize>,
+    {
+        use std::ops::Bound;
+
+        let len = self.size;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("range start overflow"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("range end overflow"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end, "drain range start {} is greater than end {}", start, end);
+        assert!(end <= len, "drain range end {} out of bounds (len {})", end, len);
+
+        self.size = start;
+        Drain { vec: self, front: start, back: end, tail_start: end, tail_len: len - end }
+    }
+}
+
+// drain() 返回的迭代器：借用 Vector，生命周期结束时把尾部搬回来
+pub struct Drain<'a, T, A: RawAlloc = Global> {
+    vec: &'a mut Vector<T, A>,
+    front: usize,      // 下一个从前面 yield 的位置
+    back: usize,       // 从后面 yield 的下一个位置 + 1
+    tail_start: usize, // range 之后的元素从这里开始
+    tail_len: usize,   // range 之后的元素个数
+}
+
+impl<T, A: RawAlloc> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let value = unsafe { std::ptr::read(self.vec.data.add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl<T, A: RawAlloc> DoubleEndedIterator for Drain<'_, T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { std::ptr::read(self.vec.data.add(self.back)) })
+    }
+}
+
+impl<T, A: RawAlloc> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: RawAlloc> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        // 1. drop range 里还没被取走的元素
+        //    先把 front 推到 back：元素的 Drop panic 时不会再被 drop 第二次
+        let count = self.back - self.front;
+        let remaining = std::ptr::slice_from_raw_parts_mut(unsafe { self.vec.data.add(self.front) }, count);
+        self.front = self.back;
+
+        // 2. 尾部前移到 size（即 range.start）处，恢复 size
+        //    放在 guard 里：即使第 1 步 panic，尾部元素也会被搬回来
+        struct MoveTail<'r, 'a, T, A: RawAlloc>(&'r mut Drain<'a, T, A>);
+
+        impl<T, A: RawAlloc> Drop for MoveTail<'_, '_, T, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = drain.vec.size;
+                if drain.tail_len > 0 {
+                    unsafe {
+                        let src = drain.vec.data.add(drain.tail_start);
+                        let dst = drain.vec.data.add(start);
+                        std::ptr::copy(src, dst, drain.tail_len);
+                    }
+                }
+                drain.vec.size = start + drain.tail_len;
+            }
+        }
+
+        let _guard = MoveTail(self);
+        if count > 0 {
+            unsafe {
+                std::ptr::drop_in_place(remaining);
+            }
+        }
+    }
+}
+
+// ==============================================================================
+// 【Phase 6: 与切片互通 - Vector 能用的地方，&[T] 都能用】
+// ==============================================================================
+//
+// Deref<Target = [T]> 之后，切片的方法（iter / len / sort / chunks / contains ...）
+// 和 v[1..3] 这样的范围下标都直接可用，也可以传给任何接收 &[T] 的函数。
+// 剩下的 trait 只是把常见的构造 / 遍历 / 比较方式补齐
+// ==============================================================================
+
+impl<T, A: RawAlloc> Vector<T, A> {
+    pub fn as_ptr(&self) -> *const T {
+        self.data
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // data 永远非空且对齐，[0, size) 都是已初始化的元素
+        unsafe { std::slice::from_raw_parts(self.data, self.size) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.size) }
+    }
+}
+
+impl<T, A: RawAlloc> std::ops::Deref for Vector<T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: RawAlloc> std::ops::DerefMut for Vector<T, A> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, A: RawAlloc> AsRef<[T]> for Vector<T, A> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, A: RawAlloc> AsMut<[T]> for Vector<T, A> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+// 克隆出来的 Vector 用同一个分配器的副本（&PoolAlloc 的副本还是同一个池）
+impl<T: Clone, A: RawAlloc + Clone> Clone for Vector<T, A> {
+    fn clone(&self) -> Self {
+        let mut cloned = Vector::with_alignment_in(self.size, self.align, self.alloc.clone());
+        cloned.policy = self.policy;
+        cloned.extend_from_slice(self);
+        cloned
+    }
+}
+
+impl<T: std::fmt::Debug, A: RawAlloc> std::fmt::Debug for Vector<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+// 比较的是元素，不比较容量
+impl<T: PartialEq<U>, U, A: RawAlloc, B: RawAlloc> PartialEq<Vector<U, B>> for Vector<T, A> {
+    fn eq(&self, other: &Vector<U, B>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, A: RawAlloc> Eq for Vector<T, A> {}
+
+impl<T: PartialEq<U>, U, A: RawAlloc> PartialEq<[U]> for Vector<T, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: RawAlloc> PartialEq<&[U]> for Vector<T, A> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: RawAlloc, const N: usize> PartialEq<[U; N]> for Vector<T, A> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: RawAlloc> PartialEq<Vec<U>> for Vector<T, A> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+// 直接接管 Vec 的元素：逐个按位搬过来，再让 Vec 以为自己是空的（只释放缓冲区）
+impl<T> From<Vec<T>> for Vector<T> {
+    fn from(mut vec: Vec<T>) -> Self {
+        let mut vector = Vector::with_capacity(vec.len());
+        unsafe {
+            std::ptr::copy_nonoverlapping(vec.as_ptr(), vector.data, vec.len());
+            vector.size = vec.len();
+            vec.set_len(0);
+        }
+        vector
+    }
+}
+
+impl<T: Clone> From<&[T]> for Vector<T> {
+    fn from(slice: &[T]) -> Self {
+        let mut vector = Vector::with_capacity(slice.len());
+        vector.extend_from_slice(slice);
+        vector
+    }
+}
+
+impl<T, A: RawAlloc> Extend<T> for Vector<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // 按 size_hint 的下界预留一次，剩下的交给 push 的翻倍扩容
+        self.grow_to(self.size.saturating_add(iter.size_hint().0));
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: RawAlloc> Extend<&'a T> for Vector<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for Vector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vector = Vector::with_capacity(0);
+        vector.extend(iter);
+        vector
+    }
+}
+
+impl<'a, T, A: RawAlloc> IntoIterator for &'a Vector<T, A> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, T, A: RawAlloc> IntoIterator for &'a mut Vector<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+impl<T, A: RawAlloc> IntoIterator for Vector<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(mut self) -> IntoIter<T, A> {
+        // 元素的所有权交给 IntoIter：size 清零后 Vector 的 Drop 只负责释放缓冲区
+        let back = self.size;
+        self.size = 0;
+        IntoIter { vec: self, front: 0, back }
+    }
+}
+
+// 按值遍历：[front, back) 是还没有交出去的元素
+pub struct IntoIter<T, A: RawAlloc = Global> {
+    vec: Vector<T, A>, // size 恒为 0，只用来在最后释放缓冲区
+    front: usize,
+    back: usize,
+}
+
+impl<T, A: RawAlloc> IntoIter<T, A> {
+    // 还没有交出去的元素
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.vec.data.add(self.front), self.back - self.front) }
+    }
+}
+
+impl<T, A: RawAlloc> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let value = unsafe { std::ptr::read(self.vec.data.add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl<T, A: RawAlloc> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { std::ptr::read(self.vec.data.add(self.back)) })
+    }
+}
+
+impl<T, A: RawAlloc> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T: std::fmt::Debug, A: RawAlloc> std::fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: RawAlloc> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // drop 还没交出去的元素；缓冲区随后由 self.vec 的 Drop 释放
+        let remaining = std::ptr::slice_from_raw_parts_mut(unsafe { self.vec.data.add(self.front) }, self.back - self.front);
+        self.front = self.back;
+        unsafe {
+            std::ptr::drop_in_place(remaining);
         }
-        
-        return result;
-        
     }
 }
 
@@ -405,7 +988,7 @@ impl<T> Vector<T> {
 //
 // 2. 性能特征：
 //    - 连续内存：SIMD 和缓存友好
-//    - 2x 扩容：平衡内存使用和拷贝开销
+//    - 2x 扩容：平衡内存使用和拷贝开销（GrowthPolicy 可以换成 1.5x / 精确 / 按页取整）
 //    - 手动管理：精确控制分配/释放时机
 //
 // 3. Rust 优势：
@@ -414,46 +997,411 @@ impl<T> Vector<T> {
 //    - RAII：异常安全，资源不会泄漏
 // ==============================================================================
 
-// ==============================================================================
-// 【Phase 3: 语法教学 - 分步骤实现和测试】
-// ==============================================================================
+#[cfg(test)]
+mod tests {
+    use super::Vector;
+    use crate::test_util::{counters, DropCounter};
+    use crate::PoolAlloc;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn ids(v: &Vector<DropCounter>) -> Vec<usize> {
+        (0..v.len()).map(|i| v[i].id).collect()
+    }
+
+    fn values(v: &Vector<i32>) -> Vec<i32> {
+        (0..v.len()).map(|i| v[i]).collect()
+    }
 
-fn main() {
-    println!("=== Rust Vector 教学测试 ===\n");
-
-    // 【第一步：测试基本创建】
-    // 任务：创建 Vector 实例并检查初始状态
-    // 提示：Vector<i32> v = Vector::new();
-    // println!("[Step 1] Vector 创建完成, size={}, capacity={}", v.size(), v.capacity());
-    let mut v = Vector::<i32>::new();
-    println!("[Step 1] Vector 创建完成, size={}, capacity={}", v.size(), v.capacity());
-
-    // 【第二步：测试 push 和扩容】
-    // 任务：添加元素并观察扩容
-    // 提示：for i in 1..=8 { v.push(i); }
-    // println!("[Step 2] 扩容后 size={}, capacity={}", v.size(), v.capacity());
-    for i in 1..=8{
-        v.push(i);
-    }
-    println!("[Step 2] 扩容后 size={}, capacity={}", v.size(), v.capacity());
-
-    // 【第三步：测试索引访问】
-    // 任务：使用 [] 语法访问元素
-    // 提示：println!("[Step 3] 第一个元素: {}", v[0]);
-    println!("[Step 3] 第三个元素: {}", v[2]);
-
-    // 【第四步：测试 stride】
-    // 任务：实现步长访问
-    // 提示：let result = v.stride(1, 2);
-    // println!("[Step 4] stride 结果: {:?}", result);
-    let result = v.stride(v.size - 1, -2);
-    println!("[Step 4] stride 结果: {:?}", result);
-
-    // 【第五步：测试 pop】
-    // 任务：弹出元素并观察缩容
-    // 提示：while let Some(val) = v.pop() { print!("{} ", val); }
-    while let Some(val) = v.pop() {
-        print!("{}", val);
-    }
-    println!("运行: rustc vector.rs && ./vector");
-}
\ No newline at end of file
+    #[test]
+    fn test_drop_drops_every_element() {
+        let drops = Rc::new(Cell::new(0));
+        let v: Vector<DropCounter> = counters(10, &drops);
+        assert_eq!(drops.get(), 0);
+        drop(v);
+        assert_eq!(drops.get(), 10);
+
+        // 元素自己拥有堆内存（String）：和计数器放在同一个元组里，
+        // 计数器被 drop 说明 String 也被 drop 了，没有泄漏
+        let drops = Rc::new(Cell::new(0));
+        let mut s = Vector::new();
+        for id in 0..20 {
+            s.push((format!("token-{}", id), DropCounter { id, drops: Rc::clone(&drops) }));
+        }
+        assert_eq!(s[19].0, "token-19");
+        drop(s);
+        assert_eq!(drops.get(), 20);
+    }
+
+    #[test]
+    fn test_pop_and_shrink_do_not_double_drop() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(32, &drops);
+        // 弹出 30 个：中途会缩容好几次，元素被搬到新缓冲区，不能被 drop
+        for expected in (2..32).rev() {
+            let item = v.pop().unwrap();
+            assert_eq!(item.id, expected);
+        }
+        assert_eq!(drops.get(), 30);
+        assert!(v.capacity() < 32);
+        drop(v);
+        assert_eq!(drops.get(), 32);
+    }
+
+    #[test]
+    fn test_resize_below_len_drops_truncated_elements() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(8, &drops);
+        v.resize(3);
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.capacity(), 3);
+        assert_eq!(drops.get(), 5);
+        assert_eq!(ids(&v), vec![0, 1, 2]);
+        drop(v);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn test_failed_resize_below_len_keeps_elements() {
+        // 池里只放得下原来的 8 个元素：缩到 3 需要先分配新缓冲区，分配失败时
+        // 一个元素都不能少，也不能被 drop
+        let drops = Rc::new(Cell::new(0));
+        let pool = PoolAlloc::new(8 * std::mem::size_of::<DropCounter>());
+        let mut v = Vector::with_capacity_in(8, &pool);
+        for id in 0..8 {
+            v.push(DropCounter { id, drops: Rc::clone(&drops) });
+        }
+        assert!(v.try_resize(3).is_err());
+        assert_eq!(v.len(), 8);
+        assert_eq!(v.capacity(), 8);
+        assert_eq!(drops.get(), 0);
+        assert_eq!((0..8).map(|i| v[i].id).collect::<Vec<_>>(), (0..8).collect::<Vec<_>>());
+
+        // 池够大时照常截断，旧缓冲区还给池
+        let big = PoolAlloc::new(1 << 10);
+        let mut v = Vector::with_capacity_in(8, &big);
+        for id in 0..8 {
+            v.push(DropCounter { id, drops: Rc::clone(&drops) });
+        }
+        v.try_resize(3).unwrap();
+        assert_eq!(drops.get(), 5);
+        assert_eq!(big.in_use(), 3 * std::mem::size_of::<DropCounter>());
+        drop(v);
+        assert_eq!(drops.get(), 8);
+        assert_eq!(big.in_use(), 0);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut v = Vector::with_capacity(2);
+        v.insert(0, 2);
+        v.insert(0, 0);
+        v.insert(1, 1);
+        v.insert(3, 4); // 末尾插入，触发扩容
+        v.insert(3, 3);
+        assert_eq!(values(&v), vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(v.remove(0), 0);
+        assert_eq!(v.remove(3), 4);
+        assert_eq!(v.remove(1), 2);
+        assert_eq!(values(&v), vec![1, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index")]
+    fn test_insert_out_of_bounds() {
+        let mut v = Vector::new();
+        v.push(1);
+        v.insert(2, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "removal index")]
+    fn test_remove_out_of_bounds() {
+        let mut v: Vector<i32> = Vector::new();
+        v.remove(0);
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(5, &drops);
+        assert_eq!(v.swap_remove(1).id, 1);
+        assert_eq!(ids(&v), vec![0, 4, 2, 3]);
+        assert_eq!(v.swap_remove(3).id, 3); // 删最后一个
+        assert_eq!(ids(&v), vec![0, 4, 2]);
+        assert_eq!(drops.get(), 2);
+        drop(v);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(10, &drops);
+        let capacity = v.capacity();
+
+        v.truncate(20); // 比 len 大：什么都不做
+        assert_eq!(v.len(), 10);
+        v.truncate(6);
+        assert_eq!(drops.get(), 4);
+        assert_eq!(ids(&v), vec![0, 1, 2, 3, 4, 5]);
+
+        v.clear();
+        assert!(v.is_empty());
+        assert_eq!(drops.get(), 10);
+        assert_eq!(v.capacity(), capacity);
+        drop(v);
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_retain() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(10, &drops);
+        v.retain(|item| item.id % 3 == 0);
+        assert_eq!(ids(&v), vec![0, 3, 6, 9]);
+        assert_eq!(drops.get(), 6);
+
+        v.retain(|_| true);
+        assert_eq!(ids(&v), vec![0, 3, 6, 9]);
+        v.retain(|_| false);
+        assert!(v.is_empty());
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(2, &drops);
+        let extra: Vec<DropCounter> = (2..7).map(|id| DropCounter { id, drops: Rc::clone(&drops) }).collect();
+        v.extend_from_slice(&extra);
+        assert_eq!(ids(&v), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert!(v.capacity() >= 7);
+
+        drop(extra);
+        assert_eq!(drops.get(), 5);
+        drop(v);
+        assert_eq!(drops.get(), 12);
+    }
+
+    #[test]
+    fn test_drain_middle() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(8, &drops);
+        let drained: Vec<usize> = v.drain(2..5).map(|item| item.id).collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(ids(&v), vec![0, 1, 5, 6, 7]);
+        assert_eq!(drops.get(), 3);
+        drop(v);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn test_drain_partially_consumed() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(8, &drops);
+        {
+            let mut drain = v.drain(1..=6);
+            assert_eq!(drain.len(), 6);
+            assert_eq!(drain.next().map(|item| item.id), Some(1));
+            assert_eq!(drain.next_back().map(|item| item.id), Some(6));
+            assert_eq!(drain.len(), 4);
+            // 剩下的 2..=5 由 Drain 的 Drop 负责
+        }
+        assert_eq!(drops.get(), 6);
+        assert_eq!(ids(&v), vec![0, 7]);
+    }
+
+    #[test]
+    fn test_drain_full_and_empty_ranges() {
+        let mut v = Vector::new();
+        for i in 0..5 {
+            v.push(i);
+        }
+        assert_eq!(v.drain(2..2).count(), 0);
+        assert_eq!(values(&v), vec![0, 1, 2, 3, 4]);
+        assert_eq!(v.drain(..).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert!(v.is_empty());
+
+        // 零容量的 Vector：data 是 dangling 指针，drain 不能读写它
+        let mut empty: Vector<String> = Vector::with_capacity(0);
+        assert_eq!(empty.drain(..).count(), 0);
+    }
+
+    #[test]
+    fn test_forgotten_drain_leaks_instead_of_double_drop() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v: Vector<DropCounter> = counters(6, &drops);
+        std::mem::forget(v.drain(2..4));
+        // size 停在 range.start：后面的元素只是泄漏，不会被 drop 两次
+        assert_eq!(ids(&v), vec![0, 1]);
+        drop(v);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_drain_out_of_bounds() {
+        let mut v: Vector<i32> = Vector::new();
+        v.push(1);
+        v.drain(0..2);
+    }
+
+    #[test]
+    fn test_deref_to_slice() {
+        fn sum(xs: &[i32]) -> i32 {
+            xs.iter().sum()
+        }
+
+        let mut v: Vector<i32> = (1..=5).collect();
+        assert_eq!(sum(&v), 15);
+        assert_eq!(&v[1..3], &[2, 3]);
+        assert_eq!(v.first(), Some(&1));
+        assert!(v.contains(&4));
+        assert_eq!(v.as_slice().len(), 5);
+        assert_eq!(v.as_ptr(), v.as_slice().as_ptr());
+
+        v.reverse();
+        v[..2].copy_from_slice(&[50, 40]);
+        assert_eq!(v, [50, 40, 3, 2, 1]);
+        v.sort_unstable();
+        assert_eq!(v, vec![1, 2, 3, 40, 50]);
+    }
+
+    #[test]
+    fn test_borrowed_iteration() {
+        let mut v: Vector<i32> = (0..4).collect();
+        for x in &mut v {
+            *x *= 10;
+        }
+        let mut seen = Vec::new();
+        for x in &v {
+            seen.push(*x);
+        }
+        assert_eq!(seen, vec![0, 10, 20, 30]);
+    }
+
+    #[test]
+    fn test_owned_into_iter() {
+        let drops = Rc::new(Cell::new(0));
+        let v: Vector<DropCounter> = counters(6, &drops);
+        let mut iter = v.into_iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next().map(|item| item.id), Some(0));
+        assert_eq!(iter.next_back().map(|item| item.id), Some(5));
+        assert_eq!(iter.as_slice().iter().map(|item| item.id).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(drops.get(), 2);
+        // 剩下的 4 个由 IntoIter 的 Drop 负责
+        drop(iter);
+        assert_eq!(drops.get(), 6);
+
+        let words: Vector<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let joined: String = words.into_iter().rev().collect();
+        assert_eq!(joined, "cba");
+    }
+
+    #[test]
+    fn test_from_iterator_and_extend() {
+        let mut v: Vector<usize> = (0..3).collect();
+        v.extend(3..6);
+        v.extend(&[6, 7]);
+        v.extend(std::iter::empty::<usize>());
+        assert_eq!(v, (0..8).collect::<Vec<_>>());
+
+        // 不知道长度的迭代器（filter 的 size_hint 下界是 0）
+        let evens: Vector<usize> = (0..100).filter(|x| x % 2 == 0).collect();
+        assert_eq!(evens.len(), 50);
+        assert_eq!(evens[49], 98);
+    }
+
+    #[test]
+    fn test_clone_debug_eq_default() {
+        let drops = Rc::new(Cell::new(0));
+        let v: Vector<DropCounter> = counters(3, &drops);
+        let cloned = v.clone();
+        assert_eq!(ids(&cloned), vec![0, 1, 2]);
+        drop(v);
+        drop(cloned);
+        assert_eq!(drops.get(), 6);
+
+        let a: Vector<i32> = Vector::from(vec![1, 2, 3]);
+        let mut b = Vector::with_capacity(100);
+        b.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(a, b); // 容量不同也相等
+        assert_ne!(a, Vector::from(vec![1, 2]));
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+        assert_eq!(a, &[1, 2, 3][..]);
+
+        let empty: Vector<i32> = Vector::default();
+        assert!(empty.is_empty());
+        assert_eq!(format!("{:?}", empty), "[]");
+    }
+
+    #[test]
+    fn test_from_vec_moves_elements() {
+        let drops = Rc::new(Cell::new(0));
+        let source: Vec<DropCounter> = (0..4).map(|id| DropCounter { id, drops: Rc::clone(&drops) }).collect();
+        let v = Vector::from(source);
+        assert_eq!(drops.get(), 0);
+        assert_eq!(ids(&v), vec![0, 1, 2, 3]);
+        drop(v);
+        assert_eq!(drops.get(), 4);
+
+        let from_slice: Vector<String> = Vector::from(&["x".to_string(), "y".to_string()][..]);
+        assert_eq!(from_slice, ["x", "y"]);
+    }
+
+    #[test]
+    fn test_zero_capacity_push() {
+        // 以前 with_capacity(0) + push 会 resize(0)，然后写 null 指针
+        let mut v = Vector::with_capacity(0);
+        assert_eq!(v.capacity(), 0);
+        assert!(v.as_slice().is_empty());
+        v.push("kv".to_string());
+        assert!(v.capacity() >= 1);
+        v.push("cache".to_string());
+        assert_eq!(v, ["kv", "cache"]);
+
+        let mut empty: Vector<u64> = Vector::with_capacity(0);
+        assert_eq!(empty.pop(), None);
+        empty.clear();
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_zero_sized_types() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Marker;
+
+        let mut v = Vector::new();
+        for _ in 0..1000 {
+            v.push(Marker);
+        }
+        assert_eq!(v.len(), 1000);
+        assert_eq!(v.capacity(), usize::MAX);
+        v.insert(500, Marker);
+        assert_eq!(v.remove(0), Marker);
+        assert_eq!(v.drain(10..20).count(), 10);
+        v.truncate(100);
+        assert_eq!(v.iter().count(), 100);
+        assert_eq!(v.clone().into_iter().count(), 100);
+        while v.pop().is_some() {}
+        assert!(v.is_empty());
+
+        // 带 Drop 的 ZST：计数器只能放在 static 里
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static ZST_DROPS: AtomicUsize = AtomicUsize::new(0);
+        struct ZstDrop;
+        impl Drop for ZstDrop {
+            fn drop(&mut self) {
+                ZST_DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let mut holders: Vector<ZstDrop> = (0..5).map(|_| ZstDrop).collect();
+        holders.truncate(3);
+        assert_eq!(ZST_DROPS.load(Ordering::Relaxed), 2);
+        drop(holders);
+        assert_eq!(ZST_DROPS.load(Ordering::Relaxed), 5);
+    }
+}